@@ -0,0 +1,86 @@
+use std::path::Path;
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use crate::format::{iso_date, parse_date};
+use crate::import::{self, ImportError, PriceFile};
+
+/// What the portfolio is compared to. Files from before imported indices
+/// stored a plain security id, which still reads as `Security`.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum Benchmark {
+    Security(u8),
+    Index(String), // name of an imported index series
+}
+
+/// Prices of an index that isn't held, e.g. imported from a CSV download.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct IndexSeries {
+    pub name: String,
+    points: Vec<(String, f32)>, // ISO date and level, sorted by date
+}
+
+impl IndexSeries {
+    pub fn get_points(&self) -> Vec<(NaiveDate, f64)> {
+        self.points
+            .iter()
+            .filter_map(|(date, level)| parse_date(date).map(|date| (date, *level as f64)))
+            .collect()
+    }
+}
+
+/// Reads index prices like the price history import. A file with one column
+/// per index gives one series each, otherwise the series is named after the file.
+pub fn import_csv(path: &Path, date_format: &str) -> Result<Vec<IndexSeries>, ImportError> {
+    let series = |name: String, points: Vec<(NaiveDate, f32)>| IndexSeries {
+        name,
+        points: points
+            .into_iter()
+            .map(|(date, level)| (iso_date(date), level))
+            .collect(),
+    };
+    match import::read_prices(path, date_format)? {
+        PriceFile::Single(points) => {
+            let name = path
+                .file_stem()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .to_string();
+            Ok(vec![series(name, points)])
+        }
+        PriceFile::PerSymbol(columns) => Ok(columns
+            .into_iter()
+            .map(|(name, points)| series(name, points))
+            .collect()),
+    }
+}
+
+/// Last level at the end of the given day.
+pub fn level_on(index: &[(NaiveDate, f64)], day: NaiveDate) -> Option<f64> {
+    index
+        .iter()
+        .take_while(|(index_day, _)| *index_day <= day)
+        .last()
+        .map(|(_, level)| *level)
+        .filter(|level| *level > 0.0)
+}
+
+/// Return of the index from the end of `start` to the end of `end`.
+pub fn period_return(index: &[(NaiveDate, f64)], start: NaiveDate, end: NaiveDate) -> Option<f64> {
+    Some(level_on(index, end)? / level_on(index, start)? - 1.0)
+}
+
+// Entry of the benchmark pick list
+#[derive(Debug, Clone, PartialEq)]
+pub struct BenchmarkOption {
+    pub benchmark: Benchmark,
+    pub label: String,
+}
+
+impl std::fmt::Display for BenchmarkOption {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.label)
+    }
+}
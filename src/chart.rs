@@ -0,0 +1,542 @@
+use chrono::{Datelike, NaiveDate};
+use iced::mouse;
+use iced::widget::canvas::{self, Event, Frame, Geometry, Path, Stroke, Text, path::Arc};
+use iced::{Color, Point, Radians, Rectangle, Renderer, Size, Theme};
+
+use crate::format::NumberFormat;
+use crate::i18n::{self, Language};
+use crate::security::Action;
+
+// Room for the axis labels around the plot
+const MARGIN_LEFT: f32 = 80.0;
+const MARGIN_RIGHT: f32 = 10.0;
+const MARGIN_TOP: f32 = 10.0;
+const MARGIN_BOTTOM: f32 = 24.0;
+const LABEL_SIZE: f32 = 12.0;
+const GRID_LINES: usize = 4;
+const MIN_VISIBLE_DAYS: f64 = 7.0;
+const ZOOM_STEP: f64 = 0.8;
+
+// Slices cycle through these, there are rarely more than a handful
+const SLICE_COLORS: [Color; 8] = [
+    Color::from_rgb(0.26, 0.52, 0.96),
+    Color::from_rgb(0.96, 0.60, 0.16),
+    Color::from_rgb(0.30, 0.69, 0.31),
+    Color::from_rgb(0.91, 0.30, 0.24),
+    Color::from_rgb(0.61, 0.35, 0.71),
+    Color::from_rgb(0.10, 0.74, 0.74),
+    Color::from_rgb(0.91, 0.45, 0.66),
+    Color::from_rgb(0.55, 0.55, 0.55),
+];
+
+// How amounts and dates are written in labels and tooltips
+#[derive(Debug, Clone)]
+pub struct Labels {
+    pub number_format: NumberFormat,
+    pub currency: String,
+    pub date_format: String,
+    pub language: Language,
+}
+
+impl Labels {
+    // Indices have no currency, their values are plain numbers
+    fn money(&self, value: f32) -> String {
+        if self.currency.is_empty() {
+            self.number_format.number(value, 2)
+        } else {
+            self.number_format.money(value, &self.currency)
+        }
+    }
+
+    fn date(&self, date: NaiveDate) -> String {
+        date.format(&self.date_format).to_string()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Series {
+    pub label: String,
+    pub color: Color,
+    pub points: Vec<(NaiveDate, f32)>, // sorted by date
+}
+
+/// Trades drawn as dots on a price line, buys in green and sales in red.
+#[derive(Debug, Clone)]
+pub struct Marker {
+    pub date: NaiveDate,
+    pub value: f32,
+    pub action: Action,
+}
+
+/// Lines over time. The mouse wheel zooms into the dates under the cursor,
+/// dragging moves the visible range and a right click shows everything again.
+#[derive(Debug, Clone)]
+pub struct LineChart {
+    pub series: Vec<Series>,
+    pub markers: Vec<Marker>,
+    pub labels: Labels,
+}
+
+#[derive(Debug, Default)]
+pub struct LineChartState {
+    visible: Option<(f64, f64)>,     // in days, None shows all dates
+    drag: Option<(f32, (f64, f64))>, // cursor x and visible range when the drag started
+}
+
+fn day_number(date: NaiveDate) -> f64 {
+    date.num_days_from_ce() as f64
+}
+
+fn from_day_number(day: f64) -> NaiveDate {
+    NaiveDate::from_num_days_from_ce_opt(day.round() as i32).unwrap_or_default()
+}
+
+fn plot_area(bounds: Rectangle) -> Rectangle {
+    Rectangle {
+        x: MARGIN_LEFT,
+        y: MARGIN_TOP,
+        width: (bounds.width - MARGIN_LEFT - MARGIN_RIGHT).max(1.0),
+        height: (bounds.height - MARGIN_TOP - MARGIN_BOTTOM).max(1.0),
+    }
+}
+
+impl LineChart {
+    fn full_range(&self) -> Option<(f64, f64)> {
+        let days = self
+            .series
+            .iter()
+            .flat_map(|series| series.points.iter())
+            .map(|(date, _)| day_number(*date));
+        let (first, last) = days.fold(None, |range: Option<(f64, f64)>, day| match range {
+            Some((first, last)) => Some((first.min(day), last.max(day))),
+            None => Some((day, day)),
+        })?;
+        // A single day still needs some width
+        Some(if last - first < 1.0 {
+            (first - 1.0, last + 1.0)
+        } else {
+            (first, last)
+        })
+    }
+
+    fn visible_range(&self, state: &LineChartState) -> Option<(f64, f64)> {
+        state.visible.or_else(|| self.full_range())
+    }
+
+    // Keeps a zoomed or dragged range inside the dates there are
+    fn clamp(&self, (start, end): (f64, f64)) -> Option<(f64, f64)> {
+        let (first, last) = self.full_range()?;
+        let width = (end - start).max(MIN_VISIBLE_DAYS);
+        if width >= last - first {
+            return None;
+        }
+        let start = start.clamp(first, last - width);
+        Some((start, start + width))
+    }
+
+    // The value of a series at the end of a day is its last point up to then
+    fn value_on(series: &Series, day: f64) -> Option<f32> {
+        series
+            .points
+            .iter()
+            .take_while(|(date, _)| day_number(*date) <= day)
+            .last()
+            .map(|(_, value)| *value)
+    }
+}
+
+impl<Message> canvas::Program<Message> for LineChart {
+    type State = LineChartState;
+
+    fn update(
+        &self,
+        state: &mut Self::State,
+        event: &Event,
+        bounds: Rectangle,
+        cursor: mouse::Cursor,
+    ) -> Option<canvas::Action<Message>> {
+        let plot = plot_area(bounds);
+        match event {
+            Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left)) => {
+                state.drag.take().map(|_| canvas::Action::request_redraw())
+            }
+            Event::Mouse(mouse::Event::CursorMoved { .. }) => {
+                if let Some((start_x, (start, end))) = state.drag
+                    && let Some(position) = cursor.position_in(bounds)
+                {
+                    let shift = (start_x - position.x) as f64 / plot.width as f64 * (end - start);
+                    state.visible = self.clamp((start + shift, end + shift));
+                }
+                cursor.is_over(bounds).then(canvas::Action::request_redraw)
+            }
+            Event::Mouse(mouse::Event::WheelScrolled { delta }) => {
+                let position = cursor.position_in(bounds)?;
+                let (start, end) = self.visible_range(state)?;
+                let lines = match delta {
+                    mouse::ScrollDelta::Lines { y, .. } => *y,
+                    mouse::ScrollDelta::Pixels { y, .. } => *y / 50.0,
+                } as f64;
+                // The date under the cursor stays where it is
+                let ratio = ((position.x - plot.x) / plot.width).clamp(0.0, 1.0) as f64;
+                let anchor = start + ratio * (end - start);
+                let width = (end - start) * ZOOM_STEP.powf(lines);
+                state.visible =
+                    self.clamp((anchor - ratio * width, anchor + (1.0 - ratio) * width));
+                Some(canvas::Action::request_redraw().and_capture())
+            }
+            Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)) => {
+                let position = cursor.position_in(bounds)?;
+                state.drag = Some((position.x, self.visible_range(state)?));
+                Some(canvas::Action::capture())
+            }
+            Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Right)) => {
+                cursor.position_in(bounds)?;
+                state.visible = None;
+                Some(canvas::Action::request_redraw().and_capture())
+            }
+            _ => None,
+        }
+    }
+
+    fn draw(
+        &self,
+        state: &Self::State,
+        renderer: &Renderer,
+        theme: &Theme,
+        bounds: Rectangle,
+        cursor: mouse::Cursor,
+    ) -> Vec<Geometry> {
+        let mut frame = Frame::new(renderer, bounds.size());
+        let palette = theme.palette();
+        let plot = plot_area(bounds);
+        let Some((start, end)) = self.visible_range(state) else {
+            return vec![frame.into_geometry()];
+        };
+
+        // Values of the visible dates decide the vertical scale
+        let visible_values: Vec<f32> = self
+            .series
+            .iter()
+            .flat_map(|series| series.points.iter())
+            .filter(|(date, _)| (start..=end).contains(&day_number(*date)))
+            .map(|(_, value)| *value)
+            .chain(self.markers.iter().map(|marker| marker.value))
+            .collect();
+        let low = visible_values.iter().copied().fold(f32::INFINITY, f32::min);
+        let high = visible_values
+            .iter()
+            .copied()
+            .fold(f32::NEG_INFINITY, f32::max);
+        let (low, high) = if !low.is_finite() {
+            (0.0, 1.0)
+        } else if high <= low {
+            (low - 1.0, high + 1.0)
+        } else {
+            let padding = (high - low) * 0.05;
+            (low - padding, high + padding)
+        };
+
+        let x = |day: f64| plot.x + ((day - start) / (end - start)) as f32 * plot.width;
+        let y = |value: f32| plot.y + plot.height - (value - low) / (high - low) * plot.height;
+        let faint = Color {
+            a: 0.2,
+            ..palette.text
+        };
+
+        for line in 0..=GRID_LINES {
+            let value = low + (high - low) * line as f32 / GRID_LINES as f32;
+            frame.stroke(
+                &Path::line(
+                    Point::new(plot.x, y(value)),
+                    Point::new(plot.x + plot.width, y(value)),
+                ),
+                Stroke::default().with_color(faint).with_width(1.0),
+            );
+            frame.fill_text(Text {
+                content: self.labels.number_format.number(value, 0),
+                position: Point::new(plot.x - 6.0, y(value) - LABEL_SIZE / 2.0),
+                color: palette.text,
+                size: LABEL_SIZE.into(),
+                align_x: iced::alignment::Horizontal::Right.into(),
+                ..Text::default()
+            });
+        }
+        for (day, align) in [
+            (start, iced::alignment::Horizontal::Left),
+            (end, iced::alignment::Horizontal::Right),
+        ] {
+            frame.fill_text(Text {
+                content: self.labels.date(from_day_number(day)),
+                position: Point::new(x(day), plot.y + plot.height + 6.0),
+                color: palette.text,
+                size: LABEL_SIZE.into(),
+                align_x: align.into(),
+                ..Text::default()
+            });
+        }
+
+        frame.with_clip(plot, |frame| {
+            for series in self.series.iter() {
+                let line = Path::new(|builder| {
+                    for (i, (date, value)) in series.points.iter().enumerate() {
+                        let point = Point::new(x(day_number(*date)), y(*value));
+                        if i == 0 {
+                            builder.move_to(point);
+                        } else {
+                            builder.line_to(point);
+                        }
+                    }
+                });
+                frame.stroke(
+                    &line,
+                    Stroke::default().with_color(series.color).with_width(2.0),
+                );
+            }
+            for marker in self.markers.iter() {
+                let color = match marker.action {
+                    Action::Buy => palette.success,
+                    Action::Sell => palette.danger,
+                    Action::Dividend | Action::Interest | Action::Distribution => palette.primary,
+                };
+                frame.fill(
+                    &Path::circle(Point::new(x(day_number(marker.date)), y(marker.value)), 4.0),
+                    color,
+                );
+            }
+        });
+
+        // Tooltip with every series on the day under the cursor
+        if state.drag.is_none()
+            && let Some(position) = cursor.position_in(bounds)
+            && plot.contains(position)
+        {
+            let day = (start + ((position.x - plot.x) / plot.width) as f64 * (end - start)).round();
+            frame.stroke(
+                &Path::line(
+                    Point::new(x(day), plot.y),
+                    Point::new(x(day), plot.y + plot.height),
+                ),
+                Stroke::default().with_color(faint).with_width(1.0),
+            );
+            let mut lines = vec![(self.labels.date(from_day_number(day)), palette.text)];
+            for series in self.series.iter() {
+                if let Some(value) = Self::value_on(series, day) {
+                    lines.push((
+                        format!("{}: {}", series.label, self.labels.money(value)),
+                        series.color,
+                    ));
+                }
+            }
+            for marker in self.markers.iter() {
+                if day_number(marker.date) == day {
+                    lines.push((
+                        format!(
+                            "{} {}",
+                            i18n::tr(self.labels.language, marker.action.label()),
+                            self.labels.money(marker.value)
+                        ),
+                        palette.text,
+                    ));
+                }
+            }
+            draw_tooltip(
+                &mut frame,
+                position,
+                bounds.size(),
+                &lines,
+                palette.background,
+            );
+        }
+
+        vec![frame.into_geometry()]
+    }
+
+    fn mouse_interaction(
+        &self,
+        state: &Self::State,
+        bounds: Rectangle,
+        cursor: mouse::Cursor,
+    ) -> mouse::Interaction {
+        if state.drag.is_some() {
+            mouse::Interaction::Grabbing
+        } else if cursor.is_over(bounds) {
+            mouse::Interaction::Crosshair
+        } else {
+            mouse::Interaction::default()
+        }
+    }
+}
+
+// Box next to the cursor, flipped to the other side near the edges
+fn draw_tooltip(
+    frame: &mut Frame,
+    position: Point,
+    size: Size,
+    lines: &[(String, Color)],
+    background: Color,
+) {
+    let line_height = LABEL_SIZE + 4.0;
+    let width = lines
+        .iter()
+        .map(|(line, _)| line.chars().count() as f32 * LABEL_SIZE * 0.6)
+        .fold(0.0, f32::max)
+        + 12.0;
+    let height = lines.len() as f32 * line_height + 8.0;
+    let mut corner = Point::new(position.x + 12.0, position.y + 12.0);
+    if corner.x + width > size.width {
+        corner.x = position.x - width - 12.0;
+    }
+    if corner.y + height > size.height {
+        corner.y = position.y - height - 12.0;
+    }
+    let outline = Path::rectangle(corner, Size::new(width, height));
+    frame.fill(
+        &outline,
+        Color {
+            a: 0.9,
+            ..background
+        },
+    );
+    frame.stroke(&outline, Stroke::default().with_width(1.0));
+    for (i, (line, color)) in lines.iter().enumerate() {
+        frame.fill_text(Text {
+            content: line.clone(),
+            position: Point::new(corner.x + 6.0, corner.y + 4.0 + i as f32 * line_height),
+            color: *color,
+            size: LABEL_SIZE.into(),
+            ..Text::default()
+        });
+    }
+}
+
+/// Shares of a whole as a ring, hovering a slice shows its name, value and share.
+#[derive(Debug, Clone)]
+pub struct DonutChart {
+    pub slices: Vec<(String, f32)>,
+    pub labels: Labels,
+}
+
+impl DonutChart {
+    fn total(&self) -> f32 {
+        self.slices.iter().map(|(_, value)| value.max(0.0)).sum()
+    }
+
+    // Clockwise from the top, as fractions of the whole circle
+    fn slice_at(&self, fraction: f32) -> Option<usize> {
+        let total = self.total();
+        let mut end = 0.0;
+        for (i, (_, value)) in self.slices.iter().enumerate() {
+            end += value.max(0.0) / total;
+            if fraction <= end {
+                return Some(i);
+            }
+        }
+        None
+    }
+}
+
+impl<Message> canvas::Program<Message> for DonutChart {
+    type State = ();
+
+    fn update(
+        &self,
+        _state: &mut Self::State,
+        event: &Event,
+        bounds: Rectangle,
+        cursor: mouse::Cursor,
+    ) -> Option<canvas::Action<Message>> {
+        match event {
+            Event::Mouse(mouse::Event::CursorMoved { .. }) if cursor.is_over(bounds) => {
+                Some(canvas::Action::request_redraw())
+            }
+            _ => None,
+        }
+    }
+
+    fn draw(
+        &self,
+        _state: &Self::State,
+        renderer: &Renderer,
+        theme: &Theme,
+        bounds: Rectangle,
+        cursor: mouse::Cursor,
+    ) -> Vec<Geometry> {
+        let mut frame = Frame::new(renderer, bounds.size());
+        let palette = theme.palette();
+        let total = self.total();
+        if total <= 0.0 {
+            return vec![frame.into_geometry()];
+        }
+
+        let center = frame.center();
+        let outer = (bounds.width.min(bounds.height) / 2.0 - 4.0).max(1.0);
+        let thickness = outer * 0.4;
+        let radius = outer - thickness / 2.0;
+        let top = -std::f32::consts::FRAC_PI_2;
+
+        let hovered = cursor.position_in(bounds).and_then(|position| {
+            let offset = position - center;
+            let distance = offset.x.hypot(offset.y);
+            if (outer - thickness..=outer).contains(&distance) {
+                let angle = offset.y.atan2(offset.x) - top;
+                self.slice_at(angle.rem_euclid(std::f32::consts::TAU) / std::f32::consts::TAU)
+            } else {
+                None
+            }
+        });
+
+        let mut start = top;
+        for (i, (_, value)) in self.slices.iter().enumerate() {
+            let sweep = value.max(0.0) / total * std::f32::consts::TAU;
+            let arc = Path::new(|builder| {
+                builder.arc(Arc {
+                    center,
+                    radius,
+                    start_angle: Radians(start),
+                    end_angle: Radians(start + sweep),
+                })
+            });
+            let width = if hovered == Some(i) {
+                thickness + 6.0
+            } else {
+                thickness
+            };
+            frame.stroke(
+                &arc,
+                Stroke::default()
+                    .with_color(SLICE_COLORS[i % SLICE_COLORS.len()])
+                    .with_width(width),
+            );
+            start += sweep;
+        }
+
+        if let Some(i) = hovered {
+            let (name, value) = &self.slices[i];
+            let lines = [
+                name.clone(),
+                self.labels.money(*value),
+                self.labels.number_format.percent(value / total * 100.0),
+            ];
+            for (line_number, line) in lines.into_iter().enumerate() {
+                frame.fill_text(Text {
+                    content: line,
+                    position: Point::new(
+                        center.x,
+                        center.y + (line_number as f32 - 1.5) * (LABEL_SIZE + 4.0),
+                    ),
+                    color: palette.text,
+                    size: LABEL_SIZE.into(),
+                    align_x: iced::alignment::Horizontal::Center.into(),
+                    ..Text::default()
+                });
+            }
+        }
+
+        vec![frame.into_geometry()]
+    }
+}
+
+/// Colour of the slice at the given position, for legends next to the chart.
+pub fn slice_color(index: usize) -> Color {
+    SLICE_COLORS[index % SLICE_COLORS.len()]
+}
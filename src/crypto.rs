@@ -0,0 +1,117 @@
+use argon2::Argon2;
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::{Aead, KeyInit, OsRng};
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+
+// File layout: MAGIC | salt | nonce | ciphertext (with authentication tag)
+const MAGIC: &[u8] = b"PORTFOLIO-ENC1\n";
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 24;
+const KEY_LEN: usize = 32;
+
+pub const FILE_EXTENSION: &str = "pfenc";
+
+#[derive(Debug)]
+pub enum CryptoError {
+    WrongPassword,
+    Corrupted,
+    KeyDerivation(argon2::Error),
+}
+
+impl std::fmt::Display for CryptoError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            // AEAD can't tell a wrong key from tampered data, the password is the usual suspect
+            CryptoError::WrongPassword => write!(f, "Wrong password"),
+            CryptoError::Corrupted => write!(f, "The file is not a valid encrypted portfolio"),
+            CryptoError::KeyDerivation(e) => write!(f, "Failed to derive key: {}", e),
+        }
+    }
+}
+
+pub fn is_encrypted(data: &[u8]) -> bool {
+    data.starts_with(MAGIC)
+}
+
+pub fn encrypt(plaintext: &[u8], password: &str) -> Result<Vec<u8>, CryptoError> {
+    let mut salt = [0u8; SALT_LEN];
+    let mut nonce = [0u8; NONCE_LEN];
+    OsRng.fill_bytes(&mut salt);
+    OsRng.fill_bytes(&mut nonce);
+
+    let cipher = XChaCha20Poly1305::new(&derive_key(password, &salt)?.into());
+    let ciphertext = cipher
+        .encrypt(XNonce::from_slice(&nonce), plaintext)
+        .map_err(|_| CryptoError::Corrupted)?;
+
+    let mut data = Vec::with_capacity(MAGIC.len() + SALT_LEN + NONCE_LEN + ciphertext.len());
+    data.extend_from_slice(MAGIC);
+    data.extend_from_slice(&salt);
+    data.extend_from_slice(&nonce);
+    data.extend_from_slice(&ciphertext);
+    Ok(data)
+}
+
+pub fn decrypt(data: &[u8], password: &str) -> Result<Vec<u8>, CryptoError> {
+    let body = data.strip_prefix(MAGIC).ok_or(CryptoError::Corrupted)?;
+    if body.len() < SALT_LEN + NONCE_LEN {
+        return Err(CryptoError::Corrupted);
+    }
+    let (salt, body) = body.split_at(SALT_LEN);
+    let (nonce, ciphertext) = body.split_at(NONCE_LEN);
+
+    let cipher = XChaCha20Poly1305::new(&derive_key(password, salt)?.into());
+    cipher
+        .decrypt(XNonce::from_slice(nonce), ciphertext)
+        .map_err(|_| CryptoError::WrongPassword)
+}
+
+fn derive_key(password: &str, salt: &[u8]) -> Result<[u8; KEY_LEN], CryptoError> {
+    let mut key = [0u8; KEY_LEN];
+    Argon2::default()
+        .hash_password_into(password.as_bytes(), salt, &mut key)
+        .map_err(CryptoError::KeyDerivation)?;
+    Ok(key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PLAINTEXT: &[u8] = b"{\"securities\":[]}";
+
+    #[test]
+    fn decrypts_what_it_encrypts() {
+        let data = encrypt(PLAINTEXT, "secret").unwrap();
+        assert!(is_encrypted(&data));
+        assert_eq!(decrypt(&data, "secret").unwrap(), PLAINTEXT);
+    }
+
+    #[test]
+    fn wrong_password() {
+        let data = encrypt(PLAINTEXT, "secret").unwrap();
+        assert!(matches!(
+            decrypt(&data, "Secret"),
+            Err(CryptoError::WrongPassword)
+        ));
+    }
+
+    #[test]
+    fn damaged_files_are_errors() {
+        let data = encrypt(PLAINTEXT, "secret").unwrap();
+        // Cut inside the header, the salt and nonce are incomplete
+        assert!(matches!(
+            decrypt(&data[..MAGIC.len() + SALT_LEN], "secret"),
+            Err(CryptoError::Corrupted)
+        ));
+        assert!(matches!(
+            decrypt(PLAINTEXT, "secret"),
+            Err(CryptoError::Corrupted)
+        ));
+        // Cut or flipped ciphertext fails authentication
+        assert!(decrypt(&data[..data.len() - 1], "secret").is_err());
+        let mut flipped = data.clone();
+        *flipped.last_mut().unwrap() ^= 1;
+        assert!(decrypt(&flipped, "secret").is_err());
+    }
+}
@@ -0,0 +1,360 @@
+use std::path::Path;
+
+use rust_xlsxwriter::{Format, Workbook, XlsxError};
+
+use crate::Overview;
+use crate::format::{format_date, format_decimal};
+use crate::i18n::{self, Text};
+use crate::security::Action;
+use crate::settings::Settings;
+use crate::tax::CapitalGain;
+
+const HOLDINGS_HEADER: [Text; 11] = [
+    Text::Security,
+    Text::Quantity,
+    Text::PricePerUnit,
+    Text::Invested,
+    Text::CurrentValue,
+    Text::Gain,
+    Text::Volatility,
+    Text::MaxDrawdown,
+    Text::Sharpe,
+    Text::Sortino,
+    Text::Beta,
+];
+const TRANSACTIONS_HEADER: [Text; 8] = [
+    Text::Date,
+    Text::Security,
+    Text::Action,
+    Text::Quantity,
+    Text::PricePerUnit,
+    Text::Fee,
+    Text::TaxWithheld,
+    Text::Total,
+];
+
+const CAPITAL_GAINS_HEADER: [Text; 10] = [
+    Text::Security,
+    Text::Acquired,
+    Text::Disposed,
+    Text::Quantity,
+    Text::Proceeds,
+    Text::CostBasis,
+    Text::Fee,
+    Text::Disallowed,
+    Text::Gain,
+    Text::Term,
+];
+
+#[derive(Debug)]
+pub enum ExportError {
+    Io(std::io::Error),
+    Csv(csv::Error),
+    Xlsx(XlsxError),
+    UnsupportedFormat(String),
+}
+
+impl std::fmt::Display for ExportError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ExportError::Io(e) => write!(f, "{}", e),
+            ExportError::Csv(e) => write!(f, "{}", e),
+            ExportError::Xlsx(e) => write!(f, "{}", e),
+            ExportError::UnsupportedFormat(extension) => {
+                write!(f, "unsupported export format: {:?}", extension)
+            }
+        }
+    }
+}
+
+impl From<std::io::Error> for ExportError {
+    fn from(e: std::io::Error) -> Self {
+        ExportError::Io(e)
+    }
+}
+
+impl From<csv::Error> for ExportError {
+    fn from(e: csv::Error) -> Self {
+        ExportError::Csv(e)
+    }
+}
+
+impl From<XlsxError> for ExportError {
+    fn from(e: XlsxError) -> Self {
+        ExportError::Xlsx(e)
+    }
+}
+
+// A cell is either text or a number, so spreadsheets keep numbers numeric
+// while CSV renders them with the user's decimal separator
+enum Cell {
+    Text(String),
+    Number(f32),
+}
+
+pub fn export_holdings(
+    overview: &Overview,
+    path: &Path,
+    settings: &Settings,
+) -> Result<(), ExportError> {
+    let rows: Vec<Vec<Cell>> = overview
+        .securities
+        .iter()
+        .map(|security| {
+            let invested = security.get_total_invested_value();
+            let current = security.get_total_current_value();
+            let risk = overview.risk_metrics(&[security], settings);
+            let percent = |value: Option<f64>| match value {
+                Some(value) => Cell::Number((value * 100.0) as f32),
+                None => Cell::Text(String::new()),
+            };
+            let ratio = |value: Option<f64>| match value {
+                Some(value) => Cell::Number(value as f32),
+                None => Cell::Text(String::new()),
+            };
+            vec![
+                Cell::Text(security.name.clone()),
+                Cell::Number(security.get_quantity() as f32),
+                Cell::Number(security.get_current_price_per_unit()),
+                Cell::Number(invested),
+                Cell::Number(current),
+                Cell::Number(current - invested),
+                percent(risk.volatility),
+                percent(risk.max_drawdown.map(|drawdown| -drawdown.depth)),
+                ratio(risk.sharpe),
+                ratio(risk.sortino),
+                ratio(risk.beta),
+            ]
+        })
+        .collect();
+    write_table(path, Text::Holdings, &HOLDINGS_HEADER, &rows, settings)
+}
+
+pub fn export_transactions(
+    overview: &Overview,
+    path: &Path,
+    settings: &Settings,
+) -> Result<(), ExportError> {
+    let mut rows: Vec<Vec<Cell>> = Vec::new();
+    for security in overview.securities.iter() {
+        let costs = security
+            .get_fees()
+            .into_iter()
+            .zip(security.get_taxes_withheld());
+        for ((action, date, quantity, price_per_unit), (fee, tax_withheld)) in
+            security.get_entries().into_iter().zip(costs)
+        {
+            // The money paid for a buy, received for a sale or income
+            let amount = quantity as f32 * price_per_unit;
+            let total = match action {
+                Action::Buy => amount + fee,
+                Action::Sell => amount - fee,
+                Action::Dividend | Action::Interest | Action::Distribution => {
+                    amount - fee - tax_withheld
+                }
+            };
+            rows.push(vec![
+                Cell::Text(format_date(&date, &settings.date_format)),
+                Cell::Text(security.name.clone()),
+                Cell::Text(i18n::tr(settings.language, action.label()).to_string()),
+                Cell::Number(quantity as f32),
+                Cell::Number(price_per_unit),
+                Cell::Number(fee),
+                Cell::Number(tax_withheld),
+                Cell::Number(total),
+            ]);
+        }
+    }
+    write_table(
+        path,
+        Text::Transactions,
+        &TRANSACTIONS_HEADER,
+        &rows,
+        settings,
+    )
+}
+
+fn write_table(
+    path: &Path,
+    sheet_name: Text,
+    header: &[Text],
+    rows: &[Vec<Cell>],
+    settings: &Settings,
+) -> Result<(), ExportError> {
+    let tr = |text: Text| i18n::tr(settings.language, text);
+    let sheet_name = tr(sheet_name);
+    // Risk columns hold percentages, the ratios are plain numbers
+    let header: Vec<String> = header
+        .iter()
+        .map(|&column| match column {
+            Text::Volatility | Text::MaxDrawdown => format!("{} (%)", tr(column)),
+            _ => tr(column).to_string(),
+        })
+        .collect();
+    let extension = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or_default()
+        .to_lowercase();
+    match extension.as_str() {
+        "csv" => write_csv(path, &header, rows, settings),
+        "xlsx" => write_xlsx(path, sheet_name, &header, rows),
+        _ => Err(ExportError::UnsupportedFormat(extension)),
+    }
+}
+
+fn write_csv(
+    path: &Path,
+    header: &[String],
+    rows: &[Vec<Cell>],
+    settings: &Settings,
+) -> Result<(), ExportError> {
+    // Spreadsheets expect ';' as delimiter when ',' is the decimal separator
+    let delimiter = if settings.decimal_separator == ',' {
+        b';'
+    } else {
+        b','
+    };
+    let mut writer = csv::WriterBuilder::new()
+        .delimiter(delimiter)
+        .from_path(path)?;
+    writer.write_record(header)?;
+    for row in rows {
+        writer.write_record(row.iter().map(|cell| match cell {
+            Cell::Text(value) => value.clone(),
+            Cell::Number(value) => format_decimal(*value, 2, settings.decimal_separator),
+        }))?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+fn write_xlsx(
+    path: &Path,
+    sheet_name: &str,
+    header: &[String],
+    rows: &[Vec<Cell>],
+) -> Result<(), ExportError> {
+    let mut workbook = Workbook::new();
+    let header_format = Format::new().set_bold();
+    let number_format = Format::new().set_num_format("#,##0.00");
+
+    let worksheet = workbook.add_worksheet();
+    worksheet.set_name(sheet_name)?;
+    for (col, title) in header.iter().enumerate() {
+        worksheet.write_string_with_format(0, col as u16, title, &header_format)?;
+    }
+    for (row, cells) in rows.iter().enumerate() {
+        let row = row as u32 + 1;
+        for (col, cell) in cells.iter().enumerate() {
+            match cell {
+                Cell::Text(value) => {
+                    worksheet.write_string(row, col as u16, value)?;
+                }
+                Cell::Number(value) => {
+                    worksheet.write_number_with_format(
+                        row,
+                        col as u16,
+                        *value as f64,
+                        &number_format,
+                    )?;
+                }
+            }
+        }
+    }
+    workbook.save(path)?;
+    Ok(())
+}
+
+pub fn export_capital_gains(
+    gains: &[CapitalGain],
+    path: &Path,
+    settings: &Settings,
+) -> Result<(), ExportError> {
+    let rows: Vec<Vec<Cell>> = gains
+        .iter()
+        .map(|gain| {
+            let acquired: Vec<String> = gain
+                .acquired
+                .iter()
+                .map(|day| day.format(&settings.date_format).to_string())
+                .collect();
+            vec![
+                Cell::Text(gain.security.clone()),
+                Cell::Text(acquired.join(", ")),
+                Cell::Text(gain.disposed.format(&settings.date_format).to_string()),
+                Cell::Number(gain.quantity),
+                Cell::Number(gain.proceeds),
+                Cell::Number(gain.cost_basis),
+                Cell::Number(gain.fees),
+                Cell::Number(gain.disallowed),
+                Cell::Number(gain.gain()),
+                Cell::Text(i18n::tr(settings.language, gain.term.label()).to_string()),
+            ]
+        })
+        .collect();
+    write_table(
+        path,
+        Text::CapitalGains,
+        &CAPITAL_GAINS_HEADER,
+        &rows,
+        settings,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::i18n::Language;
+    use crate::security::test_support::security;
+
+    fn overview() -> Overview {
+        let security = security(&[
+            (Action::Buy, "2024-01-10", 10, 1234.5, 4.5, 0.0),
+            (Action::Dividend, "2024-06-03", 10, 2.0, 0.0, 3.0),
+        ]);
+        let mut overview = Overview::new();
+        overview.securities.push(security);
+        overview
+    }
+
+    fn export_csv(name: &str, settings: &Settings) -> String {
+        let path = std::env::temp_dir().join(format!("{}-{}.csv", name, std::process::id()));
+        export_transactions(&overview(), &path, settings).unwrap();
+        let data = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        data
+    }
+
+    #[test]
+    fn csv_uses_the_number_and_date_settings() {
+        let data = export_csv("default", &Settings::default());
+        assert_eq!(
+            data,
+            "Date,Security,Action,Quantity,Price per Unit,Fee,Tax Withheld,Total\n\
+             2024-01-10,ETF,BUY,10.00,1234.50,4.50,0.00,12349.50\n\
+             2024-06-03,ETF,DIVIDEND,10.00,2.00,0.00,3.00,17.00\n"
+        );
+
+        let mut settings = Settings::default();
+        settings.decimal_separator = ',';
+        settings.date_format = "%d.%m.%Y".to_string();
+        settings.language = Language::German;
+        let data = export_csv("german", &settings);
+        assert_eq!(
+            data,
+            "Datum;Wertpapier;Aktion;Menge;Preis pro Einheit;Gebühr;Quellensteuer;Summe\n\
+             10.01.2024;ETF;KAUF;10,00;1234,50;4,50;0,00;12349,50\n\
+             03.06.2024;ETF;DIVIDENDE;10,00;2,00;0,00;3,00;17,00\n"
+        );
+    }
+
+    #[test]
+    fn unknown_extensions_are_rejected() {
+        let path = std::env::temp_dir().join("holdings.txt");
+        assert!(matches!(
+            export_holdings(&overview(), &path, &Settings::default()),
+            Err(ExportError::UnsupportedFormat(extension)) if extension == "txt"
+        ));
+    }
+}
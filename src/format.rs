@@ -0,0 +1,292 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use crate::settings::Settings;
+
+// Entry dates are typed by hand, so accept the common ways of writing them
+const DATE_INPUT_FORMATS: [&str; 5] = ["%Y-%m-%d", "%d.%m.%Y", "%d/%m/%Y", "%Y/%m/%d", "%d-%m-%Y"];
+
+pub fn parse_date(input: &str) -> Option<NaiveDate> {
+    let input = input.trim();
+    DATE_INPUT_FORMATS
+        .iter()
+        .find_map(|format| NaiveDate::parse_from_str(input, format).ok())
+}
+
+/// Parses a date typed by the user, trying their own date format first.
+pub fn parse_date_input(input: &str, date_format: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(input.trim(), date_format)
+        .ok()
+        .or_else(|| parse_date(input))
+}
+
+/// Dates are stored as ISO text so every part of the app can read them back.
+pub fn iso_date(date: NaiveDate) -> String {
+    date.format("%Y-%m-%d").to_string()
+}
+
+/// Reformats a stored date with the given format, keeping the raw text if it can't be parsed.
+pub fn format_date(raw: &str, date_format: &str) -> String {
+    match parse_date(raw) {
+        Some(date) => date.format(date_format).to_string(),
+        None => raw.to_string(),
+    }
+}
+
+pub fn format_decimal(value: f32, decimals: usize, decimal_separator: char) -> String {
+    let formatted = format!("{:.*}", decimals, value);
+    if decimal_separator == '.' {
+        formatted
+    } else {
+        formatted.replace('.', &decimal_separator.to_string())
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Locale {
+    #[default]
+    EnUs,
+    PtPt,
+    DeDe,
+}
+
+impl Locale {
+    pub const ALL: [Locale; 3] = [Locale::EnUs, Locale::PtPt, Locale::DeDe];
+
+    fn thousands_separator(self) -> char {
+        match self {
+            Locale::EnUs => ',',
+            Locale::PtPt => ' ',
+            Locale::DeDe => '.',
+        }
+    }
+
+    fn symbol_after_amount(self) -> bool {
+        !matches!(self, Locale::EnUs)
+    }
+}
+
+impl std::fmt::Display for Locale {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Locale::EnUs => write!(f, "English (US)"),
+            Locale::PtPt => write!(f, "Português (Portugal)"),
+            Locale::DeDe => write!(f, "Deutsch (Deutschland)"),
+        }
+    }
+}
+
+// Currencies with a symbol of their own, BRL first so "R$" goes before "$"
+const KNOWN_CURRENCIES: [&str; 6] = ["BRL", "CHF", "EUR", "GBP", "JPY", "USD"];
+
+pub fn currency_symbol(currency: &str) -> &str {
+    match currency {
+        "EUR" => "€",
+        "USD" => "$",
+        "GBP" => "£",
+        "JPY" => "¥",
+        "BRL" => "R$",
+        "CHF" => "CHF",
+        other => other,
+    }
+}
+
+// Rendering and parsing rules for amounts, built from the user's locale and decimal separator
+#[derive(Debug, Clone, Copy)]
+pub struct NumberFormat {
+    decimal_separator: char,
+    thousands_separator: char,
+    symbol_after_amount: bool,
+}
+
+impl NumberFormat {
+    pub fn new(settings: &Settings) -> Self {
+        let decimal_separator = settings.decimal_separator;
+        let mut thousands_separator = settings.locale.thousands_separator();
+        if thousands_separator == decimal_separator {
+            thousands_separator = if decimal_separator == '.' { ',' } else { '.' };
+        }
+        Self {
+            decimal_separator,
+            thousands_separator,
+            symbol_after_amount: settings.locale.symbol_after_amount(),
+        }
+    }
+
+    /// Reads numbers written with the given decimal separator, e.g. in a file,
+    /// whatever the user's settings are.
+    pub fn with_decimal_separator(decimal_separator: char) -> Self {
+        Self {
+            decimal_separator,
+            thousands_separator: if decimal_separator == '.' { ',' } else { '.' },
+            symbol_after_amount: false,
+        }
+    }
+
+    /// Fixed decimals with grouped thousands, e.g. 1,234.50 or 1.234,50.
+    pub fn number(&self, value: f32, decimals: usize) -> String {
+        let formatted = format!("{:.*}", decimals, value.abs());
+        let (integer, fraction) = match formatted.split_once('.') {
+            Some((integer, fraction)) => (integer, Some(fraction)),
+            None => (formatted.as_str(), None),
+        };
+
+        let mut grouped = String::new();
+        for (i, digit) in integer.chars().enumerate() {
+            if i > 0 && (integer.len() - i) % 3 == 0 {
+                grouped.push(self.thousands_separator);
+            }
+            grouped.push(digit);
+        }
+        if let Some(fraction) = fraction {
+            grouped.push(self.decimal_separator);
+            grouped.push_str(fraction);
+        }
+        // Rounding can turn small negatives into zero, which shouldn't keep the sign
+        if value < 0.0 && formatted.chars().any(|c| c.is_ascii_digit() && c != '0') {
+            grouped.insert(0, '-');
+        }
+        grouped
+    }
+
+    pub fn money(&self, value: f32, currency: &str) -> String {
+        let symbol = currency_symbol(currency);
+        let amount = self.number(value, 2);
+        if self.symbol_after_amount {
+            format!("{} {}", amount, symbol)
+        } else if let Some(amount) = amount.strip_prefix('-') {
+            format!("-{}{}", symbol, amount)
+        } else {
+            format!("{}{}", symbol, amount)
+        }
+    }
+
+    pub fn percent(&self, value: f32) -> String {
+        format!("{} %", self.number(value, 2))
+    }
+
+    /// Parses an amount typed by the user. Known currency symbols and codes and spaces
+    /// are ignored, any other character makes it invalid. A lone separator that
+    /// doesn't group thousands is read as decimal separator, other grouping must
+    /// start with a non-zero group of up to 3 digits followed by groups of 3.
+    pub fn parse(&self, input: &str) -> Option<f32> {
+        let mut cleaned: String = input.chars().filter(|c| !c.is_whitespace()).collect();
+        for currency in KNOWN_CURRENCIES {
+            cleaned = cleaned
+                .replace(currency, "")
+                .replace(currency_symbol(currency), "");
+        }
+        if cleaned.is_empty()
+            || !cleaned
+                .chars()
+                .all(|c| c.is_ascii_digit() || matches!(c, '-' | '.' | ','))
+        {
+            return None;
+        }
+
+        // Spaces are already gone, the other punctuation mark can only be grouping
+        let grouping = if self.decimal_separator == '.' {
+            ','
+        } else {
+            '.'
+        };
+        let normalized =
+            if let Some((integer, fraction)) = cleaned.split_once(self.decimal_separator) {
+                format!("{}.{}", ungroup(integer, grouping)?, fraction)
+            } else if let Some((_, tail)) = cleaned.split_once(grouping)
+                && !tail.contains(grouping)
+                && tail.len() != 3
+            {
+                cleaned.replace(grouping, ".")
+            } else {
+                ungroup(&cleaned, grouping)?
+            };
+        normalized.parse::<f32>().ok()
+    }
+}
+
+// The digits of a grouped integer, none if the groups don't separate thousands
+fn ungroup(integer: &str, grouping: char) -> Option<String> {
+    if !integer.contains(grouping) {
+        return Some(integer.to_string());
+    }
+    let mut groups = integer.split(grouping);
+    let leading = groups.next()?;
+    let digits = leading.strip_prefix('-').unwrap_or(leading);
+    if digits.is_empty() || digits.len() > 3 || digits.starts_with('0') {
+        return None;
+    }
+    let mut ungrouped = leading.to_string();
+    for group in groups {
+        if group.len() != 3 {
+            return None;
+        }
+        ungrouped.push_str(group);
+    }
+    Some(ungrouped)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn number_format(locale: Locale, decimal_separator: char) -> NumberFormat {
+        let mut settings = Settings::default();
+        settings.locale = locale;
+        settings.decimal_separator = decimal_separator;
+        NumberFormat::new(&settings)
+    }
+
+    #[test]
+    fn formatted_amounts_parse_back() {
+        for locale in Locale::ALL {
+            for decimal_separator in ['.', ','] {
+                let format = number_format(locale, decimal_separator);
+                for value in [0.0, 0.05, 12.5, 1234.56, -98765.43, 1234567.0] {
+                    for formatted in [
+                        format.number(value, 2),
+                        format.money(value, "EUR"),
+                        format.money(value, "BRL"),
+                        format.money(value, "CHF"),
+                    ] {
+                        let parsed = format.parse(&formatted);
+                        assert!(
+                            parsed.is_some_and(|parsed| (parsed - value).abs() < 0.01),
+                            "{:?} {} read {:?} as {:?}",
+                            locale,
+                            decimal_separator,
+                            formatted,
+                            parsed
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn separators_by_locale() {
+        let format = number_format(Locale::EnUs, '.');
+        assert_eq!(format.money(1234.5, "USD"), "$1,234.50");
+        assert_eq!(format.parse("1,234"), Some(1234.0));
+        assert_eq!(format.parse("1,5"), Some(1.5));
+        assert_eq!(format.parse("-12,345,678.9"), Some(-12345678.9));
+        let format = number_format(Locale::DeDe, ',');
+        assert_eq!(format.money(-1234.5, "EUR"), "-1.234,50 €");
+        assert_eq!(format.parse("1.234,5 EUR"), Some(1234.5));
+        let format = number_format(Locale::PtPt, ',');
+        assert_eq!(format.number(1234567.0, 0), "1 234 567");
+    }
+
+    #[test]
+    fn typos_are_not_amounts() {
+        let format = number_format(Locale::EnUs, '.');
+        for input in ["", "  ", "€", "1x2", "1O.5", "abc", "12 EURO", "5%", "1-2"] {
+            assert_eq!(format.parse(input), None, "{:?}", input);
+        }
+        for input in ["0,125", "1234,567", "1,23,456", "1,2345.5", ",123"] {
+            assert_eq!(format.parse(input), None, "{:?}", input);
+        }
+    }
+}
@@ -0,0 +1,224 @@
+use chrono::{Months, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+use crate::format::{iso_date, parse_date};
+use crate::security::Security;
+
+const MAX_MONTHS: u32 = 1200; // projections stop after a hundred years
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Goal {
+    pub name: String,
+    pub target_amount: f32,
+    target_date: String, // ISO
+    pub accounts: Vec<String>,
+    pub security_ids: Vec<u8>,
+    pub assumed_return: f32, // annual, in percent
+}
+
+/// Where a goal stands, worked out again whenever the holdings change.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GoalProgress {
+    pub current: f32,
+    pub progress: f32,                 // percent of the target amount
+    pub required_monthly: f32,         // to reach the target by the target date
+    pub planned_monthly: f32,          // from the savings plans of the linked securities
+    pub completion: Option<NaiveDate>, // None when the plans never get there
+}
+
+impl Goal {
+    pub fn new(
+        name: String,
+        target_amount: f32,
+        target_date: NaiveDate,
+        accounts: Vec<String>,
+        security_ids: Vec<u8>,
+        assumed_return: f32,
+    ) -> Self {
+        Self {
+            name,
+            target_amount,
+            target_date: iso_date(target_date),
+            accounts,
+            security_ids,
+            assumed_return,
+        }
+    }
+
+    pub fn get_target_date(&self) -> Option<NaiveDate> {
+        parse_date(&self.target_date)
+    }
+
+    /// Whether the security counts towards the goal, everything does when nothing is linked.
+    pub fn includes(&self, security: &Security) -> bool {
+        self.covers_everything()
+            || self.security_ids.contains(&security.id)
+            || (!security.account.is_empty() && self.accounts.contains(&security.account))
+    }
+
+    /// Goals without linked accounts or securities also count the cash.
+    pub fn covers_everything(&self) -> bool {
+        self.accounts.is_empty() && self.security_ids.is_empty()
+    }
+
+    pub fn progress(&self, securities: &[Security], cash: f32, today: NaiveDate) -> GoalProgress {
+        let linked: Vec<&Security> = securities.iter().filter(|s| self.includes(s)).collect();
+        let mut current: f32 = linked.iter().map(|s| s.get_total_current_value()).sum();
+        if self.covers_everything() {
+            current += cash;
+        }
+        let planned_monthly: f32 = linked
+            .iter()
+            .flat_map(|security| {
+                security
+                    .savings_plans
+                    .iter()
+                    .map(|plan| plan.monthly_amount(security.get_current_price_per_unit(), today))
+            })
+            .sum();
+        let rate = monthly_rate(self.assumed_return);
+        let months_left = self
+            .get_target_date()
+            .map_or(0, |target_date| months_between(today, target_date));
+
+        GoalProgress {
+            current,
+            progress: if self.target_amount > 0.0 {
+                current / self.target_amount * 100.0
+            } else {
+                100.0
+            },
+            required_monthly: required_monthly(current, self.target_amount, rate, months_left),
+            planned_monthly,
+            completion: months_to_reach(current, self.target_amount, rate, planned_monthly)
+                .and_then(|months| today.checked_add_months(Months::new(months))),
+        }
+    }
+}
+
+/// The monthly rate that compounds to the annual return in percent.
+pub fn monthly_rate(annual_percent: f32) -> f64 {
+    (1.0 + annual_percent as f64 / 100.0).powf(1.0 / 12.0) - 1.0
+}
+
+/// Monthly contribution that grows the current value to the target in the given
+/// months, all of the difference at once when no time is left.
+pub fn required_monthly(current: f32, target: f32, rate: f64, months: u32) -> f32 {
+    if months == 0 {
+        return (target - current).max(0.0);
+    }
+    let growth = (1.0 + rate).powi(months as i32);
+    let shortfall = target as f64 - current as f64 * growth;
+    let per_month = if rate.abs() < 1e-12 {
+        shortfall / months as f64
+    } else {
+        shortfall * rate / (growth - 1.0)
+    };
+    per_month.max(0.0) as f32
+}
+
+/// Months until the value with the monthly contribution reaches the target.
+pub fn months_to_reach(current: f32, target: f32, rate: f64, contribution: f32) -> Option<u32> {
+    let mut value = current as f64;
+    for month in 0..=MAX_MONTHS {
+        if value >= target as f64 {
+            return Some(month);
+        }
+        value = value * (1.0 + rate) + contribution as f64;
+    }
+    None
+}
+
+// Whole months from the first day to the second, none when it has passed
+fn months_between(from: NaiveDate, to: NaiveDate) -> u32 {
+    let mut months = 0;
+    while months < MAX_MONTHS
+        && from
+            .checked_add_months(Months::new(months + 1))
+            .is_some_and(|date| date <= to)
+    {
+        months += 1;
+    }
+    months
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::savings_plan::{Frequency, PlanSize, SavingsPlan};
+    use crate::security::Action;
+    use crate::security::test_support::{date, security};
+
+    #[test]
+    fn required_contribution_without_and_with_growth() {
+        assert_eq!(required_monthly(1000.0, 2200.0, 0.0, 12), 100.0);
+        assert_eq!(required_monthly(3000.0, 2000.0, 0.0, 12), 0.0);
+        assert_eq!(required_monthly(1000.0, 1500.0, 0.0, 0), 500.0);
+        // 1% a month: 100 for 12 months grows to 1268.25
+        let required = required_monthly(0.0, 1268.25, 0.01, 12);
+        assert!((required - 100.0).abs() < 0.01, "got {}", required);
+    }
+
+    #[test]
+    fn months_to_reach_the_target() {
+        assert_eq!(months_to_reach(1000.0, 1000.0, 0.0, 0.0), Some(0));
+        assert_eq!(months_to_reach(0.0, 1000.0, 0.0, 100.0), Some(10));
+        assert_eq!(months_to_reach(100.0, 1000.0, 0.0, 0.0), None);
+    }
+
+    #[test]
+    fn progress_of_the_linked_securities() {
+        let mut linked = security(&[(Action::Buy, "2024-01-02", 10, 100.0, 0.0, 0.0)]);
+        linked.update_current_price(100.0, "2024-06-01".to_string());
+        linked.calculate_total_current_value();
+        linked.savings_plans.push(SavingsPlan::new(
+            PlanSize::Quantity(3),
+            Frequency::Quarterly,
+            date(2024, 1, 1),
+            None,
+            1,
+        ));
+        let mut other = linked.clone();
+        other.id = 1;
+        other.account = "Pension".to_string();
+
+        let goal = Goal::new(
+            "House".to_string(),
+            4000.0,
+            date(2026, 6, 1),
+            Vec::new(),
+            vec![0],
+            0.0,
+        );
+        let progress = goal.progress(&[linked, other], 500.0, date(2024, 6, 1));
+        assert_eq!(progress.current, 1000.0);
+        assert_eq!(progress.progress, 25.0);
+        assert_eq!(progress.required_monthly, 125.0);
+        assert_eq!(progress.planned_monthly, 100.0);
+        assert_eq!(progress.completion, Some(date(2026, 12, 1)));
+    }
+
+    #[test]
+    fn goals_without_links_count_everything() {
+        let mut security = security(&[]);
+        security.account = "Broker".to_string();
+        let everything = Goal::new(
+            "Retirement".to_string(),
+            1000.0,
+            date(2040, 1, 1),
+            Vec::new(),
+            Vec::new(),
+            5.0,
+        );
+        assert!(everything.includes(&security));
+        let by_account = Goal {
+            accounts: vec!["Pension".to_string()],
+            ..everything.clone()
+        };
+        assert!(!by_account.includes(&security));
+        assert_eq!(
+            everything.progress(&[], 250.0, date(2030, 1, 1)).current,
+            250.0
+        );
+    }
+}
@@ -0,0 +1,924 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Language {
+    #[default]
+    English,
+    Portuguese,
+    German,
+}
+
+impl Language {
+    pub const ALL: [Language; 3] = [Language::English, Language::Portuguese, Language::German];
+}
+
+// Languages are listed in their own name so they can be found in any UI language
+impl std::fmt::Display for Language {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Language::English => write!(f, "English"),
+            Language::Portuguese => write!(f, "Português"),
+            Language::German => write!(f, "Deutsch"),
+        }
+    }
+}
+
+/// Option of a pick list, shown in the UI language.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Choice<T> {
+    pub value: T,
+    label: &'static str,
+}
+
+impl<T> Choice<T> {
+    pub fn new(language: Language, value: T, label: fn(&T) -> Text) -> Self {
+        let label = tr(language, label(&value));
+        Self { value, label }
+    }
+}
+
+impl<T> std::fmt::Display for Choice<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.label)
+    }
+}
+
+/// All options of a pick list with their labels in the language.
+pub fn choices<T: Copy>(language: Language, values: &[T], label: fn(&T) -> Text) -> Vec<Choice<T>> {
+    values
+        .iter()
+        .map(|value| Choice::new(language, *value, label))
+        .collect()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Text {
+    // Toolbar
+    New,
+    Open,
+    Save,
+    SaveAs,
+    ExportHoldings,
+    ExportTransactions,
+    Exported,
+    ExportFailed,
+    RefreshPrices,
+    Settings,
+    Debug,
+    // Main menu
+    RecentPortfolios,
+    ReopenLast,
+    // Overview
+    Portfolio,
+    TotalInvested,
+    AddSecurity,
+    GenerateReport,
+    Security,
+    Id,
+    Quantity,
+    CurrentValuePerUnit,
+    TotalInvestedValue,
+    TotalCurrentValue,
+    AddEntry,
+    UpdateCurrentValue,
+    Action,
+    Date,
+    ValuePerUnit,
+    UnrealizedGain,
+    RealizedGain,
+    Income,
+    TotalReturn,
+    EditSecurity,
+    FetchPrices,
+    RefreshingPrices,
+    PricesRefreshed,
+    QuotedAt,
+    ImportPrices,
+    OverwritePrices,
+    PricesAdded,
+    PricesReplaced,
+    PricesKept,
+    ImportFailed,
+    OpenSecurityToImport,
+    NoSecurityForColumn,
+    AsOf,
+    Stale,
+    StalePrices,
+    // Dashboard
+    Dashboard,
+    TotalValue,
+    TotalGain,
+    TodayChange,
+    YearChange,
+    Cash,
+    UpdateCash,
+    IncomeYtd,
+    AllocationBySecurity,
+    AllocationByAssetClass,
+    AllocationByCurrency,
+    AllocationByAccount,
+    Unassigned,
+    Price,
+    ChartHint,
+    GrowthOf100,
+    // Returns
+    Returns,
+    Period,
+    TimeWeighted,
+    MoneyWeighted,
+    SinceInception,
+    CustomPeriod,
+    // Risk
+    Risk,
+    Volatility,
+    MaxDrawdown,
+    Sharpe,
+    Sortino,
+    Beta,
+    Benchmark,
+    NoBenchmark,
+    UseAsBenchmark,
+    RemoveBenchmark,
+    ImportIndex,
+    TrackingDifference,
+    // Rebalancing
+    Rebalancing,
+    SetTarget,
+    AssetClassTarget,
+    PlanRebalancing,
+    CurrentWeight,
+    Drift,
+    BuyOnly,
+    Unallocated,
+    // Pop ups and settings
+    Confirm,
+    Back,
+    SecurityName,
+    Amount,
+    PricePerUnit,
+    CurrentValue,
+    StartDate,
+    EndDate,
+    BaseCurrency,
+    Password,
+    DateFormat,
+    DecimalSeparator,
+    AutosaveInterval,
+    DefaultDirectory,
+    RiskFreeRate,
+    AssetClass,
+    Currency,
+    Account,
+    Ticker,
+    Isin,
+    StalePriceDays,
+    Fee,
+    TaxYear,
+    LongTermDays,
+    CapitalGains,
+    Disposals,
+    Summary,
+    ShortTerm,
+    LongTerm,
+    LongTermAfter,
+    TaxWithheld,
+    Country,
+    Gross,
+    Net,
+    Group,
+    ProjectedIncome,
+    WashSaleDays,
+    WashSaleRule,
+    WashSales,
+    HarvestableLosses,
+    Loss,
+    Disallowed,
+    Replacements,
+    RecentPurchase,
+    CostBasis,
+    Acquired,
+    Disposed,
+    Proceeds,
+    Term,
+    PlanAmount,
+    PlanQuantity,
+    Frequency,
+    FrequencyMonthly,
+    FrequencyQuarterly,
+    FrequencySemiAnnually,
+    FrequencyAnnually,
+    Yearly,
+    Monthly,
+    AddToReplacementCost,
+    DisallowLoss,
+    Assumptions,
+    PriceHistory,
+    DayOfMonth,
+    SavingsPlans,
+    AddSavingsPlan,
+    PendingPlanEntries,
+    Skip,
+    Remove,
+    Goals,
+    AddGoal,
+    GoalName,
+    TargetAmount,
+    TargetDate,
+    Accounts,
+    Securities,
+    AssumedReturn,
+    RequiredMonthly,
+    PlannedMonthly,
+    ProjectedCompletion,
+    By,
+    ReportTitle,
+    Holdings,
+    Allocation,
+    Performance,
+    Transactions,
+    RealizedGains,
+    Invested,
+    Value,
+    Gain,
+    Weight,
+    Type,
+    Total,
+    ValueAtStart,
+    ValueAtEnd,
+    PurchasesInPeriod,
+    SalesInPeriod,
+    IncomeInPeriod,
+    RealizedGainsInPeriod,
+    Projection,
+    SetAssumption,
+    RunSimulation,
+    Years,
+    Paths,
+    Seed,
+    AnnualVolatility,
+    Year,
+    MonthsOfHistory,
+    NoPrice,
+    QuoteUrl,
+    HistoryUrl,
+    QuoteFile,
+    TargetWeight,
+    Tolerance,
+    Contribution,
+    MinTradeValue,
+    FractionalShares,
+    Theme,
+    NumberFormat,
+    Language,
+    DefaultCostBasis,
+    ErrorCode,
+    // Validation errors
+    PasswordEmpty,
+    InvalidAmount,
+    TooManyUnits,
+    InvalidPrice,
+    InvalidDate,
+    BaseCurrencyEmpty,
+    InvalidDateFormat,
+    InvalidDecimalSeparator,
+    InvalidAutosaveInterval,
+    InvalidRiskFreeRate,
+    InvalidWeight,
+    InvalidStalePriceDays,
+    InvalidFee,
+    UnknownAction,
+    ActionBuy,
+    ActionSell,
+    ActionDividend,
+    ActionInterest,
+    ActionDistribution,
+    InvalidTaxYear,
+    InvalidLongTermDays,
+    InvalidTaxWithheld,
+    InvalidWashSaleDays,
+    AmountOrQuantity,
+    InvalidFrequency,
+    InvalidDayOfMonth,
+    GoalNameEmpty,
+    UnknownSecurity,
+    InvalidAssumedReturn,
+    InvalidYears,
+    InvalidPaths,
+    InvalidSeed,
+    InvalidVolatility,
+    NoPriceHistory,
+}
+
+/// Looks up the text in the catalog, every key has an entry for every language.
+pub fn tr(language: Language, text: Text) -> &'static str {
+    let (english, portuguese, german) = match text {
+        Text::New => ("New", "Novo", "Neu"),
+        Text::Open => ("Open", "Abrir", "Öffnen"),
+        Text::Save => ("Save", "Guardar", "Speichern"),
+        Text::SaveAs => ("Save as", "Guardar como", "Speichern unter"),
+        Text::ExportHoldings => (
+            "Export Holdings",
+            "Exportar Posições",
+            "Bestand exportieren",
+        ),
+        Text::ExportTransactions => (
+            "Export Transactions",
+            "Exportar Transações",
+            "Transaktionen exportieren",
+        ),
+        Text::Exported => ("Exported to", "Exportado para", "Exportiert nach"),
+        Text::ExportFailed => (
+            "Export failed",
+            "Falha na exportação",
+            "Export fehlgeschlagen",
+        ),
+        Text::Settings => ("Settings", "Definições", "Einstellungen"),
+        Text::Debug => ("Debug", "Depurar", "Debug"),
+        Text::RecentPortfolios => (
+            "Recent Portfolios",
+            "Portfólios Recentes",
+            "Zuletzt geöffnete Portfolios",
+        ),
+        Text::ReopenLast => (
+            "Reopen last portfolio on startup",
+            "Reabrir o último portfólio ao iniciar",
+            "Letztes Portfolio beim Start öffnen",
+        ),
+        Text::Portfolio => ("Portfolio", "Portfólio", "Portfolio"),
+        Text::TotalInvested => ("Total Invested", "Total Investido", "Gesamt investiert"),
+        Text::AddSecurity => ("Add Security", "Adicionar Título", "Wertpapier hinzufügen"),
+        Text::GenerateReport => ("Generate Report", "Gerar Relatório", "Bericht erstellen"),
+        Text::Security => ("Security", "Título", "Wertpapier"),
+        Text::Id => ("Id", "Id", "ID"),
+        Text::Quantity => ("Quantity", "Quantidade", "Menge"),
+        Text::CurrentValuePerUnit => (
+            "Current value per unit",
+            "Valor atual por unidade",
+            "Aktueller Wert pro Einheit",
+        ),
+        Text::TotalInvestedValue => (
+            "Total invested value",
+            "Valor total investido",
+            "Investierter Gesamtwert",
+        ),
+        Text::TotalCurrentValue => (
+            "Total current value",
+            "Valor atual total",
+            "Aktueller Gesamtwert",
+        ),
+        Text::AddEntry => ("Add Entry", "Adicionar Movimento", "Buchung hinzufügen"),
+        Text::UpdateCurrentValue => (
+            "Update Current Value",
+            "Atualizar Valor Atual",
+            "Aktuellen Wert aktualisieren",
+        ),
+        Text::Action => ("Action", "Ação", "Aktion"),
+        Text::Date => ("Date", "Data", "Datum"),
+        Text::ValuePerUnit => ("Value per unit", "Valor por unidade", "Wert pro Einheit"),
+        Text::UnrealizedGain => (
+            "Unrealized gain",
+            "Mais-valia latente",
+            "Unrealisierter Gewinn",
+        ),
+        Text::RealizedGain => (
+            "Realized gain",
+            "Mais-valia realizada",
+            "Realisierter Gewinn",
+        ),
+        Text::Income => ("Income", "Rendimentos", "Erträge"),
+        Text::TotalReturn => ("Total return", "Retorno total", "Gesamtrendite"),
+        Text::EditSecurity => ("Edit Details", "Editar Detalhes", "Details bearbeiten"),
+        Text::Dashboard => ("Dashboard", "Painel", "Übersicht"),
+        Text::TotalValue => ("Total value", "Valor total", "Gesamtwert"),
+        Text::TotalGain => ("Total gain", "Ganho total", "Gesamtgewinn"),
+        Text::TodayChange => ("Today", "Hoje", "Heute"),
+        Text::YearChange => ("Year to date", "Desde o início do ano", "Seit Jahresbeginn"),
+        Text::Cash => ("Cash", "Liquidez", "Bargeld"),
+        Text::UpdateCash => ("Update Cash", "Atualizar Liquidez", "Bargeld aktualisieren"),
+        Text::IncomeYtd => (
+            "Income this year",
+            "Rendimentos este ano",
+            "Erträge dieses Jahr",
+        ),
+        Text::AllocationBySecurity => (
+            "Allocation by security",
+            "Alocação por título",
+            "Aufteilung nach Wertpapier",
+        ),
+        Text::AllocationByAssetClass => (
+            "Allocation by asset class",
+            "Alocação por classe de ativo",
+            "Aufteilung nach Anlageklasse",
+        ),
+        Text::AllocationByCurrency => (
+            "Allocation by currency",
+            "Alocação por moeda",
+            "Aufteilung nach Währung",
+        ),
+        Text::AllocationByAccount => (
+            "Allocation by account",
+            "Alocação por conta",
+            "Aufteilung nach Konto",
+        ),
+        Text::Price => ("Price", "Preço", "Kurs"),
+        Text::ChartHint => (
+            "Scroll to zoom, drag to move, right click to show everything",
+            "Role para ampliar, arraste para mover, clique direito para ver tudo",
+            "Scrollen zum Zoomen, Ziehen zum Verschieben, Rechtsklick zeigt alles",
+        ),
+        Text::GrowthOf100 => (
+            "Growth of 100 compared to the benchmark, without deposits and withdrawals",
+            "Crescimento de 100 face ao índice de referência, sem entradas e saídas",
+            "Wachstum von 100 im Vergleich zur Benchmark, ohne Ein- und Auszahlungen",
+        ),
+        Text::Unassigned => ("Unassigned", "Sem atribuição", "Nicht zugeordnet"),
+        Text::Returns => ("Returns", "Rentabilidade", "Rendite"),
+        Text::Period => ("Period", "Período", "Zeitraum"),
+        Text::TimeWeighted => ("Time-weighted", "Ponderada no tempo", "Zeitgewichtet"),
+        Text::MoneyWeighted => (
+            "Money-weighted (XIRR)",
+            "Ponderada pelo capital (TIR)",
+            "Kapitalgewichtet (IZF)",
+        ),
+        Text::SinceInception => ("Since inception", "Desde o início", "Seit Beginn"),
+        Text::CustomPeriod => ("Custom Period", "Período Personalizado", "Eigener Zeitraum"),
+        Text::Risk => ("Risk", "Risco", "Risiko"),
+        Text::Volatility => ("Volatility", "Volatilidade", "Volatilität"),
+        Text::MaxDrawdown => ("Max. drawdown", "Perda máxima", "Max. Drawdown"),
+        Text::Sharpe => ("Sharpe", "Sharpe", "Sharpe"),
+        Text::Sortino => ("Sortino", "Sortino", "Sortino"),
+        Text::Beta => ("Beta", "Beta", "Beta"),
+        Text::Benchmark => ("Benchmark", "Referência", "Benchmark"),
+        Text::NoBenchmark => (
+            "No benchmark, pick a security or import an index",
+            "Sem referência, escolha um título ou importe um índice",
+            "Kein Benchmark, ein Wertpapier wählen oder einen Index importieren",
+        ),
+        Text::UseAsBenchmark => (
+            "Use as Benchmark",
+            "Usar como Referência",
+            "Als Benchmark verwenden",
+        ),
+        Text::RemoveBenchmark => (
+            "Remove Benchmark",
+            "Remover Referência",
+            "Benchmark entfernen",
+        ),
+        Text::ImportIndex => ("Import Index", "Importar Índice", "Index importieren"),
+        Text::TrackingDifference => (
+            "Tracking difference",
+            "Diferença face à referência",
+            "Tracking-Differenz",
+        ),
+        Text::Rebalancing => ("Rebalancing", "Rebalanceamento", "Rebalancing"),
+        Text::SetTarget => ("Set Target", "Definir Alvo", "Zielgewicht festlegen"),
+        Text::AssetClassTarget => (
+            "Asset Class Target",
+            "Alvo por Classe de Ativo",
+            "Ziel je Anlageklasse",
+        ),
+        Text::PlanRebalancing => (
+            "Plan Rebalancing",
+            "Planear Rebalanceamento",
+            "Rebalancing planen",
+        ),
+        Text::CurrentWeight => ("Current", "Atual", "Aktuell"),
+        Text::Drift => ("Drift", "Desvio", "Abweichung"),
+        Text::BuyOnly => ("Buy only", "Apenas compras", "Nur kaufen"),
+        Text::Unallocated => ("Unallocated", "Por alocar", "Nicht verteilt"),
+        Text::TargetWeight => ("Target weight (%)", "Peso alvo (%)", "Zielgewicht (%)"),
+        Text::Tolerance => ("Tolerance (%)", "Tolerância (%)", "Toleranz (%)"),
+        Text::Contribution => ("Contribution", "Contribuição", "Einzahlung"),
+        Text::MinTradeValue => ("Minimum trade", "Transação mínima", "Mindestorder"),
+        Text::FractionalShares => (
+            "Allow fractional shares",
+            "Permitir frações de ações",
+            "Bruchstücke erlauben",
+        ),
+        Text::RiskFreeRate => (
+            "Risk-free Rate (%)",
+            "Taxa sem Risco (%)",
+            "Risikofreier Zins (%)",
+        ),
+        Text::AssetClass => ("Asset Class", "Classe de Ativo", "Anlageklasse"),
+        Text::Currency => ("Currency", "Moeda", "Währung"),
+        Text::Account => ("Account", "Conta", "Konto"),
+        Text::Ticker => ("Ticker", "Ticker", "Ticker"),
+        Text::Isin => ("ISIN", "ISIN", "ISIN"),
+        Text::QuoteUrl => ("Quote URL", "URL de cotações", "Kurs-URL"),
+        Text::HistoryUrl => (
+            "Price history URL",
+            "URL do histórico de preços",
+            "Kurshistorie-URL",
+        ),
+        Text::QuoteFile => ("Quote file", "Ficheiro de cotações", "Kursdatei"),
+        Text::FetchPrices => ("Fetch Prices", "Obter Preços", "Kurse abrufen"),
+        Text::RefreshPrices => ("Refresh Prices", "Atualizar Preços", "Kurse aktualisieren"),
+        Text::RefreshingPrices => (
+            "Refreshing prices",
+            "A atualizar preços",
+            "Kurse werden aktualisiert",
+        ),
+        Text::PricesRefreshed => (
+            "Prices refreshed",
+            "Preços atualizados",
+            "Kurse aktualisiert",
+        ),
+        Text::QuotedAt => ("quoted", "cotado", "notiert"),
+        Text::AsOf => ("as of", "em", "Stand"),
+        Text::Stale => ("stale", "desatualizado", "veraltet"),
+        Text::StalePrices => (
+            "Valued with stale prices",
+            "Avaliado com preços desatualizados",
+            "Mit veralteten Kursen bewertet",
+        ),
+        Text::StalePriceDays => (
+            "Prices are stale after (days)",
+            "Preços desatualizados após (dias)",
+            "Kurse veraltet nach (Tagen)",
+        ),
+        Text::InvalidStalePriceDays => (
+            "Stale price days must be a whole number",
+            "Os dias de preço desatualizado devem ser um número inteiro",
+            "Die Tage bis zu veralteten Kursen müssen eine ganze Zahl sein",
+        ),
+        Text::Fee => ("Fee", "Comissão", "Gebühr"),
+        Text::TaxYear => ("Tax Year", "Ano Fiscal", "Steuerjahr"),
+        Text::LongTermDays => (
+            "Long-term after (days held)",
+            "Longo prazo após (dias detidos)",
+            "Langfristig nach (Tagen im Bestand)",
+        ),
+        Text::CapitalGains => (
+            "Capital Gains Report",
+            "Relatório de Mais-Valias",
+            "Veräußerungsgewinne",
+        ),
+        Text::Disposals => ("Disposals", "Alienações", "Veräußerungen"),
+        Text::Summary => ("Summary", "Resumo", "Zusammenfassung"),
+        Text::ShortTerm => ("Short-term", "Curto prazo", "Kurzfristig"),
+        Text::LongTerm => ("Long-term", "Longo prazo", "Langfristig"),
+        // {} is replaced by the number of days
+        Text::LongTermAfter => (
+            "long-term after {} days",
+            "longo prazo após {} dias",
+            "langfristig nach {} Tagen",
+        ),
+        Text::TaxWithheld => ("Tax Withheld", "Imposto Retido", "Quellensteuer"),
+        Text::Country => ("Country", "País", "Land"),
+        Text::Gross => ("Gross", "Bruto", "Brutto"),
+        Text::Net => ("Net", "Líquido", "Netto"),
+        Text::Group => ("Group", "Grupo", "Gruppe"),
+        Text::ProjectedIncome => (
+            "Projected next 12 months",
+            "Previsão para os próximos 12 meses",
+            "Erwartet in den nächsten 12 Monaten",
+        ),
+        Text::WashSaleDays => (
+            "Wash sale window (days)",
+            "Janela de venda fictícia (dias)",
+            "Wash-Sale-Zeitraum (Tage)",
+        ),
+        Text::WashSaleRule => (
+            "Wash sale rule",
+            "Regra de venda fictícia",
+            "Wash-Sale-Regel",
+        ),
+        Text::WashSales => ("Wash Sales", "Vendas Fictícias", "Wash Sales"),
+        Text::HarvestableLosses => (
+            "Harvestable Losses",
+            "Perdas Realizáveis",
+            "Realisierbare Verluste",
+        ),
+        Text::Loss => ("Loss", "Perda", "Verlust"),
+        Text::Disallowed => ("Disallowed", "Não Dedutível", "Nicht abzugsfähig"),
+        Text::Replacements => ("Replacements", "Substituições", "Ersatzkäufe"),
+        Text::RecentPurchase => (
+            "Bought within the window",
+            "Comprado dentro da janela",
+            "Im Zeitraum gekauft",
+        ),
+        Text::CostBasis => ("Cost Basis", "Custo de Aquisição", "Anschaffungskosten"),
+        Text::Acquired => ("Acquired", "Adquirido", "Erworben"),
+        Text::Disposed => ("Disposed", "Alienado", "Veräußert"),
+        Text::Proceeds => ("Proceeds", "Valor de Realização", "Veräußerungserlös"),
+        Text::Term => ("Term", "Prazo", "Haltedauer"),
+        Text::PlanAmount => ("Amount to invest", "Montante a investir", "Sparbetrag"),
+        Text::PlanQuantity => ("or Units", "ou Unidades", "oder Stück"),
+        Text::Frequency => (
+            "Frequency (monthly, quarterly, semiannually, annually)",
+            "Frequência (mensal, trimestral, semestral, anual)",
+            "Intervall (monatlich, vierteljährlich, halbjährlich, jährlich)",
+        ),
+        Text::FrequencyMonthly => ("monthly", "mensal", "monatlich"),
+        Text::FrequencyQuarterly => ("quarterly", "trimestral", "vierteljährlich"),
+        Text::FrequencySemiAnnually => ("semiannually", "semestral", "halbjährlich"),
+        Text::FrequencyAnnually => ("annually", "anual", "jährlich"),
+        Text::Yearly => ("Yearly", "Anual", "Jährlich"),
+        Text::Monthly => ("Monthly", "Mensal", "Monatlich"),
+        Text::AddToReplacementCost => (
+            "Add to replacement cost",
+            "Somar ao custo da substituição",
+            "Zu den Kosten des Ersatzkaufs addieren",
+        ),
+        Text::DisallowLoss => (
+            "Disallow loss",
+            "Não deduzir a perda",
+            "Verlust nicht anerkennen",
+        ),
+        Text::Assumptions => ("Assumptions", "Pressupostos", "Annahmen"),
+        Text::PriceHistory => ("Price history", "Histórico de preços", "Kursverlauf"),
+        Text::DayOfMonth => ("Day of Month", "Dia do Mês", "Tag im Monat"),
+        Text::SavingsPlans => ("Savings Plans", "Planos de Poupança", "Sparpläne"),
+        Text::AddSavingsPlan => (
+            "Add Savings Plan",
+            "Adicionar Plano de Poupança",
+            "Sparplan hinzufügen",
+        ),
+        Text::PendingPlanEntries => (
+            "Savings plan buys to confirm",
+            "Compras de planos por confirmar",
+            "Zu bestätigende Sparplankäufe",
+        ),
+        Text::Skip => ("Skip", "Ignorar", "Überspringen"),
+        Text::Remove => ("Remove", "Remover", "Entfernen"),
+        Text::Goals => ("Goals", "Objetivos", "Ziele"),
+        Text::AddGoal => ("Add Goal", "Adicionar Objetivo", "Ziel hinzufügen"),
+        Text::GoalName => ("Goal", "Objetivo", "Ziel"),
+        Text::TargetAmount => ("Target Amount", "Montante Alvo", "Zielbetrag"),
+        Text::TargetDate => ("Target Date", "Data Alvo", "Zieldatum"),
+        Text::Accounts => (
+            "Accounts (comma separated)",
+            "Contas (separadas por vírgulas)",
+            "Konten (durch Kommas getrennt)",
+        ),
+        Text::Securities => (
+            "Securities (comma separated)",
+            "Títulos (separados por vírgulas)",
+            "Wertpapiere (durch Kommas getrennt)",
+        ),
+        Text::AssumedReturn => (
+            "assumed return (%)",
+            "retorno assumido (%)",
+            "angenommene Rendite (%)",
+        ),
+        Text::RequiredMonthly => (
+            "Required per month",
+            "Necessário por mês",
+            "Benötigt pro Monat",
+        ),
+        Text::PlannedMonthly => ("planned per month", "planeado por mês", "geplant pro Monat"),
+        Text::ProjectedCompletion => (
+            "Reached with the plans",
+            "Atingido com os planos",
+            "Erreicht mit den Sparplänen",
+        ),
+        Text::By => ("by", "até", "bis"),
+        Text::ReportTitle => (
+            "Portfolio Report",
+            "Relatório do Portfólio",
+            "Portfoliobericht",
+        ),
+        Text::Holdings => ("Holdings", "Posições", "Bestand"),
+        Text::Allocation => ("Allocation", "Alocação", "Aufteilung"),
+        Text::Performance => ("Performance", "Desempenho", "Wertentwicklung"),
+        Text::Transactions => ("Transactions", "Transações", "Transaktionen"),
+        Text::RealizedGains => ("Realized Gains", "Ganhos Realizados", "Realisierte Gewinne"),
+        Text::Invested => ("Invested", "Investido", "Investiert"),
+        Text::Value => ("Value", "Valor", "Wert"),
+        Text::Gain => ("Gain", "Ganho", "Gewinn"),
+        Text::Weight => ("Weight", "Peso", "Gewichtung"),
+        Text::Type => ("Type", "Tipo", "Art"),
+        Text::Total => ("Total", "Total", "Summe"),
+        Text::ValueAtStart => ("Value at the start", "Valor no início", "Wert zu Beginn"),
+        Text::ValueAtEnd => ("Value at the end", "Valor no fim", "Wert am Ende"),
+        Text::PurchasesInPeriod => (
+            "Purchases in period",
+            "Compras no período",
+            "Käufe im Zeitraum",
+        ),
+        Text::SalesInPeriod => (
+            "Sales in period",
+            "Vendas no período",
+            "Verkäufe im Zeitraum",
+        ),
+        Text::IncomeInPeriod => (
+            "Income in period",
+            "Rendimentos no período",
+            "Erträge im Zeitraum",
+        ),
+        Text::RealizedGainsInPeriod => (
+            "Realized gains in period",
+            "Ganhos realizados no período",
+            "Realisierte Gewinne im Zeitraum",
+        ),
+        Text::Projection => ("Projection", "Projeção", "Prognose"),
+        Text::SetAssumption => ("Set Assumption", "Definir Pressuposto", "Annahme festlegen"),
+        Text::RunSimulation => ("Run Simulation", "Simular", "Simulieren"),
+        Text::Years => ("Years", "Anos", "Jahre"),
+        Text::Paths => ("Simulated paths", "Cenários simulados", "Simulierte Pfade"),
+        Text::Seed => ("Seed", "Semente", "Startwert"),
+        Text::AnnualVolatility => (
+            "Volatility (% a year)",
+            "Volatilidade (% ao ano)",
+            "Volatilität (% pro Jahr)",
+        ),
+        Text::Year => ("Year", "Ano", "Jahr"),
+        Text::MonthsOfHistory => (
+            "months of price history",
+            "meses de histórico de preços",
+            "Monate Kursverlauf",
+        ),
+        Text::GoalNameEmpty => (
+            "Goal name must not be empty",
+            "O nome do objetivo não pode estar vazio",
+            "Der Name des Ziels darf nicht leer sein",
+        ),
+        Text::UnknownSecurity => (
+            "Unknown security",
+            "Título desconhecido",
+            "Unbekanntes Wertpapier",
+        ),
+        Text::InvalidAssumedReturn => (
+            "Invalid assumed return",
+            "Retorno assumido inválido",
+            "Ungültige angenommene Rendite",
+        ),
+        Text::InvalidYears => (
+            "Years must be between 1 and 50",
+            "Os anos devem estar entre 1 e 50",
+            "Die Jahre müssen zwischen 1 und 50 liegen",
+        ),
+        Text::InvalidPaths => (
+            "Simulated paths must be between 1 and 10000",
+            "Os cenários simulados devem estar entre 1 e 10000",
+            "Die simulierten Pfade müssen zwischen 1 und 10000 liegen",
+        ),
+        Text::InvalidSeed => ("Invalid seed", "Semente inválida", "Ungültiger Startwert"),
+        Text::InvalidVolatility => (
+            "Invalid volatility",
+            "Volatilidade inválida",
+            "Ungültige Volatilität",
+        ),
+        Text::NoPriceHistory => (
+            "Not enough price history, at least two consecutive months are needed",
+            "Histórico de preços insuficiente, são necessários pelo menos dois meses seguidos",
+            "Zu wenig Kursverlauf, mindestens zwei aufeinanderfolgende Monate werden benötigt",
+        ),
+        Text::NoPrice => ("no price", "sem preço", "kein Kurs"),
+        Text::AmountOrQuantity => (
+            "Enter either an amount or a number of units",
+            "Indique um montante ou um número de unidades",
+            "Entweder einen Betrag oder eine Stückzahl angeben",
+        ),
+        Text::InvalidFrequency => (
+            "Invalid frequency",
+            "Frequência inválida",
+            "Ungültiges Intervall",
+        ),
+        Text::InvalidDayOfMonth => (
+            "Day of month must be between 1 and 31",
+            "O dia do mês deve estar entre 1 e 31",
+            "Der Tag im Monat muss zwischen 1 und 31 liegen",
+        ),
+        Text::InvalidWashSaleDays => (
+            "Wash sale days must be a whole number",
+            "Os dias de venda fictícia devem ser um número inteiro",
+            "Die Wash-Sale-Tage müssen eine ganze Zahl sein",
+        ),
+        Text::InvalidTaxWithheld => (
+            "Invalid tax withheld",
+            "Imposto retido inválido",
+            "Ungültige Quellensteuer",
+        ),
+        Text::InvalidFee => ("Invalid fee", "Comissão inválida", "Ungültige Gebühr"),
+        Text::UnknownAction => (
+            "Action must be BUY, SELL, DIVIDEND, INTEREST or DISTRIBUTION",
+            "A ação deve ser COMPRA, VENDA, DIVIDENDO, JUROS ou DISTRIBUIÇÃO",
+            "Die Aktion muss KAUF, VERKAUF, DIVIDENDE, ZINSEN oder AUSSCHÜTTUNG sein",
+        ),
+        Text::ActionBuy => ("BUY", "COMPRA", "KAUF"),
+        Text::ActionSell => ("SELL", "VENDA", "VERKAUF"),
+        Text::ActionDividend => ("DIVIDEND", "DIVIDENDO", "DIVIDENDE"),
+        Text::ActionInterest => ("INTEREST", "JUROS", "ZINSEN"),
+        Text::ActionDistribution => ("DISTRIBUTION", "DISTRIBUIÇÃO", "AUSSCHÜTTUNG"),
+        Text::InvalidTaxYear => (
+            "Invalid tax year",
+            "Ano fiscal inválido",
+            "Ungültiges Steuerjahr",
+        ),
+        Text::InvalidLongTermDays => (
+            "Long-term days must be a whole number",
+            "Os dias de longo prazo devem ser um número inteiro",
+            "Die Tage bis langfristig müssen eine ganze Zahl sein",
+        ),
+        Text::ImportPrices => ("Import Prices", "Importar Preços", "Kurse importieren"),
+        Text::OverwritePrices => (
+            "Replace recorded prices",
+            "Substituir preços registados",
+            "Erfasste Kurse ersetzen",
+        ),
+        Text::PricesAdded => ("added", "adicionados", "hinzugefügt"),
+        Text::PricesReplaced => ("replaced", "substituídos", "ersetzt"),
+        Text::PricesKept => ("kept", "mantidos", "beibehalten"),
+        Text::ImportFailed => (
+            "Import failed",
+            "Falha na importação",
+            "Import fehlgeschlagen",
+        ),
+        Text::OpenSecurityToImport => (
+            "Open a security to import its prices",
+            "Abra um título para importar os seus preços",
+            "Öffnen Sie ein Wertpapier, um seine Kurse zu importieren",
+        ),
+        Text::NoSecurityForColumn => (
+            "No security for the column",
+            "Nenhum título para a coluna",
+            "Kein Wertpapier für die Spalte",
+        ),
+        Text::Confirm => ("Confirm", "Confirmar", "Bestätigen"),
+        Text::Back => ("Back", "Voltar", "Zurück"),
+        Text::SecurityName => ("Security Name", "Nome do Título", "Name des Wertpapiers"),
+        Text::Amount => ("Amount", "Quantidade", "Anzahl"),
+        Text::PricePerUnit => ("Price per Unit", "Preço por Unidade", "Preis pro Einheit"),
+        Text::CurrentValue => ("Current Value", "Valor Atual", "Aktueller Wert"),
+        Text::StartDate => ("Start Date", "Data de Início", "Startdatum"),
+        Text::EndDate => ("End Date", "Data de Fim", "Enddatum"),
+        Text::BaseCurrency => ("Base Currency", "Moeda Base", "Basiswährung"),
+        Text::Password => ("Password", "Palavra-passe", "Passwort"),
+        Text::DateFormat => ("Date Format", "Formato de Data", "Datumsformat"),
+        Text::DecimalSeparator => (
+            "Decimal Separator",
+            "Separador Decimal",
+            "Dezimaltrennzeichen",
+        ),
+        Text::AutosaveInterval => (
+            "Autosave Interval (minutes)",
+            "Gravação Automática (minutos)",
+            "Autospeicherintervall (Minuten)",
+        ),
+        Text::DefaultDirectory => (
+            "Default Directory",
+            "Pasta Predefinida",
+            "Standardverzeichnis",
+        ),
+        Text::Theme => ("Theme", "Tema", "Design"),
+        Text::NumberFormat => ("Number Format", "Formato Numérico", "Zahlenformat"),
+        Text::Language => ("Language", "Idioma", "Sprache"),
+        Text::DefaultCostBasis => (
+            "Default Cost Basis",
+            "Método de Custo Predefinido",
+            "Standard-Kostenbasis",
+        ),
+        Text::ErrorCode => ("Error code", "Código de erro", "Fehlercode"),
+        Text::PasswordEmpty => (
+            "Password must not be empty",
+            "A palavra-passe não pode estar vazia",
+            "Das Passwort darf nicht leer sein",
+        ),
+        Text::InvalidAmount => ("Invalid amount", "Quantidade inválida", "Ungültige Anzahl"),
+        Text::TooManyUnits => (
+            "A security can't hold more than 255 units",
+            "Um título não pode ter mais de 255 unidades",
+            "Ein Wertpapier kann nicht mehr als 255 Stück halten",
+        ),
+        Text::InvalidPrice => ("Invalid price", "Preço inválido", "Ungültiger Preis"),
+        Text::InvalidDate => ("Invalid date", "Data inválida", "Ungültiges Datum"),
+        Text::BaseCurrencyEmpty => (
+            "Base currency must not be empty",
+            "A moeda base não pode estar vazia",
+            "Die Basiswährung darf nicht leer sein",
+        ),
+        Text::InvalidDateFormat => (
+            "Invalid date format",
+            "Formato de data inválido",
+            "Ungültiges Datumsformat",
+        ),
+        Text::InvalidDecimalSeparator => (
+            "Decimal separator must be . or ,",
+            "O separador decimal deve ser . ou ,",
+            "Das Dezimaltrennzeichen muss . oder , sein",
+        ),
+        Text::InvalidRiskFreeRate => (
+            "Invalid risk-free rate",
+            "Taxa sem risco inválida",
+            "Ungültiger risikofreier Zins",
+        ),
+        Text::InvalidWeight => (
+            "Weight must be between 0 and 100",
+            "O peso deve estar entre 0 e 100",
+            "Das Gewicht muss zwischen 0 und 100 liegen",
+        ),
+        Text::InvalidAutosaveInterval => (
+            "Autosave interval must be a whole number of minutes",
+            "O intervalo de gravação automática deve ser um número inteiro de minutos",
+            "Das Autospeicherintervall muss eine ganze Zahl von Minuten sein",
+        ),
+    };
+    match language {
+        Language::English => english,
+        Language::Portuguese => portuguese,
+        Language::German => german,
+    }
+}
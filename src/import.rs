@@ -0,0 +1,231 @@
+use std::path::Path;
+
+use chrono::NaiveDate;
+
+use crate::format::{NumberFormat, parse_date_input};
+
+#[derive(Debug)]
+pub enum ImportError {
+    Io(std::io::Error),
+    Csv(csv::Error),
+    NoPrices,
+}
+
+impl std::fmt::Display for ImportError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ImportError::Io(e) => write!(f, "{}", e),
+            ImportError::Csv(e) => write!(f, "{}", e),
+            ImportError::NoPrices => write!(f, "no dated prices found"),
+        }
+    }
+}
+
+impl From<std::io::Error> for ImportError {
+    fn from(e: std::io::Error) -> Self {
+        ImportError::Io(e)
+    }
+}
+
+impl From<csv::Error> for ImportError {
+    fn from(e: csv::Error) -> Self {
+        ImportError::Csv(e)
+    }
+}
+
+/// Prices read from a file, sorted by date.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PriceFile {
+    Single(Vec<(NaiveDate, f32)>),
+    PerSymbol(Vec<(String, Vec<(NaiveDate, f32)>)>), // column header and its prices
+}
+
+/// Reads all rows, with semicolons as the delimiter when the first line has them.
+/// The decimal separator comes with them, a point when commas delimit the values
+/// and otherwise a comma if any value has one.
+fn read_rows(data: &str) -> Result<(Vec<Vec<String>>, char), ImportError> {
+    let first_line = data.lines().next().unwrap_or_default();
+    let delimiter = if first_line.contains(';') { b';' } else { b',' };
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(delimiter)
+        .has_headers(false)
+        .flexible(true)
+        .trim(csv::Trim::All)
+        .from_reader(data.as_bytes());
+    let mut rows: Vec<Vec<String>> = Vec::new();
+    for record in reader.records() {
+        rows.push(record?.iter().map(str::to_string).collect());
+    }
+    let decimal_comma = delimiter == b';'
+        && rows
+            .iter()
+            .flat_map(|row| row.iter().skip(1))
+            .any(|cell| cell.contains(','));
+    Ok((rows, if decimal_comma { ',' } else { '.' }))
+}
+
+/// Reads either the prices of one security, with the date first and a close column
+/// (open, high, low and volume are left out) or the price last, or one column of
+/// prices per ticker under a header row. Dates are tried in the user's format first,
+/// numbers are read the way the file writes them.
+pub fn read_prices(path: &Path, date_format: &str) -> Result<PriceFile, ImportError> {
+    parse_file(&std::fs::read_to_string(path)?, date_format)
+}
+
+fn parse_file(data: &str, date_format: &str) -> Result<PriceFile, ImportError> {
+    let (rows, decimal_separator) = read_rows(data)?;
+    let number_format = NumberFormat::with_decimal_separator(decimal_separator);
+    parse_prices(
+        &rows,
+        |input| number_format.parse(input),
+        |input| parse_date_input(input, date_format),
+    )
+}
+
+fn parse_prices(
+    rows: &[Vec<String>],
+    parse_number: impl Fn(&str) -> Option<f32>,
+    parse_day: impl Fn(&str) -> Option<NaiveDate>,
+) -> Result<PriceFile, ImportError> {
+    let header = rows
+        .first()
+        .filter(|row| row.first().is_none_or(|cell| parse_day(cell).is_none()));
+    let data = &rows[header.is_some() as usize..];
+    let column = |name: &str| {
+        header.and_then(|header| {
+            header
+                .iter()
+                .position(|cell| cell.to_lowercase().replace(['_', ' '], "") == name)
+        })
+    };
+    let prices_in = |column: Option<usize>| {
+        let mut prices: Vec<(NaiveDate, f32)> = data
+            .iter()
+            .filter_map(|row| {
+                let day = parse_day(row.first()?)?;
+                // Without a header date, open, high, low, close and volume are common
+                let cell = match column {
+                    Some(column) => row.get(column)?,
+                    None if row.len() == 6 => row.get(4)?,
+                    None => row.iter().skip(1).next_back()?,
+                };
+                Some((day, parse_number(cell)?))
+            })
+            .collect();
+        prices.sort_by_key(|(day, _)| *day);
+        prices.dedup_by_key(|(day, _)| *day);
+        prices
+    };
+
+    let close = column("close").or_else(|| column("price"));
+    let columns = header.map_or(0, Vec::len);
+    let file = if close.is_some() || columns <= 2 {
+        PriceFile::Single(prices_in(close))
+    } else {
+        let header = header.map(Vec::as_slice).unwrap_or_default();
+        PriceFile::PerSymbol(
+            header
+                .iter()
+                .enumerate()
+                .skip(1)
+                .map(|(i, symbol)| (symbol.trim().to_string(), prices_in(Some(i))))
+                .filter(|(_, prices)| !prices.is_empty())
+                .collect(),
+        )
+    };
+    match &file {
+        PriceFile::Single(prices) if prices.is_empty() => Err(ImportError::NoPrices),
+        PriceFile::PerSymbol(columns) if columns.is_empty() => Err(ImportError::NoPrices),
+        _ => Ok(file),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    fn parse(lines: &[&str]) -> Result<PriceFile, ImportError> {
+        let rows: Vec<Vec<String>> = lines
+            .iter()
+            .map(|line| line.split(',').map(str::to_string).collect())
+            .collect();
+        parse_prices(&rows, |input| input.parse().ok(), crate::format::parse_date)
+    }
+
+    #[test]
+    fn ohlc_files_use_the_close() {
+        let file = parse(&[
+            "Date,Open,High,Low,Close,Adj Close,Volume",
+            "2024-01-03,10,12,9,11,10.9,1000",
+            "2024-01-02,9,10,8,10,9.9,2000",
+        ])
+        .unwrap();
+        assert_eq!(
+            file,
+            PriceFile::Single(vec![(date(2024, 1, 2), 10.0), (date(2024, 1, 3), 11.0)])
+        );
+    }
+
+    #[test]
+    fn files_without_header_use_the_last_column() {
+        let file = parse(&["2024-01-02,5.5", "2024-01-03,not a price", "2024-01-04,6"]).unwrap();
+        assert_eq!(
+            file,
+            PriceFile::Single(vec![(date(2024, 1, 2), 5.5), (date(2024, 1, 4), 6.0)])
+        );
+    }
+
+    #[test]
+    fn files_without_header_skip_the_volume() {
+        let file = parse(&["2024-01-02,9,10,8,9.5,120000"]).unwrap();
+        assert_eq!(file, PriceFile::Single(vec![(date(2024, 1, 2), 9.5)]));
+    }
+
+    #[test]
+    fn numbers_are_read_the_way_the_file_writes_them() {
+        // Whatever the user's decimal separator is
+        let file = parse_file("Date,Close\n2024-01-02,105.125\n", "%Y-%m-%d").unwrap();
+        assert_eq!(file, PriceFile::Single(vec![(date(2024, 1, 2), 105.125)]));
+        let file = parse_file("Date;Close\n2024-01-02;1.105,125\n", "%Y-%m-%d").unwrap();
+        assert_eq!(file, PriceFile::Single(vec![(date(2024, 1, 2), 1105.125)]));
+        let file = parse_file("Date;Close\n2024-01-02;105.5\n", "%Y-%m-%d").unwrap();
+        assert_eq!(file, PriceFile::Single(vec![(date(2024, 1, 2), 105.5)]));
+    }
+
+    #[test]
+    fn dates_in_the_users_format_come_first() {
+        let file = parse_file("Date,Close\n01/02/2024,10\n", "%m/%d/%Y").unwrap();
+        assert_eq!(file, PriceFile::Single(vec![(date(2024, 1, 2), 10.0)]));
+        let file = parse_file("Date,Close\n01/02/2024,10\n", "%d/%m/%Y").unwrap();
+        assert_eq!(file, PriceFile::Single(vec![(date(2024, 2, 1), 10.0)]));
+    }
+
+    #[test]
+    fn one_column_per_ticker() {
+        let file = parse(&[
+            "Date,VWCE,EUNL,EMPTY",
+            "2024-01-02,100,80,",
+            "2024-01-03,101,,",
+        ])
+        .unwrap();
+        assert_eq!(
+            file,
+            PriceFile::PerSymbol(vec![
+                (
+                    "VWCE".to_string(),
+                    vec![(date(2024, 1, 2), 100.0), (date(2024, 1, 3), 101.0)]
+                ),
+                ("EUNL".to_string(), vec![(date(2024, 1, 2), 80.0)]),
+            ])
+        );
+    }
+
+    #[test]
+    fn files_without_prices_are_rejected() {
+        assert!(matches!(parse(&["Date,Close"]), Err(ImportError::NoPrices)));
+    }
+}
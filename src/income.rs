@@ -0,0 +1,241 @@
+use std::collections::BTreeMap;
+
+use chrono::{Duration, NaiveDate};
+
+use crate::format::parse_date;
+use crate::i18n::Text;
+use crate::security::Security;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum IncomeGrouping {
+    #[default]
+    Security,
+    Currency,
+    Country,
+}
+
+impl IncomeGrouping {
+    pub const ALL: [IncomeGrouping; 3] = [
+        IncomeGrouping::Security,
+        IncomeGrouping::Currency,
+        IncomeGrouping::Country,
+    ];
+
+    pub fn label(&self) -> Text {
+        match self {
+            IncomeGrouping::Security => Text::Security,
+            IncomeGrouping::Currency => Text::Currency,
+            IncomeGrouping::Country => Text::Country,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum IncomeInterval {
+    #[default]
+    Year,
+    Month,
+}
+
+impl IncomeInterval {
+    pub const ALL: [IncomeInterval; 2] = [IncomeInterval::Year, IncomeInterval::Month];
+
+    fn period(self, date: NaiveDate) -> String {
+        match self {
+            IncomeInterval::Year => date.format("%Y").to_string(),
+            IncomeInterval::Month => date.format("%Y-%m").to_string(),
+        }
+    }
+
+    pub fn label(&self) -> Text {
+        match self {
+            IncomeInterval::Year => Text::Yearly,
+            IncomeInterval::Month => Text::Monthly,
+        }
+    }
+}
+
+/// Income of one group within one year or month.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IncomeRow {
+    pub period: String, // "2024" or "2024-03"
+    pub group: String,  // empty when the currency or country isn't known
+    pub gross: f32,
+    pub tax_withheld: f32,
+}
+
+impl IncomeRow {
+    pub fn net(&self) -> f32 {
+        self.gross - self.tax_withheld
+    }
+}
+
+/// Income of the next twelve months of one security.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Projection {
+    pub security_id: u8,
+    pub gross: f32,
+    pub tax_withheld: f32,
+}
+
+impl Projection {
+    pub fn net(&self) -> f32 {
+        self.gross - self.tax_withheld
+    }
+}
+
+/// Income by period and group, sorted by both. Securities without a currency
+/// are counted in the base currency, amounts aren't converted.
+pub fn summarize(
+    securities: &[Security],
+    grouping: IncomeGrouping,
+    interval: IncomeInterval,
+    base_currency: &str,
+) -> Vec<IncomeRow> {
+    let mut totals: BTreeMap<(String, String), (f32, f32)> = BTreeMap::new();
+    for security in securities.iter() {
+        let group = match grouping {
+            IncomeGrouping::Security => security.name.clone(),
+            IncomeGrouping::Currency if security.currency.is_empty() => base_currency.to_string(),
+            IncomeGrouping::Currency => security.currency.clone(),
+            IncomeGrouping::Country => security.country.clone(),
+        };
+        for payment in security.get_income_payments() {
+            let Some(date) = parse_date(&payment.date) else {
+                continue;
+            };
+            let total = totals
+                .entry((interval.period(date), group.clone()))
+                .or_default();
+            total.0 += payment.gross();
+            total.1 += payment.tax_withheld;
+        }
+    }
+    totals
+        .into_iter()
+        .map(|((period, group), (gross, tax_withheld))| IncomeRow {
+            period,
+            group,
+            gross,
+            tax_withheld,
+        })
+        .collect()
+}
+
+/// Expects every payment of the last twelve months to be paid again on the
+/// units held now, with the same share of tax withheld.
+pub fn project(securities: &[Security], today: NaiveDate) -> Vec<Projection> {
+    let since = today - Duration::days(365);
+    securities
+        .iter()
+        .filter_map(|security| {
+            let units = security.get_quantity() as f32;
+            let (gross, tax_withheld) = security
+                .get_income_payments()
+                .iter()
+                .filter(|payment| {
+                    parse_date(&payment.date).is_some_and(|day| day > since && day <= today)
+                })
+                .fold((0.0, 0.0), |(gross, tax_withheld), payment| {
+                    let expected = payment.per_unit * units;
+                    let withheld_share = if payment.gross() > 0.0 {
+                        payment.tax_withheld / payment.gross()
+                    } else {
+                        0.0
+                    };
+                    (gross + expected, tax_withheld + expected * withheld_share)
+                });
+            (gross > 0.0).then_some(Projection {
+                security_id: security.id,
+                gross,
+                tax_withheld,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::security::Action;
+    use crate::security::test_support::{date, security};
+
+    fn taxed_in(country: &str, entries: &[(Action, &str, u8, f32, f32, f32)]) -> Security {
+        let mut security = security(entries);
+        security.country = country.to_string();
+        security
+    }
+
+    #[test]
+    fn income_by_country_and_year() {
+        let securities = [
+            taxed_in(
+                "US",
+                &[
+                    (Action::Buy, "2023-01-02", 10, 100.0, 0.0, 0.0),
+                    (Action::Dividend, "2023-06-01", 10, 1.0, 0.0, 1.5),
+                    (Action::Dividend, "2024-06-01", 10, 1.2, 0.0, 1.8),
+                ],
+            ),
+            taxed_in("US", &[(Action::Interest, "2023-12-31", 1, 20.0, 0.0, 0.0)]),
+            taxed_in(
+                "",
+                &[(Action::Distribution, "2023-03-01", 5, 2.0, 0.0, 0.0)],
+            ),
+        ];
+        let rows = summarize(
+            &securities,
+            IncomeGrouping::Country,
+            IncomeInterval::Year,
+            "EUR",
+        );
+        let row = |period: &str, group: &str| {
+            rows.iter()
+                .find(|row| row.period == period && row.group == group)
+                .unwrap()
+        };
+        assert_eq!(rows.len(), 3);
+        assert_eq!(row("2023", "US").gross, 30.0);
+        assert_eq!(row("2023", "US").net(), 28.5);
+        assert_eq!(row("2023", "").gross, 10.0);
+        assert_eq!(row("2024", "US").tax_withheld, 1.8);
+    }
+
+    #[test]
+    fn monthly_income_by_currency() {
+        let mut usd = taxed_in("", &[(Action::Dividend, "2024-03-15", 2, 5.0, 0.0, 0.0)]);
+        usd.currency = "USD".to_string();
+        let eur = taxed_in("", &[(Action::Dividend, "2024-03-20", 1, 3.0, 0.0, 0.0)]);
+        let rows = summarize(
+            &[usd, eur],
+            IncomeGrouping::Currency,
+            IncomeInterval::Month,
+            "EUR",
+        );
+        assert_eq!(
+            rows.iter()
+                .map(|row| (row.period.as_str(), row.group.as_str(), row.gross))
+                .collect::<Vec<_>>(),
+            vec![("2024-03", "EUR", 3.0), ("2024-03", "USD", 10.0)]
+        );
+    }
+
+    #[test]
+    fn projection_uses_the_units_held_now() {
+        let securities = [taxed_in(
+            "",
+            &[
+                (Action::Buy, "2023-01-02", 10, 100.0, 0.0, 0.0),
+                (Action::Dividend, "2023-03-01", 10, 9.0, 0.0, 0.0),
+                (Action::Dividend, "2024-03-01", 10, 1.0, 0.0, 2.5),
+                (Action::Dividend, "2024-09-01", 10, 1.0, 0.0, 2.5),
+                (Action::Buy, "2024-10-01", 10, 100.0, 0.0, 0.0),
+            ],
+        )];
+        // Only the two payments of the last year, on 20 units now
+        let projection = project(&securities, date(2024, 12, 31));
+        assert_eq!(projection.len(), 1);
+        assert_eq!(projection[0].gross, 40.0);
+        assert_eq!(projection[0].net(), 30.0);
+    }
+}
@@ -1,12 +1,36 @@
+use chrono::Datelike;
 use iced::Alignment::Start;
 use iced::widget::{
-    Column, button, column, container, row, rule, scrollable, space, stack, text, text_input,
+    Column, button, canvas, checkbox, column, container, pick_list, progress_bar, row, rule,
+    scrollable, space, stack, text, text_input,
 };
-use iced::{Border, Color, Element, Fill, Length, Shadow, Task, Theme};
+use iced::{Border, Color, Element, Fill, Length, Shadow, Subscription, Task, Theme};
 use rfd::FileDialog;
 use serde::{Deserialize, Serialize};
 
+use i18n::Text;
+
+mod benchmark;
+mod chart;
+mod crypto;
+mod export;
+mod format;
+mod goals;
+mod i18n;
+mod import;
+mod income;
+mod price;
+mod rebalance;
+mod report;
+mod returns;
+mod risk;
+mod savings_plan;
 mod security;
+mod settings;
+mod simulation;
+mod storage;
+mod tax;
+mod wash_sale;
 
 #[derive(Debug, Clone, Default)]
 enum Screen {
@@ -29,14 +53,184 @@ enum Message {
     AddSecurity(String),
     OpenSecurity(u8),
     OpenEntryInput,
-    AddEntry(String, String, String),
-    NewInput(String, String),
+    AddEntry(String, String, String, String, String, String),
+    NewInput(InputField, String),
     OpenUpdateCurrentValue,
     UpdateCurrentValue(String),
+    ExportHoldings,
+    ExportTransactions,
+    OpenReportInput,
+    GenerateReport(String, String),
+    OpenCapitalGainsInput,
+    ExportCapitalGains(String),
+    SelectIncomeGrouping(income::IncomeGrouping),
+    OpenGoalInput,
+    AddGoal(String, String, String, String, String, String),
+    RemoveGoal(usize),
+    OpenSimulationInput,
+    RunSimulation(String, String, String, String),
+    SelectReturnModel(simulation::ReturnModel),
+    OpenAssumptionInput,
+    SetAssumption(String, String, String),
+    RemoveAssumption(usize),
+    OpenSavingsPlanInput,
+    AddSavingsPlan(String, String, String, String, String, String),
+    RemoveSavingsPlan(usize),
+    ConfirmPlannedEntry(u8, usize, chrono::NaiveDate),
+    SkipPlannedEntry(u8, usize, chrono::NaiveDate),
+    SelectIncomeInterval(income::IncomeInterval),
+    SubmitPassword,
+    OpenDashboard,
+    OpenSecurityDetailsInput,
+    EditSecurity(String, String, String, String, String, String),
+    OpenCashInput,
+    UpdateCash(String),
+    OpenReturnsPeriodInput,
+    SetReturnsPeriod(String, String),
+    SetBenchmark(Option<benchmark::Benchmark>),
+    SelectBenchmark(benchmark::BenchmarkOption),
+    ImportBenchmark,
+    ImportPrices,
+    ToggleOverwritePrices(bool),
+    FetchPrices,
+    RefreshAllPrices,
+    PricesFetched(u8, Result<price::PriceUpdate, price::PriceError>),
+    OpenTargetInput,
+    SetTarget(String, String),
+    OpenAssetClassTargetInput,
+    SetAssetClassTarget(String, String, String),
+    OpenRebalanceInput,
+    PlanRebalance(String, String),
+    ToggleBuyOnly(bool),
+    ToggleFractionalShares(bool),
+    OpenRecentFile(std::path::PathBuf),
+    ToggleReopenLast(bool),
+    SelectTheme(settings::ThemeChoice),
+    SelectLocale(format::Locale),
+    SelectLanguage(i18n::Language),
+    SelectCostBasisMethod(security::CostBasisMethod),
+    SelectWashSaleRule(wash_sale::WashSaleRule),
+    SaveSettings,
+    CloseSettings,
+    Autosave,
+}
+
+// Identifies a pop up field, independently of the label shown in the current language
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum InputField {
+    SecurityName,
+    Action,
+    Date,
+    Amount,
+    PricePerUnit,
+    CurrentValue,
+    StartDate,
+    EndDate,
+    Password,
+    BaseCurrency,
+    DateFormat,
+    DecimalSeparator,
+    AutosaveInterval,
+    DefaultDirectory,
+    RiskFreeRate,
+    AssetClass,
+    Currency,
+    Account,
+    Ticker,
+    Isin,
+    StalePriceDays,
+    QuoteUrl,
+    HistoryUrl,
+    QuoteFile,
+    Cash,
+    TargetWeight,
+    Tolerance,
+    Contribution,
+    MinTradeValue,
+    Fee,
+    TaxYear,
+    LongTermDays,
+    TaxWithheld,
+    Country,
+    WashSaleDays,
+    PlanAmount,
+    PlanQuantity,
+    Frequency,
+    DayOfMonth,
+    GoalName,
+    TargetAmount,
+    TargetDate,
+    Accounts,
+    Securities,
+    AssumedReturn,
+    Years,
+    Paths,
+    Seed,
+    Volatility,
+}
+
+impl InputField {
+    fn label(self) -> Text {
+        match self {
+            InputField::SecurityName => Text::SecurityName,
+            InputField::Action => Text::Action,
+            InputField::Date => Text::Date,
+            InputField::Amount => Text::Amount,
+            InputField::PricePerUnit => Text::PricePerUnit,
+            InputField::CurrentValue => Text::CurrentValue,
+            InputField::StartDate => Text::StartDate,
+            InputField::EndDate => Text::EndDate,
+            InputField::BaseCurrency => Text::BaseCurrency,
+            InputField::Password => Text::Password,
+            InputField::DateFormat => Text::DateFormat,
+            InputField::DecimalSeparator => Text::DecimalSeparator,
+            InputField::AutosaveInterval => Text::AutosaveInterval,
+            InputField::DefaultDirectory => Text::DefaultDirectory,
+            InputField::RiskFreeRate => Text::RiskFreeRate,
+            InputField::AssetClass => Text::AssetClass,
+            InputField::Currency => Text::Currency,
+            InputField::Account => Text::Account,
+            InputField::Ticker => Text::Ticker,
+            InputField::Isin => Text::Isin,
+            InputField::StalePriceDays => Text::StalePriceDays,
+            InputField::QuoteUrl => Text::QuoteUrl,
+            InputField::HistoryUrl => Text::HistoryUrl,
+            InputField::QuoteFile => Text::QuoteFile,
+            InputField::Cash => Text::Cash,
+            InputField::TargetWeight => Text::TargetWeight,
+            InputField::Tolerance => Text::Tolerance,
+            InputField::Contribution => Text::Contribution,
+            InputField::MinTradeValue => Text::MinTradeValue,
+            InputField::Fee => Text::Fee,
+            InputField::TaxYear => Text::TaxYear,
+            InputField::LongTermDays => Text::LongTermDays,
+            InputField::TaxWithheld => Text::TaxWithheld,
+            InputField::Country => Text::Country,
+            InputField::WashSaleDays => Text::WashSaleDays,
+            InputField::PlanAmount => Text::PlanAmount,
+            InputField::PlanQuantity => Text::PlanQuantity,
+            InputField::Frequency => Text::Frequency,
+            InputField::DayOfMonth => Text::DayOfMonth,
+            InputField::GoalName => Text::GoalName,
+            InputField::TargetAmount => Text::TargetAmount,
+            InputField::TargetDate => Text::TargetDate,
+            InputField::Accounts => Text::Accounts,
+            InputField::Securities => Text::Securities,
+            InputField::AssumedReturn => Text::AssumedReturn,
+            InputField::Years => Text::Years,
+            InputField::Paths => Text::Paths,
+            InputField::Seed => Text::Seed,
+            InputField::Volatility => Text::AnnualVolatility,
+        }
+    }
 }
 
 pub fn main() -> iced::Result {
-    iced::application(App::new, App::update, App::view).run() //.run_with(App::new)
+    iced::application(App::new, App::update, App::view)
+        .title(App::title)
+        .theme(App::theme)
+        .subscription(App::subscription)
+        .run()
 }
 
 #[derive(Default, Debug)]
@@ -44,97 +238,515 @@ struct App {
     current_screen: Screen,
     main_menu: MainMenu,
     overview: Overview,
-    inputs_config: [Vec<(String, String)>; 3],
+    inputs_config: [Vec<(InputField, String)>; 17],
     current_input: Option<usize>,
     current_file_path: Option<std::path::PathBuf>,
+    current_password: Option<String>, // set when the current file is encrypted
+    unsaved_changes: bool,
+    pending_file: Option<PendingFile>,
+    pop_up_error: Option<String>,
+    status: Option<Result<String, String>>, // outcome of the last export or import, shown below the toolbar
+    price_refresh: Option<price::RefreshProgress>,
+    settings: settings::Settings,
+}
+
+// A file operation waiting for the password pop up
+#[derive(Debug)]
+enum PendingFile {
+    Load(std::path::PathBuf),
+    Save(std::path::PathBuf),
 }
 
 impl App {
-    fn title() -> String {
-        "Portfolio".to_string()
+    fn title(&self) -> String {
+        let title = self.tr(Text::Portfolio);
+        match &self.current_file_path {
+            Some(path) => format!(
+                "{} - {}{}",
+                title,
+                path.file_name().unwrap_or_default().to_string_lossy(),
+                if self.unsaved_changes { "*" } else { "" }
+            ),
+            None => title.to_string(),
+        }
+    }
+
+    fn tr(&self, text: Text) -> &'static str {
+        i18n::tr(self.settings.language, text)
     }
 
     fn new() -> (Self, Task<Message>) {
-        (
-            Self {
-                current_screen: Screen::MainMenu,
-                main_menu: MainMenu::new(),
-                overview: Overview::new(),
-                inputs_config: [
-                    vec![("Security Name".to_string(), String::new())],
-                    vec![
-                        ("Date".to_string(), String::new()),
-                        ("Amount".to_string(), String::new()),
-                        ("Price per Unit".to_string(), String::new()),
-                    ],
-                    vec![("Current Value".to_string(), String::new())],
+        let mut settings = settings::Settings::load();
+        if settings.prune_recent_files() {
+            settings.save();
+        }
+        let mut app = Self {
+            current_screen: Screen::MainMenu,
+            main_menu: MainMenu::new(),
+            overview: Overview::new(),
+            inputs_config: [
+                vec![(InputField::SecurityName, String::new())],
+                vec![
+                    (InputField::Action, String::new()),
+                    (InputField::Date, String::new()),
+                    (InputField::Amount, String::new()),
+                    (InputField::PricePerUnit, String::new()),
+                    (InputField::Fee, String::new()),
+                    (InputField::TaxWithheld, String::new()),
                 ],
-                current_input: None,
-                current_file_path: None,
-            },
-            Task::none(),
-        )
+                vec![(InputField::CurrentValue, String::new())],
+                vec![
+                    (InputField::StartDate, String::new()),
+                    (InputField::EndDate, String::new()),
+                ],
+                vec![(InputField::Password, String::new())],
+                vec![
+                    (InputField::BaseCurrency, String::new()),
+                    (InputField::DateFormat, String::new()),
+                    (InputField::DecimalSeparator, String::new()),
+                    (InputField::AutosaveInterval, String::new()),
+                    (InputField::DefaultDirectory, String::new()),
+                    (InputField::RiskFreeRate, String::new()),
+                    (InputField::StalePriceDays, String::new()),
+                    (InputField::LongTermDays, String::new()),
+                    (InputField::WashSaleDays, String::new()),
+                    (InputField::QuoteUrl, String::new()),
+                    (InputField::HistoryUrl, String::new()),
+                    (InputField::QuoteFile, String::new()),
+                ],
+                vec![
+                    (InputField::AssetClass, String::new()),
+                    (InputField::Currency, String::new()),
+                    (InputField::Account, String::new()),
+                    (InputField::Ticker, String::new()),
+                    (InputField::Isin, String::new()),
+                    (InputField::Country, String::new()),
+                ],
+                vec![(InputField::Cash, String::new())],
+                vec![
+                    (InputField::StartDate, String::new()),
+                    (InputField::EndDate, String::new()),
+                ],
+                vec![
+                    (InputField::TargetWeight, String::new()),
+                    (InputField::Tolerance, String::new()),
+                ],
+                vec![
+                    (InputField::AssetClass, String::new()),
+                    (InputField::TargetWeight, String::new()),
+                    (InputField::Tolerance, String::new()),
+                ],
+                vec![
+                    (InputField::Contribution, String::new()),
+                    (InputField::MinTradeValue, String::new()),
+                ],
+                vec![(InputField::TaxYear, String::new())],
+                vec![
+                    (InputField::PlanAmount, String::new()),
+                    (InputField::PlanQuantity, String::new()),
+                    (InputField::Frequency, String::new()),
+                    (InputField::StartDate, String::new()),
+                    (InputField::EndDate, String::new()),
+                    (InputField::DayOfMonth, String::new()),
+                ],
+                vec![
+                    (InputField::GoalName, String::new()),
+                    (InputField::TargetAmount, String::new()),
+                    (InputField::TargetDate, String::new()),
+                    (InputField::Accounts, String::new()),
+                    (InputField::Securities, String::new()),
+                    (InputField::AssumedReturn, String::new()),
+                ],
+                vec![
+                    (InputField::Years, String::new()),
+                    (InputField::Paths, String::new()),
+                    (InputField::Seed, String::new()),
+                    (InputField::Contribution, String::new()),
+                ],
+                vec![
+                    (InputField::AssetClass, String::new()),
+                    (InputField::AssumedReturn, String::new()),
+                    (InputField::Volatility, String::new()),
+                ],
+            ],
+            current_input: None,
+            current_file_path: None,
+            current_password: None,
+            unsaved_changes: false,
+            pending_file: None,
+            pop_up_error: None,
+            status: None,
+            price_refresh: None,
+            settings,
+        };
+        if app.settings.reopen_last
+            && let Some(path) = app.settings.recent_files.first().cloned()
+        {
+            app.open_file(path);
+        }
+        (app, Task::none())
+    }
+
+    fn theme(&self) -> Theme {
+        self.settings.theme.to_theme()
+    }
+
+    fn subscription(&self) -> Subscription<Message> {
+        if self.settings.autosave_minutes > 0 {
+            iced::time::every(std::time::Duration::from_secs(
+                self.settings.autosave_minutes as u64 * 60,
+            ))
+            .map(|_| Message::Autosave)
+        } else {
+            Subscription::none()
+        }
     }
 
     fn update(&mut self, mut message: Message) -> Task<Message> {
         println!("Message: {:#?}", message);
+        if matches!(
+            message,
+            Message::OpenSecurityNameInput
+                | Message::OpenEntryInput
+                | Message::OpenUpdateCurrentValue
+                | Message::OpenReportInput
+                | Message::OpenSecurityDetailsInput
+                | Message::OpenCashInput
+                | Message::OpenReturnsPeriodInput
+                | Message::OpenTargetInput
+                | Message::OpenAssetClassTargetInput
+                | Message::OpenRebalanceInput
+                | Message::OpenCapitalGainsInput
+                | Message::OpenSavingsPlanInput
+                | Message::OpenGoalInput
+                | Message::OpenSimulationInput
+                | Message::OpenAssumptionInput
+        ) {
+            self.pop_up_error = None;
+        }
         match &message {
+            Message::NewPortfolio => {
+                self.overview = Overview::new();
+                self.overview.cost_basis_method = self.settings.cost_basis_method;
+                self.current_file_path = None;
+                self.current_password = None;
+                self.unsaved_changes = false;
+            }
             Message::SavePortfolio => {
                 // Save to current file, or open dialog if no file is set
-                if let Some(path) = &self.current_file_path {
+                if let Some(path) = self.current_file_path.clone() {
                     // Save to existing file
-                    match serde_json::to_string_pretty(&self.overview) {
-                        Ok(json) => match std::fs::write(path, json) {
-                            Ok(_) => println!("Saved to {:?}", path),
-                            Err(e) => println!("Failed to save: {}", e),
-                        },
-                        Err(e) => println!("Failed to serialize: {}", e),
-                    }
+                    self.write_file(&path);
                 } else {
                     return self.update(Message::SavePortfolioAs);
                 }
             }
             Message::SavePortfolioAs => {
-                if let Some(path) = FileDialog::new()
+                if let Some(path) = self
+                    .file_dialog()
                     .set_file_name("portfolio.json")
                     .add_filter("JSON", &["json"])
+                    .add_filter("Encrypted Portfolio", &[crypto::FILE_EXTENSION])
+                    .add_filter("SQLite Database", &[storage::SQLITE_EXTENSION])
                     .save_file()
                 {
-                    // Serialize and save
-                    match serde_json::to_string_pretty(&self.overview) {
-                        Ok(json) => match std::fs::write(&path, json) {
-                            Ok(_) => println!("Saved successfully"),
-                            Err(e) => println!("Failed to save: {}", e),
-                        },
-                        Err(e) => println!("Failed to serialize: {}", e),
+                    if path
+                        .extension()
+                        .is_some_and(|e| e == crypto::FILE_EXTENSION)
+                    {
+                        // Ask for the password before writing anything
+                        self.pending_file = Some(PendingFile::Save(path));
+                        self.pop_up_error = None;
+                        self.current_input = Some(4);
+                        self.current_screen = Screen::Overview(true);
+                        return Task::none();
+                    }
+                    if storage::is_sqlite(&path) && path.exists() {
+                        // Incremental writes assume the database holds this portfolio
+                        if let Err(e) = std::fs::remove_file(&path) {
+                            println!("Failed to replace {:?}: {}", path, e);
+                            return Task::none();
+                        }
+                    }
+                    self.current_password = None;
+                    self.write_file(&path);
+                    self.remember_file(&path);
+                    self.current_file_path = Some(path);
+                }
+            }
+            Message::LoadPortfolio => {
+                self.load_file();
+                if self.pending_file.is_some() {
+                    return Task::none();
+                }
+            }
+            Message::OpenRecentFile(path) => {
+                if path.exists() {
+                    self.open_file(path.clone());
+                } else {
+                    println!("{:?} no longer exists", path);
+                    self.settings.remove_recent_file(path);
+                    self.settings.save();
+                }
+                return Task::none();
+            }
+            Message::ToggleReopenLast(reopen_last) => {
+                self.settings.reopen_last = *reopen_last;
+                self.settings.save();
+                return Task::none();
+            }
+            Message::SubmitPassword => {
+                let password = self.inputs_config[4]
+                    .iter_mut()
+                    .find(|(field, _)| *field == InputField::Password)
+                    .map(|(_, v)| std::mem::take(v))
+                    .unwrap_or_default();
+                match self.pending_file.take() {
+                    Some(PendingFile::Load(path)) => {
+                        match self.read_overview(path.clone(), Some(password)) {
+                            Ok(_) => self.current_input = None,
+                            Err(e) => {
+                                // Keep the pop up open so the password can be typed again
+                                println!("Failed to load: {}", e);
+                                self.pop_up_error = Some(e.to_string());
+                                self.pending_file = Some(PendingFile::Load(path));
+                            }
+                        }
+                    }
+                    Some(PendingFile::Save(path)) => {
+                        if password.is_empty() {
+                            self.pop_up_error = Some(self.tr(Text::PasswordEmpty).to_string());
+                            self.pending_file = Some(PendingFile::Save(path));
+                        } else {
+                            self.current_input = None;
+                            self.current_password = Some(password);
+                            self.write_file(&path);
+                            self.remember_file(&path);
+                            self.current_file_path = Some(path);
+                            self.current_screen = Screen::Overview(false);
+                        }
+                    }
+                    None => println!("No file waiting for a password"),
+                }
+                return Task::none();
+            }
+            Message::ImportBenchmark => {
+                if let Some(path) = self
+                    .file_dialog()
+                    .add_filter("CSV", &["csv", "txt"])
+                    .pick_file()
+                {
+                    match benchmark::import_csv(&path, &self.settings.date_format) {
+                        Ok(series) => {
+                            for series in series {
+                                println!("Imported {} from {:?}", series.name, path);
+                                self.overview.add_index_series(series);
+                            }
+                            self.unsaved_changes = true;
+                        }
+                        Err(e) => println!("Failed to import benchmark: {}", e),
+                    }
+                }
+                return Task::none();
+            }
+            Message::FetchPrices => {
+                if let Some(provider) = self.price_provider()
+                    && let Some(security) = self.overview.get_open_security()
+                {
+                    return fetch_prices(security, provider, self.settings.stale_price_days);
+                }
+                return Task::none();
+            }
+            Message::RefreshAllPrices => {
+                let Some(provider) = self.price_provider() else {
+                    return Task::none();
+                };
+                let ids = self.overview.securities.iter().map(|s| s.id).collect();
+                self.price_refresh = Some(price::RefreshProgress::new(ids));
+                // Every security is fetched at the same time, failures only stop their own
+                return Task::batch(self.overview.securities.iter().map(|security| {
+                    fetch_prices(security, provider.clone(), self.settings.stale_price_days)
+                }));
+            }
+            Message::PricesFetched(security_id, result) => {
+                let name = self
+                    .overview
+                    .securities
+                    .iter()
+                    .find(|s| s.id == *security_id)
+                    .map(|security| security.name.clone())
+                    .unwrap_or_default();
+                if let Some(progress) = self.price_refresh.as_mut() {
+                    progress.finish(
+                        *security_id,
+                        &name,
+                        result.as_ref().map(|_| ()).map_err(Clone::clone),
+                    );
+                }
+                if let Err(e) = result {
+                    println!("Failed to fetch prices for {}: {}", name, e);
+                    return Task::none();
+                }
+                // Applied in place, prices arriving in the background keep the screen as it is
+                if let Ok(update) = result {
+                    self.overview.apply_price_update(*security_id, update);
+                    self.unsaved_changes = true;
+                }
+                return Task::none();
+            }
+            Message::ImportPrices => {
+                if let Some(path) = self
+                    .file_dialog()
+                    .add_filter("CSV", &["csv", "txt"])
+                    .pick_file()
+                {
+                    let status = match import::read_prices(&path, &self.settings.date_format) {
+                        Ok(file) => self.overview.import_prices(file, &self.settings),
+                        Err(e) => Err(e.to_string()),
+                    };
+                    if status.is_ok() {
+                        self.unsaved_changes = true;
                     }
+                    self.status =
+                        Some(status.map_err(|e| format!("{}: {}", self.tr(Text::ImportFailed), e)));
                 }
+                return Task::none();
+            }
+            Message::ToggleOverwritePrices(overwrite) => {
+                self.overview.overwrite_prices = *overwrite;
+                return Task::none();
+            }
+            Message::ExportHoldings => {
+                if let Some(path) = self.export_dialog("holdings.csv") {
+                    let result = export::export_holdings(&self.overview, &path, &self.settings);
+                    self.set_export_status(&path, result);
+                }
+                return Task::none();
+            }
+            Message::ExportTransactions => {
+                if let Some(path) = self.export_dialog("transactions.csv") {
+                    let result = export::export_transactions(&self.overview, &path, &self.settings);
+                    self.set_export_status(&path, result);
+                }
+                return Task::none();
             }
-            Message::LoadPortfolio => self.load_file(),
             Message::NewInput(key, value) => {
                 if let Some(current_input) = self.current_input {
                     // Find and update the matching key
                     if let Some(entry) = self.inputs_config[current_input]
                         .iter_mut()
-                        .find(|(k, _)| k == key)
+                        .find(|(field, _)| field == key)
                     {
                         entry.1 = value.clone();
                     }
+                    if let Screen::Settings = self.current_screen {
+                        return Task::none();
+                    }
                 } else {
                     println!("Error in current_input");
                     self.current_screen = Screen::Error(3);
                 }
             }
+            Message::Settings => {
+                let autosave_minutes = self.settings.autosave_minutes.to_string();
+                let risk_free_rate = format::NumberFormat::new(&self.settings)
+                    .number(self.settings.risk_free_rate, 2);
+                let default_directory = self
+                    .settings
+                    .default_directory
+                    .as_ref()
+                    .map(|dir| dir.display().to_string())
+                    .unwrap_or_default();
+                let quote_file = self
+                    .settings
+                    .quotes
+                    .file
+                    .as_ref()
+                    .map(|file| file.display().to_string())
+                    .unwrap_or_default();
+                for (field, value) in self.inputs_config[5].iter_mut() {
+                    *value = match field {
+                        InputField::BaseCurrency => self.settings.base_currency.clone(),
+                        InputField::DateFormat => self.settings.date_format.clone(),
+                        InputField::DecimalSeparator => self.settings.decimal_separator.to_string(),
+                        InputField::AutosaveInterval => autosave_minutes.clone(),
+                        InputField::DefaultDirectory => default_directory.clone(),
+                        InputField::RiskFreeRate => risk_free_rate.clone(),
+                        InputField::StalePriceDays => self.settings.stale_price_days.to_string(),
+                        InputField::LongTermDays => self.settings.long_term_days.to_string(),
+                        InputField::WashSaleDays => self.settings.wash_sale_days.to_string(),
+                        InputField::QuoteUrl => self.settings.quotes.latest_url.clone(),
+                        InputField::HistoryUrl => self.settings.quotes.history_url.clone(),
+                        InputField::QuoteFile => quote_file.clone(),
+                        _ => String::new(),
+                    };
+                }
+                self.pop_up_error = None;
+                self.current_input = Some(5);
+                self.current_screen = Screen::Settings;
+                return Task::none();
+            }
+            Message::SelectTheme(theme) => {
+                self.settings.theme = *theme;
+                return Task::none();
+            }
+            Message::SelectLocale(locale) => {
+                self.settings.locale = *locale;
+                return Task::none();
+            }
+            Message::SelectLanguage(language) => {
+                self.settings.language = *language;
+                return Task::none();
+            }
+            Message::SelectCostBasisMethod(method) => {
+                self.settings.cost_basis_method = *method;
+                return Task::none();
+            }
+            Message::SelectWashSaleRule(rule) => {
+                self.settings.wash_sale_rule = *rule;
+                return Task::none();
+            }
+            Message::ToggleFractionalShares(fractional_shares) => {
+                self.settings.fractional_shares = *fractional_shares;
+                return Task::none();
+            }
+            Message::SaveSettings => {
+                match self.apply_settings_inputs() {
+                    Ok(_) => {
+                        self.settings.save();
+                        self.pop_up_error = None;
+                    }
+                    Err(e) => self.pop_up_error = Some(e),
+                }
+                return Task::none();
+            }
+            Message::CloseSettings => {
+                self.current_input = None;
+                self.current_screen = if self.current_file_path.is_some() {
+                    Screen::Overview(false)
+                } else {
+                    Screen::MainMenu
+                };
+                return Task::none();
+            }
+            Message::Autosave => {
+                if self.unsaved_changes
+                    && let Some(path) = self.current_file_path.clone()
+                {
+                    self.write_file(&path);
+                }
+                return Task::none();
+            }
             // handle "OpenSecurityNameINput, AddSecurity" Sequence
             Message::OpenSecurityNameInput => {
                 self.current_input = Some(0);
             }
             Message::AddSecurity(_) => {
                 if let Some(0) = self.current_input {
-                    // Find the "Security Name" value
+                    // Find the security name value
                     if let Some((_, name)) = self.inputs_config[0]
                         .iter()
-                        .find(|(k, _)| k == "Security Name")
+                        .find(|(field, _)| *field == InputField::SecurityName)
                     {
                         self.current_input = None;
                         message = Message::AddSecurity(name.clone());
@@ -149,31 +761,130 @@ impl App {
             }
             // handle "OpenEntryInput, AddEntry" Sequence
             Message::OpenEntryInput => {
+                // An action that can be read is shown in the UI language
+                if let Some((_, value)) = self.inputs_config[1]
+                    .iter_mut()
+                    .find(|(field, _)| *field == InputField::Action)
+                    && let Some(action) = security::Action::parse(value)
+                {
+                    *value = i18n::tr(self.settings.language, action.label()).to_string();
+                }
                 self.current_input = Some(1);
             }
-            Message::AddEntry(_, _, _) => {
+            Message::AddEntry(..) => {
                 println!("ADD ENTRY");
                 if let Some(1) = self.current_input {
                     // Helper function to find value by key
-                    let find_value = |key: &str| {
+                    let find_value = |key: InputField| {
                         self.inputs_config[1]
                             .iter()
-                            .find(|(k, _)| k == key)
+                            .find(|(field, _)| *field == key)
                             .map(|(_, v)| v.clone())
                             .unwrap_or_default()
                     };
 
-                    let date = find_value("Date");
-                    let quantity = find_value("Amount");
-                    let price = find_value("Price per Unit");
+                    let action = find_value(InputField::Action);
+                    let date = find_value(InputField::Date);
+                    let quantity = find_value(InputField::Amount);
+                    let price = find_value(InputField::PricePerUnit);
+                    let fee = find_value(InputField::Fee);
+                    let tax_withheld = find_value(InputField::TaxWithheld);
+
+                    // Keep the pop up open until the action, the date and the numbers can be read
+                    if security::Action::parse(&action).is_none() {
+                        self.pop_up_error =
+                            Some(format!("{}: {:?}", self.tr(Text::UnknownAction), action));
+                        return Task::none();
+                    }
+                    let Some(parsed_date) =
+                        format::parse_date_input(&date, &self.settings.date_format)
+                    else {
+                        self.pop_up_error =
+                            Some(format!("{}: {:?}", self.tr(Text::InvalidDate), date));
+                        return Task::none();
+                    };
+                    let Ok(units) = quantity.trim().parse::<u8>() else {
+                        self.pop_up_error =
+                            Some(format!("{}: {:?}", self.tr(Text::InvalidAmount), quantity));
+                        return Task::none();
+                    };
+                    if security::Action::parse(&action) == Some(security::Action::Buy)
+                        && self
+                            .overview
+                            .get_open_security()
+                            .is_some_and(|security| !security.can_buy(units))
+                    {
+                        self.pop_up_error = Some(self.tr(Text::TooManyUnits).to_string());
+                        return Task::none();
+                    }
+                    if format::NumberFormat::new(&self.settings)
+                        .parse(&price)
+                        .is_none()
+                    {
+                        self.pop_up_error =
+                            Some(format!("{}: {:?}", self.tr(Text::InvalidPrice), price));
+                        return Task::none();
+                    }
+
+                    if !fee.trim().is_empty()
+                        && format::NumberFormat::new(&self.settings)
+                            .parse(&fee)
+                            .is_none()
+                    {
+                        self.pop_up_error =
+                            Some(format!("{}: {:?}", self.tr(Text::InvalidFee), fee));
+                        return Task::none();
+                    }
+
+                    if !tax_withheld.trim().is_empty()
+                        && format::NumberFormat::new(&self.settings)
+                            .parse(&tax_withheld)
+                            .is_none()
+                    {
+                        self.pop_up_error = Some(format!(
+                            "{}: {:?}",
+                            self.tr(Text::InvalidTaxWithheld),
+                            tax_withheld
+                        ));
+                        return Task::none();
+                    }
 
                     self.current_input = None;
-                    message = Message::AddEntry(date, quantity, price);
+                    message = Message::AddEntry(
+                        action,
+                        format::iso_date(parsed_date),
+                        quantity,
+                        price,
+                        fee,
+                        tax_withheld,
+                    );
                 } else {
                     println!("Error in current_input");
                     self.current_screen = Screen::Error(2);
                 }
             }
+            // Confirmed from the dashboard, there is no pop up to keep open
+            Message::ConfirmPlannedEntry(security_id, plan, date) => {
+                let today = chrono::Local::now().date_naive();
+                let too_many = savings_plan::pending_entries(&self.overview.securities, today)
+                    .into_iter()
+                    .find(|entry| {
+                        entry.security_id == *security_id
+                            && entry.plan == *plan
+                            && entry.date == *date
+                    })
+                    .zip(
+                        self.overview
+                            .securities
+                            .iter()
+                            .find(|s| s.id == *security_id),
+                    )
+                    .is_some_and(|(entry, security)| !security.can_buy(entry.quantity));
+                if too_many {
+                    self.status = Some(Err(self.tr(Text::TooManyUnits).to_string()));
+                    return Task::none();
+                }
+            }
             Message::OpenUpdateCurrentValue => {
                 self.current_input = Some(2);
             }
@@ -181,15 +892,24 @@ impl App {
                 println!("Update current value");
                 if let Some(2) = self.current_input {
                     // Helper function to find value by key
-                    let find_value = |key: &str| {
+                    let find_value = |key: InputField| {
                         self.inputs_config[2]
                             .iter()
-                            .find(|(k, _)| k == key)
+                            .find(|(field, _)| *field == key)
                             .map(|(_, v)| v.clone())
                             .unwrap_or_default()
                     };
 
-                    let price = find_value("Current Value");
+                    let price = find_value(InputField::CurrentValue);
+
+                    if format::NumberFormat::new(&self.settings)
+                        .parse(&price)
+                        .is_none()
+                    {
+                        self.pop_up_error =
+                            Some(format!("{}: {:?}", self.tr(Text::InvalidPrice), price));
+                        return Task::none();
+                    }
 
                     self.current_input = None;
                     message = Message::UpdateCurrentValue(price);
@@ -198,139 +918,1288 @@ impl App {
                     self.current_screen = Screen::Error(2);
                 }
             }
-            _ => {}
-        }
-        self.current_screen = self.overview.update(message);
-        Task::none()
-    }
-
-    fn view(&self) -> Element<'_, Message> {
-        match self.current_screen {
-            Screen::MainMenu => {
-                container(column![self.view_utilities(), self.main_menu.view()]).into()
-            }
-            Screen::Overview(active_pop_up) => {
-                println!("{:#?}", self);
-                if active_pop_up {
-                    if let Some(current_input) = self.current_input {
-                        let message = match current_input {
-                            0 => Message::AddSecurity("".to_string()),
-                            1 => Message::AddEntry("".to_string(), "".to_string(), "".to_string()),
-                            2 => Message::UpdateCurrentValue("".to_string()),
-                            _ => Message::AddSecurity("".to_string()),
+            Message::OpenSecurityDetailsInput => {
+                if let Some(security) = self.overview.get_open_security() {
+                    for (field, value) in self.inputs_config[6].iter_mut() {
+                        *value = match field {
+                            InputField::AssetClass => security.asset_class.clone(),
+                            InputField::Currency => security.currency.clone(),
+                            InputField::Account => security.account.clone(),
+                            InputField::Ticker => security.ticker.clone(),
+                            InputField::Isin => security.isin.clone(),
+                            InputField::Country => security.country.clone(),
+                            _ => String::new(),
                         };
-                        println!("Activate PopUp");
-                        container(stack![
-                            column![self.view_utilities(), self.overview.view()],
-                            self.pop_up(
-                                &self.inputs_config[current_input], //reference to the HashMap
-                                message
-                            )
-                        ])
-                        .into()
-                    } else {
-                        println!("Error in current_input");
-                        text("Error").into()
                     }
+                }
+                self.current_input = Some(6);
+            }
+            Message::EditSecurity(..) => {
+                if let Some(6) = self.current_input {
+                    let find_value = |key: InputField| {
+                        self.inputs_config[6]
+                            .iter()
+                            .find(|(field, _)| *field == key)
+                            .map(|(_, v)| v.trim().to_string())
+                            .unwrap_or_default()
+                    };
+
+                    let asset_class = find_value(InputField::AssetClass);
+                    let currency = find_value(InputField::Currency).to_uppercase();
+                    let account = find_value(InputField::Account);
+                    let ticker = find_value(InputField::Ticker).to_uppercase();
+                    let isin = find_value(InputField::Isin).to_uppercase();
+                    let country = find_value(InputField::Country).to_uppercase();
+
+                    self.current_input = None;
+                    message = Message::EditSecurity(
+                        asset_class,
+                        currency,
+                        account,
+                        ticker,
+                        isin,
+                        country,
+                    );
                 } else {
-                    container(column![self.view_utilities(), self.overview.view()]).into()
+                    println!("Error in current_input");
+                    self.current_screen = Screen::Error(2);
                 }
             }
-            Screen::Settings => container(column![text("Settings!").size(50),]).into(),
-            Screen::Error(error_message) => container(column![
-                text(format!("Error code: {}", error_message.to_string())).size(50),
-            ])
-            .into(),
-        }
-    }
+            Message::OpenCashInput => {
+                self.inputs_config[7][0].1 =
+                    format::NumberFormat::new(&self.settings).number(self.overview.cash, 2);
+                self.current_input = Some(7);
+            }
+            Message::UpdateCash(_) => {
+                if let Some(7) = self.current_input {
+                    let cash = self.inputs_config[7][0].1.clone();
+                    if format::NumberFormat::new(&self.settings)
+                        .parse(&cash)
+                        .is_none()
+                    {
+                        self.pop_up_error =
+                            Some(format!("{}: {:?}", self.tr(Text::InvalidAmount), cash));
+                        return Task::none();
+                    }
 
-    fn view_utilities(&self) -> Element<'_, Message> {
-        container(row![
-            button("New").on_press(Message::NewPortfolio),
-            button("Open").on_press(Message::LoadPortfolio),
-            button("Save").on_press(Message::SavePortfolio),
-            button("Save as").on_press(Message::SavePortfolioAs),
-            button("Settings").on_press(Message::Settings),
-            button("Debug").on_press(Message::Debug),
-        ])
-        .align_x(Start)
-        .align_y(Start)
-        .style(|_: &Theme| container::Style {
-            text_color: None,
-            background: None,
-            shadow: Shadow::default(),
-            snap: false,
-            border: Border {
-                color: Color::from_rgb(0.0, 0.0, 0.0),
-                width: 2.0,
-                radius: 1.0.into(),
-            },
-        })
-        .into()
-    }
+                    self.current_input = None;
+                    message = Message::UpdateCash(cash);
+                } else {
+                    println!("Error in current_input");
+                    self.current_screen = Screen::Error(2);
+                }
+            }
+            Message::OpenReturnsPeriodInput => {
+                self.current_input = Some(8);
+            }
+            Message::SetReturnsPeriod(_, _) => {
+                if let Some(8) = self.current_input {
+                    let find_value = |key: InputField| {
+                        self.inputs_config[8]
+                            .iter()
+                            .find(|(field, _)| *field == key)
+                            .map(|(_, v)| v.clone())
+                            .unwrap_or_default()
+                    };
 
-    fn pop_up(&self, entries: &Vec<(String, String)>, message: Message) -> Element<'_, Message> {
-        let inputs: Column<_> = column(entries.iter().map(|(key, value)| {
-            let key_clone = key.clone();
-            row![
-                container(text(key.clone())),
-                container(
-                    text_input("", value)
-                        .on_input(move |new_val| { Message::NewInput(key_clone.clone(), new_val) })
-                )
-            ]
-            .spacing(10)
-            .into()
-        }));
-        container(
-            container(column![inputs, button("Confirm").on_press(message)].spacing(10))
-                .center_x(Length::Fill)
-                .center_y(Length::Fill)
-                .padding(10)
-                .height(Length::Shrink)
-                .width(Length::FillPortion(2))
-                .style(container::bordered_box),
-        )
-        .center(Fill)
-        .into()
-    }
+                    let start = find_value(InputField::StartDate);
+                    let end = find_value(InputField::EndDate);
 
-    fn load_file(&mut self) {
-        if let Some(path) = FileDialog::new().add_filter("JSON", &["json"]).pick_file() {
-            match std::fs::read_to_string(&path) {
-                Ok(json) => {
-                    match serde_json::from_str::<Overview>(&json) {
-                        // Deserialize to Overview
-                        Ok(overview) => {
-                            self.overview = overview; // Replace the current overview
-                            self.current_screen = Screen::Overview(false);
-                        }
-                        Err(e) => {
-                            println!("Failed to parse on load: {}", e);
+                    for date in [&start, &end] {
+                        if format::parse_date_input(date, &self.settings.date_format).is_none() {
+                            self.pop_up_error =
+                                Some(format!("{}: {:?}", self.tr(Text::InvalidDate), date));
+                            return Task::none();
                         }
                     }
+
+                    self.current_input = None;
+                    message = Message::SetReturnsPeriod(start, end);
+                } else {
+                    println!("Error in current_input");
+                    self.current_screen = Screen::Error(2);
                 }
-                Err(e) => {
-                    println!("Failed to read file: {}", e);
+            }
+            Message::OpenTargetInput => {
+                let number_format = format::NumberFormat::new(&self.settings);
+                let target = self.overview.open_security.and_then(|security_id| {
+                    self.overview
+                        .get_target(&rebalance::TargetGroup::Security(security_id))
+                });
+                self.inputs_config[9][0].1 = target
+                    .map(|target| number_format.number(target.weight, 2))
+                    .unwrap_or_default();
+                self.inputs_config[9][1].1 = number_format.number(
+                    target.map_or(rebalance::DEFAULT_TOLERANCE, |target| target.tolerance),
+                    2,
+                );
+                self.current_input = Some(9);
+            }
+            Message::SetTarget(_, _) => {
+                if let Some(9) = self.current_input {
+                    let weight = self.inputs_config[9][0].1.clone();
+                    let tolerance = self.inputs_config[9][1].1.clone();
+                    if let Err(e) = self.validate_target(&weight, &tolerance) {
+                        self.pop_up_error = Some(e);
+                        return Task::none();
+                    }
+
+                    self.current_input = None;
+                    message = Message::SetTarget(weight, tolerance);
+                } else {
+                    println!("Error in current_input");
+                    self.current_screen = Screen::Error(2);
                 }
             }
-        }
-    }
-}
+            Message::OpenAssetClassTargetInput => {
+                let tolerance = format::NumberFormat::new(&self.settings)
+                    .number(rebalance::DEFAULT_TOLERANCE, 2);
+                for (field, value) in self.inputs_config[10].iter_mut() {
+                    *value = match field {
+                        InputField::Tolerance => tolerance.clone(),
+                        _ => String::new(),
+                    };
+                }
+                self.current_input = Some(10);
+            }
+            Message::SetAssetClassTarget(_, _, _) => {
+                if let Some(10) = self.current_input {
+                    let find_value = |key: InputField| {
+                        self.inputs_config[10]
+                            .iter()
+                            .find(|(field, _)| *field == key)
+                            .map(|(_, v)| v.trim().to_string())
+                            .unwrap_or_default()
+                    };
 
-#[derive(Default, Debug)]
-struct MainMenu {
-    //text: String,
-}
+                    let asset_class = find_value(InputField::AssetClass);
+                    let weight = find_value(InputField::TargetWeight);
+                    let tolerance = find_value(InputField::Tolerance);
+                    if let Err(e) = self.validate_target(&weight, &tolerance) {
+                        self.pop_up_error = Some(e);
+                        return Task::none();
+                    }
 
-impl MainMenu {
-    fn new() -> Self {
-        Self {}
-    }
+                    self.current_input = None;
+                    message = Message::SetAssetClassTarget(asset_class, weight, tolerance);
+                } else {
+                    println!("Error in current_input");
+                    self.current_screen = Screen::Error(2);
+                }
+            }
+            Message::OpenRebalanceInput => {
+                self.current_input = Some(11);
+            }
+            Message::PlanRebalance(_, _) => {
+                if let Some(11) = self.current_input {
+                    let find_value = |key: InputField| {
+                        self.inputs_config[11]
+                            .iter()
+                            .find(|(field, _)| *field == key)
+                            .map(|(_, v)| v.clone())
+                            .unwrap_or_default()
+                    };
 
-    fn update(&mut self, message: Message) -> Screen {
-        match message {
-            Message::NewPortfolio => Screen::Overview(false),
+                    let contribution = find_value(InputField::Contribution);
+                    let min_trade_value = find_value(InputField::MinTradeValue);
+                    let number_format = format::NumberFormat::new(&self.settings);
+                    // Both may be left empty for nothing
+                    for value in [&contribution, &min_trade_value] {
+                        if !value.trim().is_empty()
+                            && number_format.parse(value).is_none_or(|value| value < 0.0)
+                        {
+                            self.pop_up_error =
+                                Some(format!("{}: {:?}", self.tr(Text::InvalidAmount), value));
+                            return Task::none();
+                        }
+                    }
+
+                    self.current_input = None;
+                    message = Message::PlanRebalance(contribution, min_trade_value);
+                } else {
+                    println!("Error in current_input");
+                    self.current_screen = Screen::Error(2);
+                }
+            }
+            // Without exchange rates reports are always in the base currency
+            Message::OpenReportInput => {
+                self.current_input = Some(3);
+            }
+            Message::GenerateReport(_, _) => {
+                if let Some(3) = self.current_input {
+                    let find_value = |key: InputField| {
+                        self.inputs_config[3]
+                            .iter()
+                            .find(|(field, _)| *field == key)
+                            .map(|(_, v)| v.clone())
+                            .unwrap_or_default()
+                    };
+
+                    let start = find_value(InputField::StartDate);
+                    let end = find_value(InputField::EndDate);
+
+                    // Dates are passed on as ISO, empty ones keep their defaults
+                    let mut dates = Vec::new();
+                    for input in [start, end] {
+                        if input.trim().is_empty() {
+                            dates.push(String::new());
+                            continue;
+                        }
+                        let Some(date) =
+                            format::parse_date_input(&input, &self.settings.date_format)
+                        else {
+                            self.pop_up_error =
+                                Some(format!("{}: {:?}", self.tr(Text::InvalidDate), input));
+                            return Task::none();
+                        };
+                        dates.push(format::iso_date(date));
+                    }
+                    self.current_input = None;
+                    let end = dates.pop().unwrap_or_default();
+                    let start = dates.pop().unwrap_or_default();
+                    message = Message::GenerateReport(start, end);
+                } else {
+                    println!("Error in current_input");
+                    self.current_screen = Screen::Error(2);
+                }
+            }
+            Message::OpenSavingsPlanInput => {
+                // A frequency that can be read is shown in the UI language
+                if let Some((_, value)) = self.inputs_config[13]
+                    .iter_mut()
+                    .find(|(field, _)| *field == InputField::Frequency)
+                    && let Some(frequency) = savings_plan::Frequency::parse(value)
+                {
+                    *value = i18n::tr(self.settings.language, frequency.label()).to_string();
+                }
+                self.current_input = Some(13);
+            }
+            Message::OpenGoalInput => {
+                self.current_input = Some(14);
+            }
+            Message::OpenSimulationInput => {
+                let number_format = format::NumberFormat::new(&self.settings);
+                let options = &self.overview.simulation_options;
+                // The plans until a simulation was run, then what it used
+                let contribution = if self.overview.projection.is_empty() {
+                    simulation::planned_contribution(
+                        &self.overview.securities,
+                        chrono::Local::now().date_naive(),
+                    )
+                } else {
+                    options.monthly_contribution
+                };
+                for (field, value) in self.inputs_config[15].iter_mut() {
+                    *value = match field {
+                        InputField::Years => options.years.to_string(),
+                        InputField::Paths => options.paths.to_string(),
+                        InputField::Seed => options.seed.to_string(),
+                        InputField::Contribution => number_format.number(contribution, 2),
+                        _ => String::new(),
+                    };
+                }
+                self.current_input = Some(15);
+            }
+            Message::RunSimulation(..) => {
+                if let Some(15) = self.current_input {
+                    match self.validate_simulation() {
+                        Ok(run) => {
+                            self.current_input = None;
+                            message = run;
+                        }
+                        Err(e) => {
+                            self.pop_up_error = Some(e);
+                            return Task::none();
+                        }
+                    }
+                } else {
+                    println!("Error in current_input");
+                    self.current_screen = Screen::Error(2);
+                }
+            }
+            Message::OpenAssumptionInput => {
+                self.current_input = Some(16);
+            }
+            Message::SetAssumption(..) => {
+                if let Some(16) = self.current_input {
+                    match self.validate_assumption() {
+                        Ok(assumption) => {
+                            self.current_input = None;
+                            message = assumption;
+                        }
+                        Err(e) => {
+                            self.pop_up_error = Some(e);
+                            return Task::none();
+                        }
+                    }
+                } else {
+                    println!("Error in current_input");
+                    self.current_screen = Screen::Error(2);
+                }
+            }
+            Message::AddGoal(..) => {
+                if let Some(14) = self.current_input {
+                    match self.validate_goal() {
+                        Ok(goal) => {
+                            self.current_input = None;
+                            message = goal;
+                        }
+                        Err(e) => {
+                            self.pop_up_error = Some(e);
+                            return Task::none();
+                        }
+                    }
+                } else {
+                    println!("Error in current_input");
+                    self.current_screen = Screen::Error(2);
+                }
+            }
+            Message::AddSavingsPlan(..) => {
+                if let Some(13) = self.current_input {
+                    match self.validate_savings_plan() {
+                        Ok(plan) => {
+                            self.current_input = None;
+                            message = plan;
+                        }
+                        Err(e) => {
+                            self.pop_up_error = Some(e);
+                            return Task::none();
+                        }
+                    }
+                } else {
+                    println!("Error in current_input");
+                    self.current_screen = Screen::Error(2);
+                }
+            }
+            Message::OpenCapitalGainsInput => {
+                // Usually done for the year that just ended
+                let last_year = chrono::Local::now().date_naive().year() - 1;
+                self.inputs_config[12] = vec![(InputField::TaxYear, last_year.to_string())];
+                self.current_input = Some(12);
+            }
+            Message::ExportCapitalGains(_) => {
+                if let Some(12) = self.current_input {
+                    let year = self.inputs_config[12]
+                        .iter()
+                        .find(|(field, _)| *field == InputField::TaxYear)
+                        .map(|(_, v)| v.trim().to_string())
+                        .unwrap_or_default();
+                    if year.parse::<i32>().is_err() {
+                        self.pop_up_error =
+                            Some(format!("{}: {:?}", self.tr(Text::InvalidTaxYear), year));
+                        return Task::none();
+                    }
+                    self.current_input = None;
+                    message = Message::ExportCapitalGains(year);
+                } else {
+                    println!("Error in current_input");
+                    self.current_screen = Screen::Error(2);
+                }
+            }
+            _ => {}
+        }
+        if let Message::GenerateReport(start, end) = &message {
+            self.generate_report(start, end);
+        }
+        if let Message::ExportCapitalGains(year) = &message
+            && let Ok(year) = year.parse::<i32>()
+        {
+            self.export_capital_gains(year);
+        }
+        if matches!(
+            message,
+            Message::AddSecurity(_)
+                | Message::AddEntry(..)
+                | Message::UpdateCurrentValue(_)
+                | Message::EditSecurity(..)
+                | Message::UpdateCash(_)
+                | Message::SetBenchmark(_)
+                | Message::SelectBenchmark(_)
+                | Message::SetTarget(..)
+                | Message::SetAssetClassTarget(..)
+                | Message::AddSavingsPlan(..)
+                | Message::AddGoal(..)
+                | Message::RemoveGoal(_)
+                | Message::SetAssumption(..)
+                | Message::RemoveAssumption(_)
+                | Message::RemoveSavingsPlan(_)
+                | Message::ConfirmPlannedEntry(..)
+                | Message::SkipPlannedEntry(..)
+        ) {
+            self.unsaved_changes = true;
+        }
+        self.current_screen = match self.current_screen {
+            Screen::MainMenu => self.main_menu.update(message),
+            _ => self.overview.update(message, &self.settings),
+        };
+        Task::none()
+    }
+
+    fn view(&self) -> Element<'_, Message> {
+        match self.current_screen {
+            Screen::MainMenu => container(column![
+                self.view_utilities(),
+                self.main_menu.view(&self.settings)
+            ])
+            .into(),
+            Screen::Overview(active_pop_up) => {
+                println!("{:#?}", self);
+                if active_pop_up {
+                    if let Some(current_input) = self.current_input {
+                        let message = match current_input {
+                            0 => Message::AddSecurity("".to_string()),
+                            1 => Message::AddEntry(
+                                "".to_string(),
+                                "".to_string(),
+                                "".to_string(),
+                                "".to_string(),
+                                "".to_string(),
+                                "".to_string(),
+                            ),
+                            2 => Message::UpdateCurrentValue("".to_string()),
+                            3 => Message::GenerateReport("".to_string(), "".to_string()),
+                            4 => Message::SubmitPassword,
+                            6 => Message::EditSecurity(
+                                "".to_string(),
+                                "".to_string(),
+                                "".to_string(),
+                                "".to_string(),
+                                "".to_string(),
+                                "".to_string(),
+                            ),
+                            7 => Message::UpdateCash("".to_string()),
+                            8 => Message::SetReturnsPeriod("".to_string(), "".to_string()),
+                            9 => Message::SetTarget("".to_string(), "".to_string()),
+                            10 => Message::SetAssetClassTarget(
+                                "".to_string(),
+                                "".to_string(),
+                                "".to_string(),
+                            ),
+                            11 => Message::PlanRebalance("".to_string(), "".to_string()),
+                            12 => Message::ExportCapitalGains("".to_string()),
+                            14 => Message::AddGoal(
+                                "".to_string(),
+                                "".to_string(),
+                                "".to_string(),
+                                "".to_string(),
+                                "".to_string(),
+                                "".to_string(),
+                            ),
+                            15 => Message::RunSimulation(
+                                "".to_string(),
+                                "".to_string(),
+                                "".to_string(),
+                                "".to_string(),
+                            ),
+                            16 => Message::SetAssumption(
+                                "".to_string(),
+                                "".to_string(),
+                                "".to_string(),
+                            ),
+                            13 => Message::AddSavingsPlan(
+                                "".to_string(),
+                                "".to_string(),
+                                "".to_string(),
+                                "".to_string(),
+                                "".to_string(),
+                                "".to_string(),
+                            ),
+                            _ => Message::AddSecurity("".to_string()),
+                        };
+                        println!("Activate PopUp");
+                        container(stack![
+                            column![self.view_utilities(), self.overview.view(&self.settings)],
+                            self.pop_up(
+                                &self.inputs_config[current_input], //reference to the HashMap
+                                message
+                            )
+                        ])
+                        .into()
+                    } else {
+                        println!("Error in current_input");
+                        text("Error").into()
+                    }
+                } else {
+                    container(column![
+                        self.view_utilities(),
+                        self.overview.view(&self.settings)
+                    ])
+                    .into()
+                }
+            }
+            Screen::Settings => self.view_settings(),
+            Screen::Error(error_message) => container(column![
+                text(format!("{}: {}", self.tr(Text::ErrorCode), error_message)).size(50),
+            ])
+            .into(),
+        }
+    }
+
+    fn view_utilities(&self) -> Element<'_, Message> {
+        let toolbar = container(row![
+            button(self.tr(Text::New)).on_press(Message::NewPortfolio),
+            button(self.tr(Text::Open)).on_press(Message::LoadPortfolio),
+            button(self.tr(Text::Save)).on_press(Message::SavePortfolio),
+            button(self.tr(Text::SaveAs)).on_press(Message::SavePortfolioAs),
+            button(self.tr(Text::ExportHoldings)).on_press(Message::ExportHoldings),
+            button(self.tr(Text::ExportTransactions)).on_press(Message::ExportTransactions),
+            button(self.tr(Text::RefreshPrices)).on_press(Message::RefreshAllPrices),
+            button(self.tr(Text::Settings)).on_press(Message::Settings),
+            button(self.tr(Text::Debug)).on_press(Message::Debug),
+        ])
+        .align_x(Start)
+        .align_y(Start)
+        .style(|_: &Theme| container::Style {
+            text_color: None,
+            background: None,
+            shadow: Shadow::default(),
+            snap: false,
+            border: Border {
+                color: Color::from_rgb(0.0, 0.0, 0.0),
+                width: 2.0,
+                radius: 1.0.into(),
+            },
+        });
+        let status = self.status.as_ref().map(|status| match status {
+            Ok(status) => text(status.clone()).size(12).into(),
+            Err(e) => text(e.clone())
+                .color(Color::from_rgb(0.8, 0.0, 0.0))
+                .size(12)
+                .into(),
+        });
+        let Some(progress) = &self.price_refresh else {
+            return column![toolbar].extend(status).into();
+        };
+        let refresh = if progress.is_finished() {
+            self.tr(Text::PricesRefreshed)
+        } else {
+            self.tr(Text::RefreshingPrices)
+        };
+        let failures = progress.failures.iter().map(|(name, e)| {
+            text(format!("{}: {}", name, e))
+                .color(Color::from_rgb(0.8, 0.0, 0.0))
+                .size(12)
+                .into()
+        });
+        column![
+            toolbar,
+            text(format!(
+                "{} {}/{}",
+                refresh,
+                progress.done(),
+                progress.total
+            ))
+            .size(12),
+        ]
+        .extend(failures)
+        .extend(status)
+        .into()
+    }
+
+    fn set_export_status<E: std::fmt::Display>(
+        &mut self,
+        path: &std::path::Path,
+        result: Result<(), E>,
+    ) {
+        self.status = Some(match result {
+            Ok(_) => Ok(format!("{} {}", self.tr(Text::Exported), path.display())),
+            Err(e) => Err(format!("{}: {}", self.tr(Text::ExportFailed), e)),
+        });
+    }
+
+    fn pop_up(&self, entries: &[(InputField, String)], message: Message) -> Element<'_, Message> {
+        let inputs: Column<_> = column(entries.iter().map(|(field, value)| {
+            let field = *field;
+            row![
+                container(text(self.tr(field.label()))),
+                container(
+                    text_input("", value)
+                        .secure(field == InputField::Password)
+                        .on_input(move |new_val| Message::NewInput(field, new_val))
+                )
+            ]
+            .spacing(10)
+            .into()
+        }));
+        let error = match &self.pop_up_error {
+            Some(error) => text(error.clone()).color(Color::from_rgb(0.8, 0.0, 0.0)),
+            None => text(""),
+        };
+        container(
+            container(
+                column![
+                    inputs,
+                    error,
+                    button(self.tr(Text::Confirm)).on_press(message)
+                ]
+                .spacing(10),
+            )
+            .center_x(Length::Fill)
+            .center_y(Length::Fill)
+            .padding(10)
+            .height(Length::Shrink)
+            .width(Length::FillPortion(2))
+            .style(container::bordered_box),
+        )
+        .center(Fill)
+        .into()
+    }
+
+    // CSV and spreadsheets get the plain table, HTML and PDF the report with totals
+    fn export_capital_gains(&mut self, year: i32) {
+        let gains = tax::capital_gains(
+            &self.overview.securities,
+            self.overview.cost_basis_method,
+            year,
+            self.settings.long_term_days,
+            self.settings.wash_sale_days,
+            self.settings.wash_sale_rule,
+        );
+        let Some(path) = self
+            .file_dialog()
+            .set_file_name(format!("capital_gains_{}.csv", year))
+            .add_filter("CSV", &["csv"])
+            .add_filter("PDF", &["pdf"])
+            .add_filter("Excel", &["xlsx"])
+            .add_filter("HTML", &["html"])
+            .save_file()
+        else {
+            return;
+        };
+        let extension = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .unwrap_or_default()
+            .to_lowercase();
+        let result = if matches!(extension.as_str(), "pdf" | "html" | "htm") {
+            report::Report::capital_gains(
+                &gains,
+                year,
+                &self.settings.base_currency,
+                &self.settings,
+            )
+            .write(&path)
+            .map_err(|e| e.to_string())
+        } else {
+            export::export_capital_gains(&gains, &path, &self.settings).map_err(|e| e.to_string())
+        };
+        self.set_export_status(&path, result);
+    }
+
+    // Dates are ISO, empty ones default to the beginning of the year and today
+    fn generate_report(&mut self, start: &str, end: &str) {
+        let today = chrono::Local::now().date_naive();
+        let start = if start.is_empty() {
+            today.with_ordinal(1)
+        } else {
+            format::parse_date(start)
+        };
+        let end = if end.is_empty() {
+            Some(today)
+        } else {
+            format::parse_date(end)
+        };
+        let (Some(start), Some(end)) = (start, end) else {
+            self.status = Some(Err(self.tr(Text::InvalidDate).to_string()));
+            return;
+        };
+
+        if let Some(path) = self
+            .file_dialog()
+            .set_file_name("report.html")
+            .add_filter("HTML", &["html"])
+            .add_filter("PDF", &["pdf"])
+            .save_file()
+        {
+            let period = report::ReportPeriod { start, end };
+            let transactions = self
+                .stored_transactions(period)
+                .unwrap_or_else(|| report::transactions_in(&self.overview, period));
+            let report =
+                report::Report::build(&self.overview, &transactions, period, today, &self.settings);
+            let result = report.write(&path);
+            self.set_export_status(&path, result);
+        }
+    }
+
+    // A saved SQLite portfolio can answer the period query from its date index
+    fn stored_transactions(
+        &self,
+        period: report::ReportPeriod,
+    ) -> Option<Vec<report::Transaction>> {
+        let path = self.current_file_path.as_ref()?;
+        if self.unsaved_changes || !storage::is_sqlite(path) {
+            return None;
+        }
+        match storage::SqliteStorage::open(path)
+            .and_then(|storage| storage.transactions_between(period.start, period.end))
+        {
+            Ok(transactions) => Some(transactions),
+            Err(e) => {
+                println!("Failed to query transactions: {}", e);
+                None
+            }
+        }
+    }
+
+    fn write_file(&mut self, path: &std::path::Path) {
+        match storage::open(path, self.current_password.clone())
+            .and_then(|mut storage| storage.save(&self.overview))
+        {
+            Ok(_) => {
+                println!("Saved to {:?}", path);
+                self.unsaved_changes = false;
+            }
+            Err(e) => println!("Failed to save: {}", e),
+        }
+    }
+
+    fn load_file(&mut self) {
+        if let Some(path) = self
+            .file_dialog()
+            .add_filter(
+                "Portfolio",
+                &["json", crypto::FILE_EXTENSION, storage::SQLITE_EXTENSION],
+            )
+            .pick_file()
+        {
+            self.open_file(path);
+        }
+    }
+
+    fn open_file(&mut self, path: std::path::PathBuf) {
+        if !storage::is_sqlite(&path) {
+            match std::fs::read(&path) {
+                Ok(data) if crypto::is_encrypted(&data) => {
+                    // Loaded once the password is submitted
+                    self.pending_file = Some(PendingFile::Load(path));
+                    self.pop_up_error = None;
+                    self.current_input = Some(4);
+                    self.current_screen = Screen::Overview(true);
+                    return;
+                }
+                Ok(_) => {}
+                Err(e) => {
+                    println!("Failed to read file: {}", e);
+                    return;
+                }
+            }
+        }
+        if let Err(e) = self.read_overview(path, None) {
+            println!("Failed to load: {}", e);
+        }
+    }
+
+    fn file_dialog(&self) -> FileDialog {
+        match &self.settings.default_directory {
+            Some(dir) => FileDialog::new().set_directory(dir),
+            None => FileDialog::new(),
+        }
+    }
+
+    fn export_dialog(&self, file_name: &str) -> Option<std::path::PathBuf> {
+        self.file_dialog()
+            .set_file_name(file_name)
+            .add_filter("CSV", &["csv"])
+            .add_filter("Excel Workbook", &["xlsx"])
+            .save_file()
+    }
+
+    fn view_settings(&self) -> Element<'_, Message> {
+        let inputs: Column<_> = column(self.inputs_config[5].iter().map(|(field, value)| {
+            let field = *field;
+            row![
+                text(self.tr(field.label())).width(Length::FillPortion(1)),
+                text_input("", value)
+                    .on_input(move |new_val| Message::NewInput(field, new_val))
+                    .width(Length::FillPortion(2)),
+            ]
+            .spacing(10)
+            .into()
+        }))
+        .spacing(10);
+        let error = match &self.pop_up_error {
+            Some(error) => text(error.clone()).color(Color::from_rgb(0.8, 0.0, 0.0)),
+            None => text(""),
+        };
+        container(
+            column![
+                text(self.tr(Text::Settings)).size(30),
+                inputs,
+                row![
+                    text(self.tr(Text::Language)).width(Length::FillPortion(1)),
+                    pick_list(
+                        i18n::Language::ALL,
+                        Some(self.settings.language),
+                        Message::SelectLanguage
+                    )
+                    .width(Length::FillPortion(2)),
+                ]
+                .spacing(10),
+                row![
+                    text(self.tr(Text::Theme)).width(Length::FillPortion(1)),
+                    pick_list(
+                        settings::ThemeChoice::ALL,
+                        Some(self.settings.theme),
+                        Message::SelectTheme
+                    )
+                    .width(Length::FillPortion(2)),
+                ]
+                .spacing(10),
+                row![
+                    text(self.tr(Text::NumberFormat)).width(Length::FillPortion(1)),
+                    pick_list(
+                        format::Locale::ALL,
+                        Some(self.settings.locale),
+                        Message::SelectLocale
+                    )
+                    .width(Length::FillPortion(2)),
+                ]
+                .spacing(10),
+                checkbox(self.settings.fractional_shares)
+                    .label(self.tr(Text::FractionalShares))
+                    .on_toggle(Message::ToggleFractionalShares),
+                row![
+                    text(self.tr(Text::DefaultCostBasis)).width(Length::FillPortion(1)),
+                    pick_list(
+                        security::CostBasisMethod::ALL,
+                        Some(self.settings.cost_basis_method),
+                        Message::SelectCostBasisMethod
+                    )
+                    .width(Length::FillPortion(2)),
+                ]
+                .spacing(10),
+                row![
+                    text(self.tr(Text::WashSaleRule)).width(Length::FillPortion(1)),
+                    pick_list(
+                        i18n::choices(
+                            self.settings.language,
+                            &wash_sale::WashSaleRule::ALL,
+                            wash_sale::WashSaleRule::label
+                        ),
+                        Some(i18n::Choice::new(
+                            self.settings.language,
+                            self.settings.wash_sale_rule,
+                            wash_sale::WashSaleRule::label
+                        )),
+                        |choice| Message::SelectWashSaleRule(choice.value)
+                    )
+                    .width(Length::FillPortion(2)),
+                ]
+                .spacing(10),
+                error,
+                row![
+                    button(self.tr(Text::Save)).on_press(Message::SaveSettings),
+                    button(self.tr(Text::Back)).on_press(Message::CloseSettings),
+                ]
+                .spacing(10),
+            ]
+            .spacing(15)
+            .max_width(600),
+        )
+        .padding(20)
+        .into()
+    }
+
+    // Validates the text fields of the settings screen before taking them over
+    fn apply_settings_inputs(&mut self) -> Result<(), String> {
+        let find_value = |key: InputField| {
+            self.inputs_config[5]
+                .iter()
+                .find(|(field, _)| *field == key)
+                .map(|(_, v)| v.trim().to_string())
+                .unwrap_or_default()
+        };
+
+        let base_currency = find_value(InputField::BaseCurrency);
+        if base_currency.is_empty() {
+            return Err(self.tr(Text::BaseCurrencyEmpty).to_string());
+        }
+        let date_format = find_value(InputField::DateFormat);
+        if date_format.is_empty()
+            || chrono::format::StrftimeItems::new(&date_format)
+                .any(|item| matches!(item, chrono::format::Item::Error))
+        {
+            return Err(format!(
+                "{}: {:?}",
+                self.tr(Text::InvalidDateFormat),
+                date_format
+            ));
+        }
+        let decimal_separator = match find_value(InputField::DecimalSeparator).as_str() {
+            "." => '.',
+            "," => ',',
+            other => {
+                return Err(format!(
+                    "{}: {:?}",
+                    self.tr(Text::InvalidDecimalSeparator),
+                    other
+                ));
+            }
+        };
+        let autosave_minutes = find_value(InputField::AutosaveInterval)
+            .parse::<u32>()
+            .map_err(|_| self.tr(Text::InvalidAutosaveInterval).to_string())?;
+        let stale_price_days = find_value(InputField::StalePriceDays)
+            .parse::<u32>()
+            .map_err(|_| self.tr(Text::InvalidStalePriceDays).to_string())?;
+        let long_term_days = find_value(InputField::LongTermDays)
+            .parse::<u32>()
+            .map_err(|_| self.tr(Text::InvalidLongTermDays).to_string())?;
+        let wash_sale_days = find_value(InputField::WashSaleDays)
+            .parse::<u32>()
+            .map_err(|_| self.tr(Text::InvalidWashSaleDays).to_string())?;
+        let default_directory = find_value(InputField::DefaultDirectory);
+        let risk_free_rate = find_value(InputField::RiskFreeRate);
+        let Some(risk_free_rate) = format::NumberFormat::new(&self.settings).parse(&risk_free_rate)
+        else {
+            return Err(format!(
+                "{}: {:?}",
+                self.tr(Text::InvalidRiskFreeRate),
+                risk_free_rate
+            ));
+        };
+
+        self.settings.base_currency = base_currency;
+        self.settings.date_format = date_format;
+        self.settings.decimal_separator = decimal_separator;
+        self.settings.autosave_minutes = autosave_minutes;
+        self.settings.risk_free_rate = risk_free_rate;
+        self.settings.stale_price_days = stale_price_days;
+        self.settings.long_term_days = long_term_days;
+        self.settings.wash_sale_days = wash_sale_days;
+        self.settings.default_directory = if default_directory.is_empty() {
+            None
+        } else {
+            Some(std::path::PathBuf::from(default_directory))
+        };
+        self.settings.quotes.latest_url = find_value(InputField::QuoteUrl);
+        self.settings.quotes.history_url = find_value(InputField::HistoryUrl);
+        let quote_file = find_value(InputField::QuoteFile);
+        self.settings.quotes.file = if quote_file.is_empty() {
+            None
+        } else {
+            Some(std::path::PathBuf::from(quote_file))
+        };
+        Ok(())
+    }
+
+    // An empty contribution is what the savings plans invest, the bootstrap
+    // needs at least one month of returns
+    fn validate_simulation(&self) -> Result<Message, String> {
+        let find_value = |key: InputField| {
+            self.inputs_config[15]
+                .iter()
+                .find(|(field, _)| *field == key)
+                .map(|(_, v)| v.trim().to_string())
+                .unwrap_or_default()
+        };
+        let number_format = format::NumberFormat::new(&self.settings);
+        let years = find_value(InputField::Years);
+        let paths = find_value(InputField::Paths);
+        let seed = find_value(InputField::Seed);
+        let contribution = find_value(InputField::Contribution);
+
+        if !(1..=50).contains(&years.parse::<u32>().unwrap_or(0)) {
+            return Err(format!("{}: {:?}", self.tr(Text::InvalidYears), years));
+        }
+        if !(1..=10000).contains(&paths.parse::<u32>().unwrap_or(0)) {
+            return Err(format!("{}: {:?}", self.tr(Text::InvalidPaths), paths));
+        }
+        if seed.parse::<u64>().is_err() {
+            return Err(format!("{}: {:?}", self.tr(Text::InvalidSeed), seed));
+        }
+        let contribution = if contribution.is_empty() {
+            number_format.number(
+                simulation::planned_contribution(
+                    &self.overview.securities,
+                    chrono::Local::now().date_naive(),
+                ),
+                2,
+            )
+        } else if number_format
+            .parse(&contribution)
+            .is_some_and(|amount| amount >= 0.0)
+        {
+            contribution
+        } else {
+            return Err(format!(
+                "{}: {:?}",
+                self.tr(Text::InvalidAmount),
+                contribution
+            ));
+        };
+        if self.overview.simulation_options.model == simulation::ReturnModel::Bootstrap
+            && simulation::historical_returns(&self.overview.securities).is_empty()
+        {
+            return Err(self.tr(Text::NoPriceHistory).to_string());
+        }
+        Ok(Message::RunSimulation(years, paths, seed, contribution))
+    }
+
+    fn validate_assumption(&self) -> Result<Message, String> {
+        let find_value = |key: InputField| {
+            self.inputs_config[16]
+                .iter()
+                .find(|(field, _)| *field == key)
+                .map(|(_, v)| v.trim().to_string())
+                .unwrap_or_default()
+        };
+        let number_format = format::NumberFormat::new(&self.settings);
+        let asset_class = find_value(InputField::AssetClass);
+        let annual_return = find_value(InputField::AssumedReturn);
+        let volatility = find_value(InputField::Volatility);
+
+        if number_format
+            .parse(&annual_return)
+            .is_none_or(|annual_return| annual_return <= -100.0)
+        {
+            return Err(format!(
+                "{}: {:?}",
+                self.tr(Text::InvalidAssumedReturn),
+                annual_return
+            ));
+        }
+        let volatility = if volatility.is_empty() {
+            "0".to_string()
+        } else if number_format
+            .parse(&volatility)
+            .is_some_and(|volatility| volatility >= 0.0)
+        {
+            volatility
+        } else {
+            return Err(format!(
+                "{}: {:?}",
+                self.tr(Text::InvalidVolatility),
+                volatility
+            ));
+        };
+        Ok(Message::SetAssumption(
+            asset_class,
+            annual_return,
+            volatility,
+        ))
+    }
+
+    // Securities are given by name or ticker and passed on as comma separated ids,
+    // the target date as ISO
+    fn validate_goal(&self) -> Result<Message, String> {
+        let find_value = |key: InputField| {
+            self.inputs_config[14]
+                .iter()
+                .find(|(field, _)| *field == key)
+                .map(|(_, v)| v.trim().to_string())
+                .unwrap_or_default()
+        };
+        let number_format = format::NumberFormat::new(&self.settings);
+        let name = find_value(InputField::GoalName);
+        let target_amount = find_value(InputField::TargetAmount);
+        let target_date = find_value(InputField::TargetDate);
+        let accounts = find_value(InputField::Accounts);
+        let securities = find_value(InputField::Securities);
+        let assumed_return = find_value(InputField::AssumedReturn);
+
+        if name.is_empty() {
+            return Err(self.tr(Text::GoalNameEmpty).to_string());
+        }
+        if !number_format
+            .parse(&target_amount)
+            .is_some_and(|amount| amount > 0.0)
+        {
+            return Err(format!(
+                "{}: {:?}",
+                self.tr(Text::InvalidAmount),
+                target_amount
+            ));
+        }
+        let Some(target_date) = format::parse_date_input(&target_date, &self.settings.date_format)
+        else {
+            return Err(format!("{}: {:?}", self.tr(Text::InvalidDate), target_date));
+        };
+        let mut security_ids = Vec::new();
+        for security in securities
+            .split(',')
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+        {
+            let Some(found) = self.overview.securities.iter().find(|s| {
+                s.name.eq_ignore_ascii_case(security) || s.ticker.eq_ignore_ascii_case(security)
+            }) else {
+                return Err(format!(
+                    "{}: {:?}",
+                    self.tr(Text::UnknownSecurity),
+                    security
+                ));
+            };
+            security_ids.push(found.id.to_string());
+        }
+        let assumed_return = if assumed_return.is_empty() {
+            "0".to_string()
+        } else if number_format.parse(&assumed_return).is_some() {
+            assumed_return
+        } else {
+            return Err(format!(
+                "{}: {:?}",
+                self.tr(Text::InvalidAssumedReturn),
+                assumed_return
+            ));
+        };
+        Ok(Message::AddGoal(
+            name,
+            target_amount,
+            format::iso_date(target_date),
+            accounts,
+            security_ids.join(","),
+            assumed_return,
+        ))
+    }
+
+    // Either an amount or a quantity, an empty start is today and an empty day the start's day.
+    // Dates are passed on as ISO.
+    fn validate_savings_plan(&self) -> Result<Message, String> {
+        let find_value = |key: InputField| {
+            self.inputs_config[13]
+                .iter()
+                .find(|(field, _)| *field == key)
+                .map(|(_, v)| v.trim().to_string())
+                .unwrap_or_default()
+        };
+        let amount = find_value(InputField::PlanAmount);
+        let quantity = find_value(InputField::PlanQuantity);
+        let frequency = find_value(InputField::Frequency);
+        let start = find_value(InputField::StartDate);
+        let end = find_value(InputField::EndDate);
+        let day_of_month = find_value(InputField::DayOfMonth);
+
+        match (amount.is_empty(), quantity.is_empty()) {
+            (false, true) => {
+                if !format::NumberFormat::new(&self.settings)
+                    .parse(&amount)
+                    .is_some_and(|amount| amount > 0.0)
+                {
+                    return Err(format!("{}: {:?}", self.tr(Text::InvalidAmount), amount));
+                }
+            }
+            (true, false) => {
+                if !quantity.parse::<u8>().is_ok_and(|quantity| quantity > 0) {
+                    return Err(format!("{}: {:?}", self.tr(Text::InvalidAmount), quantity));
+                }
+            }
+            _ => return Err(self.tr(Text::AmountOrQuantity).to_string()),
+        }
+        if savings_plan::Frequency::parse(&frequency).is_none() {
+            return Err(format!(
+                "{}: {:?}",
+                self.tr(Text::InvalidFrequency),
+                frequency
+            ));
+        }
+        let start = if start.is_empty() {
+            chrono::Local::now().date_naive()
+        } else {
+            format::parse_date_input(&start, &self.settings.date_format)
+                .ok_or_else(|| format!("{}: {:?}", self.tr(Text::InvalidDate), start))?
+        };
+        let end = if end.is_empty() {
+            String::new()
+        } else {
+            match format::parse_date_input(&end, &self.settings.date_format) {
+                Some(end) if end >= start => format::iso_date(end),
+                _ => return Err(format!("{}: {:?}", self.tr(Text::InvalidDate), end)),
+            }
+        };
+        let day_of_month = if day_of_month.is_empty() {
+            start.day().to_string()
+        } else if day_of_month
+            .parse::<u32>()
+            .is_ok_and(|day| (1..=31).contains(&day))
+        {
+            day_of_month
+        } else {
+            return Err(format!(
+                "{}: {:?}",
+                self.tr(Text::InvalidDayOfMonth),
+                day_of_month
+            ));
+        };
+        Ok(Message::AddSavingsPlan(
+            amount,
+            quantity,
+            frequency,
+            format::iso_date(start),
+            end,
+            day_of_month,
+        ))
+    }
+
+    // A weight of zero removes the target, an empty tolerance keeps the default
+    fn validate_target(&self, weight: &str, tolerance: &str) -> Result<(), String> {
+        let number_format = format::NumberFormat::new(&self.settings);
+        if number_format
+            .parse(weight)
+            .is_none_or(|weight| !(0.0..=100.0).contains(&weight))
+        {
+            return Err(format!("{}: {:?}", self.tr(Text::InvalidWeight), weight));
+        }
+        if !tolerance.trim().is_empty()
+            && number_format
+                .parse(tolerance)
+                .is_none_or(|tolerance| tolerance < 0.0)
+        {
+            return Err(format!("{}: {:?}", self.tr(Text::InvalidWeight), tolerance));
+        }
+        Ok(())
+    }
+
+    fn price_provider(&self) -> Option<std::sync::Arc<dyn price::PriceProvider>> {
+        match price::from_source(&self.settings.quotes) {
+            Ok(Some(provider)) => Some(provider),
+            Ok(None) => {
+                println!("No price provider set up");
+                None
+            }
+            Err(e) => {
+                println!("Failed to set up the price provider: {}", e);
+                None
+            }
+        }
+    }
+
+    fn remember_file(&mut self, path: &std::path::Path) {
+        self.settings.add_recent_file(path);
+        self.settings.save();
+    }
+
+    fn read_overview(
+        &mut self,
+        path: std::path::PathBuf,
+        password: Option<String>,
+    ) -> Result<(), storage::StorageError> {
+        let mut overview = storage::open(&path, password.clone())?.load()?;
+        overview.open_security = None; // Opened portfolios start on the dashboard
+        self.overview = overview; // Replace the current overview
+        self.remember_file(&path);
+        self.current_file_path = Some(path);
+        self.current_password = password;
+        self.unsaved_changes = false;
+        self.current_screen = Screen::Overview(false);
+        Ok(())
+    }
+}
+
+#[derive(Default, Debug)]
+struct MainMenu {
+    //text: String,
+}
+
+impl MainMenu {
+    fn new() -> Self {
+        Self {}
+    }
+
+    fn update(&mut self, message: Message) -> Screen {
+        match message {
+            Message::NewPortfolio => Screen::Overview(false),
             Message::LoadPortfolio => Screen::Overview(false),
             Message::SavePortfolio => Screen::Overview(false),
             Message::Settings => Screen::Settings,
@@ -342,8 +2211,34 @@ impl MainMenu {
         }
     }
 
-    fn view(&self) -> Element<'_, Message> {
-        container(text("")).into()
+    fn view(&self, settings: &settings::Settings) -> Element<'_, Message> {
+        let tr = |text: Text| i18n::tr(settings.language, text);
+        let recent_files = column(settings.recent_files.iter().map(|path| {
+            let name = path
+                .file_name()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .to_string();
+            row![
+                button(text(name)).on_press(Message::OpenRecentFile(path.clone())),
+                text(path.display().to_string()),
+            ]
+            .spacing(10)
+            .into()
+        }))
+        .spacing(5);
+        container(
+            column![
+                text(tr(Text::RecentPortfolios)).size(20),
+                recent_files,
+                checkbox(settings.reopen_last)
+                    .label(tr(Text::ReopenLast))
+                    .on_toggle(Message::ToggleReopenLast),
+            ]
+            .spacing(10),
+        )
+        .padding(20)
+        .into()
     }
 }
 
@@ -353,6 +2248,34 @@ struct Overview {
     open_security: Option<u8>,
     last_security_id: u8,
     total_invested: f32,
+    #[serde(default)]
+    cost_basis_method: security::CostBasisMethod,
+    #[serde(default)]
+    cash: f32,
+    #[serde(skip)]
+    custom_returns_period: Option<returns::ReturnPeriod>,
+    #[serde(default)]
+    benchmark: Option<benchmark::Benchmark>,
+    #[serde(default)]
+    index_series: Vec<benchmark::IndexSeries>,
+    #[serde(default)]
+    targets: Vec<rebalance::Target>,
+    #[serde(default)]
+    goals: Vec<goals::Goal>,
+    #[serde(default)]
+    assumptions: Vec<simulation::Assumption>,
+    #[serde(skip)]
+    rebalance_options: rebalance::PlanOptions,
+    #[serde(skip)]
+    overwrite_prices: bool, // whether imported prices replace recorded ones of the same day
+    #[serde(skip)]
+    income_grouping: income::IncomeGrouping,
+    #[serde(skip)]
+    income_interval: income::IncomeInterval,
+    #[serde(skip)]
+    simulation_options: simulation::Options,
+    #[serde(skip)]
+    projection: Vec<simulation::Band>, // empty until a simulation was run
 }
 
 impl Overview {
@@ -362,12 +2285,205 @@ impl Overview {
             open_security: None,
             last_security_id: 0,
             total_invested: 0.0,
+            cost_basis_method: security::CostBasisMethod::default(),
+            cash: 0.0,
+            custom_returns_period: None,
+            benchmark: None,
+            index_series: Vec::new(),
+            targets: Vec::new(),
+            goals: Vec::new(),
+            assumptions: Vec::new(),
+            rebalance_options: rebalance::PlanOptions::default(),
+            overwrite_prices: false,
+            income_grouping: income::IncomeGrouping::default(),
+            income_interval: income::IncomeInterval::default(),
+            simulation_options: simulation::Options::default(),
+            projection: Vec::new(),
         }
     }
 
-    fn update(&mut self, message: Message) -> Screen {
-        self.calculate_total_investment();
-        match message {
+    /// Merges the imported prices. A single price column goes to the open security,
+    /// other columns to the security with that ticker, ISIN or name. The summary names
+    /// every security and unmatched column.
+    fn import_prices(
+        &mut self,
+        file: import::PriceFile,
+        settings: &settings::Settings,
+    ) -> Result<String, String> {
+        let tr = |text: Text| i18n::tr(settings.language, text);
+        let mut unmatched = Vec::new();
+        let columns = match file {
+            import::PriceFile::Single(prices) => match self.open_security {
+                Some(security_id) => vec![(security_id, prices)],
+                None => return Err(tr(Text::OpenSecurityToImport).to_string()),
+            },
+            import::PriceFile::PerSymbol(columns) => columns
+                .into_iter()
+                .filter_map(|(symbol, prices)| {
+                    let security = self.securities.iter().find(|security| {
+                        [&security.ticker, &security.isin, &security.name]
+                            .iter()
+                            .any(|name| name.eq_ignore_ascii_case(&symbol))
+                    });
+                    if security.is_none() {
+                        unmatched.push(format!("{}: {}", tr(Text::NoSecurityForColumn), symbol));
+                    }
+                    security.map(|security| (security.id, prices))
+                })
+                .collect(),
+        };
+        let mut summary = Vec::new();
+        for (security_id, prices) in columns {
+            if let Some(security) = self.securities.iter_mut().find(|s| s.id == security_id) {
+                let merge = security.add_price_history(&prices, self.overwrite_prices);
+                summary.push(format!(
+                    "{}: {} {}, {} {}, {} {}",
+                    security.name,
+                    merge.added,
+                    tr(Text::PricesAdded),
+                    merge.replaced,
+                    tr(Text::PricesReplaced),
+                    merge.kept,
+                    tr(Text::PricesKept)
+                ));
+            }
+        }
+        summary.extend(unmatched);
+        Ok(summary.join("; "))
+    }
+
+    fn view_price_import(&self, settings: &settings::Settings) -> Element<'_, Message> {
+        let tr = |text: Text| i18n::tr(settings.language, text);
+        row![
+            button(tr(Text::ImportPrices)).on_press(Message::ImportPrices),
+            checkbox(self.overwrite_prices)
+                .label(tr(Text::OverwritePrices))
+                .on_toggle(Message::ToggleOverwritePrices),
+        ]
+        .spacing(10)
+        .into()
+    }
+
+    fn get_target(&self, group: &rebalance::TargetGroup) -> Option<&rebalance::Target> {
+        self.targets.iter().find(|target| target.group == *group)
+    }
+
+    // Replaces the group's target, a weight of zero removes it
+    fn set_target(&mut self, group: rebalance::TargetGroup, weight: f32, tolerance: f32) {
+        self.targets.retain(|target| target.group != group);
+        if weight > 0.0 {
+            self.targets.push(rebalance::Target {
+                group,
+                weight,
+                tolerance,
+            });
+        }
+    }
+
+    fn run_simulation(&mut self) {
+        self.projection = simulation::project(
+            &self.securities,
+            self.cash,
+            &self.assumptions,
+            &simulation::historical_returns(&self.securities),
+            &self.simulation_options,
+        );
+    }
+
+    fn get_benchmark_name(&self) -> Option<String> {
+        match self.benchmark.as_ref()? {
+            benchmark::Benchmark::Security(security_id) => self
+                .securities
+                .iter()
+                .find(|s| s.id == *security_id)
+                .map(|security| security.name.clone()),
+            benchmark::Benchmark::Index(name) => self
+                .index_series
+                .iter()
+                .find(|series| series.name == *name)
+                .map(|series| series.name.clone()),
+        }
+    }
+
+    // Growth of the benchmark, a held security counts its income like the portfolio does
+    fn benchmark_index(&self, today: chrono::NaiveDate) -> Option<Vec<(chrono::NaiveDate, f64)>> {
+        match self.benchmark.as_ref()? {
+            benchmark::Benchmark::Security(security_id) => self
+                .securities
+                .iter()
+                .find(|s| s.id == *security_id)
+                .map(|security| risk::growth_index(&[security], today)),
+            benchmark::Benchmark::Index(name) => self
+                .index_series
+                .iter()
+                .find(|series| series.name == *name)
+                .map(|series| series.get_points()),
+        }
+    }
+
+    fn benchmark_options(&self) -> Vec<benchmark::BenchmarkOption> {
+        let securities = self
+            .securities
+            .iter()
+            .map(|security| benchmark::BenchmarkOption {
+                benchmark: benchmark::Benchmark::Security(security.id),
+                label: security.name.clone(),
+            });
+        let indices = self
+            .index_series
+            .iter()
+            .map(|series| benchmark::BenchmarkOption {
+                benchmark: benchmark::Benchmark::Index(series.name.clone()),
+                label: series.name.clone(),
+            });
+        securities.chain(indices).collect()
+    }
+
+    // Imported again under the same name, the old prices are replaced
+    fn add_index_series(&mut self, series: benchmark::IndexSeries) {
+        self.index_series.retain(|other| other.name != series.name);
+        self.benchmark = Some(benchmark::Benchmark::Index(series.name.clone()));
+        self.index_series.push(series);
+    }
+
+    fn risk_metrics(
+        &self,
+        securities: &[&security::Security],
+        settings: &settings::Settings,
+    ) -> risk::RiskMetrics {
+        let today = chrono::Local::now().date_naive();
+        let benchmark = self.benchmark_index(today);
+        risk::calculate(
+            &risk::growth_index(securities, today),
+            benchmark.as_deref(),
+            settings.risk_free_rate as f64 / 100.0,
+        )
+    }
+
+    fn apply_price_update(&mut self, security_id: u8, update: &price::PriceUpdate) {
+        if let Some(security) = self.securities.iter_mut().find(|s| s.id == security_id) {
+            let history: Vec<(chrono::NaiveDate, f32)> = update
+                .history
+                .iter()
+                .map(|quote| (quote.date, quote.price))
+                .collect();
+            security.add_price_history(&history, true);
+            security.update_quote(
+                update.latest.price,
+                format::iso_date(update.latest.date),
+                update.latest.timestamp.map(|time| time.to_rfc3339()),
+            );
+            security.calculate_total_current_value();
+        }
+    }
+
+    fn get_open_security(&self) -> Option<&security::Security> {
+        let security_id = self.open_security?;
+        self.securities.iter().find(|s| s.id == security_id)
+    }
+
+    fn update(&mut self, message: Message, settings: &settings::Settings) -> Screen {
+        let screen = match message {
             Message::NewPortfolio => Screen::Overview(false),
             Message::LoadPortfolio => Screen::Overview(false),
             Message::SavePortfolio => Screen::Overview(false),
@@ -375,7 +2491,7 @@ impl Overview {
             Message::Settings => Screen::Settings,
             Message::OpenSecurityNameInput => Screen::Overview(true),
             Message::AddSecurity(security_name) => {
-                if self.securities.len() == 0 {
+                if self.securities.is_empty() {
                     self.last_security_id = 0;
                 } else {
                     self.last_security_id += 1;
@@ -387,42 +2503,368 @@ impl Overview {
                 ));
                 Screen::Overview(false)
             }
-            Message::NewInput(_, _) => Screen::Overview(true),
-            Message::OpenSecurity(id) => {
-                self.open_security = Some(id);
+            Message::NewInput(_, _) => Screen::Overview(true),
+            Message::OpenSecurity(id) => {
+                self.open_security = Some(id);
+                Screen::Overview(false)
+            }
+            Message::OpenDashboard => {
+                self.open_security = None;
+                Screen::Overview(false)
+            }
+            Message::OpenSecurityDetailsInput => Screen::Overview(true),
+            Message::EditSecurity(asset_class, currency, account, ticker, isin, country) => {
+                if let Some(security_id) = self.open_security
+                    && let Some(security) = self.securities.iter_mut().find(|s| s.id == security_id)
+                {
+                    security.asset_class = asset_class;
+                    security.currency = currency;
+                    security.account = account;
+                    security.ticker = ticker;
+                    security.isin = isin;
+                    security.country = country;
+                }
+                Screen::Overview(false)
+            }
+            Message::OpenCashInput => Screen::Overview(true),
+            Message::OpenReturnsPeriodInput => Screen::Overview(true),
+            Message::SetBenchmark(benchmark) => {
+                self.benchmark = benchmark;
+                Screen::Overview(false)
+            }
+            Message::SelectBenchmark(option) => {
+                self.benchmark = Some(option.benchmark);
+                Screen::Overview(false)
+            }
+            Message::OpenTargetInput => Screen::Overview(true),
+            Message::OpenAssetClassTargetInput => Screen::Overview(true),
+            Message::OpenRebalanceInput => Screen::Overview(true),
+            Message::SetTarget(weight, tolerance) => {
+                let number_format = format::NumberFormat::new(settings);
+                let Some(weight) = number_format.parse(&weight) else {
+                    println!("Invalid target: {}", weight);
+                    return Screen::Error(2);
+                };
+                let tolerance = number_format
+                    .parse(&tolerance)
+                    .unwrap_or(rebalance::DEFAULT_TOLERANCE);
+                if let Some(security_id) = self.open_security {
+                    self.set_target(
+                        rebalance::TargetGroup::Security(security_id),
+                        weight,
+                        tolerance,
+                    );
+                }
+                Screen::Overview(false)
+            }
+            Message::SetAssetClassTarget(asset_class, weight, tolerance) => {
+                let number_format = format::NumberFormat::new(settings);
+                let Some(weight) = number_format.parse(&weight) else {
+                    println!("Invalid target: {}", weight);
+                    return Screen::Error(2);
+                };
+                let tolerance = number_format
+                    .parse(&tolerance)
+                    .unwrap_or(rebalance::DEFAULT_TOLERANCE);
+                self.set_target(
+                    rebalance::TargetGroup::AssetClass(asset_class),
+                    weight,
+                    tolerance,
+                );
+                Screen::Overview(false)
+            }
+            Message::PlanRebalance(contribution, min_trade_value) => {
+                let number_format = format::NumberFormat::new(settings);
+                self.rebalance_options.contribution =
+                    number_format.parse(&contribution).unwrap_or_default();
+                self.rebalance_options.min_trade_value =
+                    number_format.parse(&min_trade_value).unwrap_or_default();
+                Screen::Overview(false)
+            }
+            Message::ToggleBuyOnly(buy_only) => {
+                self.rebalance_options.buy_only = buy_only;
+                Screen::Overview(false)
+            }
+            Message::OpenGoalInput => Screen::Overview(true),
+            Message::AddGoal(
+                name,
+                target_amount,
+                target_date,
+                accounts,
+                security_ids,
+                assumed_return,
+            ) => {
+                let number_format = format::NumberFormat::new(settings);
+                let (Some(target_amount), Some(target_date), Some(assumed_return)) = (
+                    number_format.parse(&target_amount),
+                    format::parse_date(&target_date),
+                    number_format.parse(&assumed_return),
+                ) else {
+                    println!("Invalid goal: {} {}", target_amount, target_date);
+                    return Screen::Error(2);
+                };
+                let list = |input: &str| -> Vec<String> {
+                    input
+                        .split(',')
+                        .map(str::trim)
+                        .filter(|item| !item.is_empty())
+                        .map(str::to_string)
+                        .collect()
+                };
+                self.goals.push(goals::Goal::new(
+                    name,
+                    target_amount,
+                    target_date,
+                    list(&accounts),
+                    list(&security_ids)
+                        .iter()
+                        .filter_map(|id| id.parse().ok())
+                        .collect(),
+                    assumed_return,
+                ));
+                Screen::Overview(false)
+            }
+            Message::RemoveGoal(index) => {
+                if index < self.goals.len() {
+                    self.goals.remove(index);
+                }
+                Screen::Overview(false)
+            }
+            Message::OpenSimulationInput | Message::OpenAssumptionInput => Screen::Overview(true),
+            Message::RunSimulation(years, paths, seed, contribution) => {
+                let number_format = format::NumberFormat::new(settings);
+                let (Ok(years), Ok(paths), Ok(seed), Some(contribution)) = (
+                    years.parse(),
+                    paths.parse(),
+                    seed.parse(),
+                    number_format.parse(&contribution),
+                ) else {
+                    println!("Invalid simulation: {} {} {}", years, paths, seed);
+                    return Screen::Error(2);
+                };
+                self.simulation_options = simulation::Options {
+                    years,
+                    paths,
+                    seed,
+                    monthly_contribution: contribution,
+                    model: self.simulation_options.model,
+                };
+                self.run_simulation();
+                Screen::Overview(false)
+            }
+            Message::SelectReturnModel(model) => {
+                // The price history might not be enough, the next run checks it
+                self.simulation_options.model = model;
+                self.projection.clear();
+                Screen::Overview(false)
+            }
+            Message::SetAssumption(asset_class, annual_return, volatility) => {
+                let number_format = format::NumberFormat::new(settings);
+                let (Some(annual_return), Some(volatility)) = (
+                    number_format.parse(&annual_return),
+                    number_format.parse(&volatility),
+                ) else {
+                    println!("Invalid assumption: {} {}", annual_return, volatility);
+                    return Screen::Error(2);
+                };
+                self.assumptions
+                    .retain(|assumption| assumption.asset_class != asset_class);
+                self.assumptions.push(simulation::Assumption {
+                    asset_class,
+                    annual_return,
+                    volatility,
+                });
+                self.assumptions
+                    .sort_by(|a, b| a.asset_class.cmp(&b.asset_class));
+                if !self.projection.is_empty() {
+                    self.run_simulation();
+                }
+                Screen::Overview(false)
+            }
+            Message::RemoveAssumption(index) => {
+                if index < self.assumptions.len() {
+                    self.assumptions.remove(index);
+                }
+                if !self.projection.is_empty() {
+                    self.run_simulation();
+                }
+                Screen::Overview(false)
+            }
+            Message::OpenSavingsPlanInput => Screen::Overview(true),
+            Message::AddSavingsPlan(amount, quantity, frequency, start, end, day_of_month) => {
+                let size = if amount.is_empty() {
+                    quantity.parse().ok().map(savings_plan::PlanSize::Quantity)
+                } else {
+                    format::NumberFormat::new(settings)
+                        .parse(&amount)
+                        .map(savings_plan::PlanSize::Amount)
+                };
+                let (Some(size), Some(frequency), Some(start), Ok(day_of_month)) = (
+                    size,
+                    savings_plan::Frequency::parse(&frequency),
+                    format::parse_date(&start),
+                    day_of_month.parse::<u32>(),
+                ) else {
+                    println!("Invalid savings plan");
+                    return Screen::Error(2);
+                };
+                let plan = savings_plan::SavingsPlan::new(
+                    size,
+                    frequency,
+                    start,
+                    format::parse_date(&end),
+                    day_of_month,
+                );
+                if let Some(security_id) = self.open_security
+                    && let Some(security) = self.securities.iter_mut().find(|s| s.id == security_id)
+                {
+                    security.savings_plans.push(plan);
+                }
+                Screen::Overview(false)
+            }
+            Message::RemoveSavingsPlan(index) => {
+                if let Some(security_id) = self.open_security
+                    && let Some(security) = self.securities.iter_mut().find(|s| s.id == security_id)
+                    && index < security.savings_plans.len()
+                {
+                    security.savings_plans.remove(index);
+                }
+                Screen::Overview(false)
+            }
+            Message::ConfirmPlannedEntry(security_id, plan, date) => {
+                let today = chrono::Local::now().date_naive();
+                let pending = savings_plan::pending_entries(&self.securities, today)
+                    .into_iter()
+                    .find(|entry| {
+                        entry.security_id == security_id && entry.plan == plan && entry.date == date
+                    });
+                if let Some(entry) = pending
+                    && let Some(price) = entry.price_per_unit
+                    && let Some(security) = self.securities.iter_mut().find(|s| s.id == security_id)
+                {
+                    if security
+                        .add_entry(
+                            security::Action::Buy,
+                            format::iso_date(date),
+                            entry.quantity,
+                            price,
+                            0.0,
+                            0.0,
+                        )
+                        .is_err()
+                    {
+                        println!("Too many units of {}", security.name);
+                        return Screen::Error(2);
+                    }
+                    security.calculate_total_invested_value(self.cost_basis_method);
+                    security.calculate_total_current_value();
+                    if let Some(plan) = security.savings_plans.get_mut(plan) {
+                        plan.mark_handled(date);
+                    }
+                }
+                Screen::Overview(false)
+            }
+            Message::SkipPlannedEntry(security_id, plan, date) => {
+                if let Some(security) = self.securities.iter_mut().find(|s| s.id == security_id)
+                    && let Some(plan) = security.savings_plans.get_mut(plan)
+                {
+                    plan.mark_handled(date);
+                }
+                Screen::Overview(false)
+            }
+            Message::SelectIncomeGrouping(grouping) => {
+                self.income_grouping = grouping;
+                Screen::Overview(false)
+            }
+            Message::SelectIncomeInterval(interval) => {
+                self.income_interval = interval;
+                Screen::Overview(false)
+            }
+            Message::SetReturnsPeriod(start, end) => {
+                let (Some(start), Some(end)) = (
+                    format::parse_date_input(&start, &settings.date_format),
+                    format::parse_date_input(&end, &settings.date_format),
+                ) else {
+                    println!("Invalid period: {} {}", start, end);
+                    return Screen::Error(2);
+                };
+                self.custom_returns_period = Some(returns::ReturnPeriod::Custom(start, end));
+                Screen::Overview(false)
+            }
+            Message::UpdateCash(value) => {
+                let Some(cash) = format::NumberFormat::new(settings).parse(&value) else {
+                    println!("Invalid cash: {}", value);
+                    return Screen::Error(2);
+                };
+                self.cash = cash;
                 Screen::Overview(false)
             }
             Message::OpenEntryInput => Screen::Overview(true),
-            Message::AddEntry(date, quantity, price) => {
-                if let Some(security_id) = self.open_security {
-                    if let Some(security) = self.securities.iter_mut().find(|s| s.id == security_id)
+            Message::AddEntry(action, date, quantity, price, fee, tax_withheld) => {
+                let Some(action) = security::Action::parse(&action) else {
+                    println!("Unknown action: {}", action);
+                    return Screen::Error(2);
+                };
+                let number_format = format::NumberFormat::new(settings);
+                // Fees and withheld tax can be left empty
+                let optional = |input: &str| {
+                    if input.trim().is_empty() {
+                        Some(0.0)
+                    } else {
+                        number_format.parse(input)
+                    }
+                };
+                // The date arrives as ISO, it's shown in the user's format
+                let (Some(day), Ok(quantity), Some(price), Some(fee), Some(tax_withheld)) = (
+                    format::parse_date(&date),
+                    quantity.trim().parse::<u8>(),
+                    number_format.parse(&price),
+                    optional(&fee),
+                    optional(&tax_withheld),
+                ) else {
+                    println!(
+                        "Invalid entry: {} {} {} {} {}",
+                        date, quantity, price, fee, tax_withheld
+                    );
+                    return Screen::Error(2);
+                };
+                let date = format::iso_date(day);
+                if let Some(security_id) = self.open_security
+                    && let Some(security) = self.securities.iter_mut().find(|s| s.id == security_id)
+                {
+                    if security
+                        .add_entry(action, date, quantity, price, fee, tax_withheld)
+                        .is_err()
                     {
-                        security.add_entry(
-                            date,
-                            quantity.trim().parse::<u8>().unwrap(),
-                            price.trim().replace(',', ".").parse::<f32>().unwrap(),
-                        );
-                        security.calculate_total_invested_value();
-                        security.calculate_total_current_value();
+                        println!("Too many units of {}", security.name);
+                        return Screen::Error(2);
                     }
+                    security.calculate_total_invested_value(self.cost_basis_method);
+                    security.calculate_total_current_value();
                 }
                 //self.securities.get(self.open_security);
                 Screen::Overview(false)
             }
             Message::OpenUpdateCurrentValue => Screen::Overview(true),
             Message::UpdateCurrentValue(value) => {
-                if let Some(security_id) = self.open_security {
-                    if let Some(security) = self.securities.iter_mut().find(|s| s.id == security_id)
-                    {
-                        security.update_current_price(
-                            value.trim().replace(',', ".").parse::<f32>().unwrap(),
-                        );
-                        security.calculate_total_current_value();
-                    }
+                let Some(price) = format::NumberFormat::new(settings).parse(&value) else {
+                    println!("Invalid price: {}", value);
+                    return Screen::Error(2);
+                };
+                if let Some(security_id) = self.open_security
+                    && let Some(security) = self.securities.iter_mut().find(|s| s.id == security_id)
+                {
+                    let today = chrono::Local::now().date_naive();
+                    security.update_current_price(price, format::iso_date(today));
+                    security.calculate_total_current_value();
                 }
                 //self.securities.get(self.open_security);
                 Screen::Overview(false)
             }
+            Message::OpenReportInput => Screen::Overview(true),
+            Message::GenerateReport(_, _) => Screen::Overview(false),
+            Message::OpenCapitalGainsInput => Screen::Overview(true),
+            Message::ExportCapitalGains(_) => Screen::Overview(false),
             Message::Debug => {
                 println!("{:#?}", self);
                 Screen::Overview(false)
@@ -431,69 +2873,173 @@ impl Overview {
                 println!("{:#?}", self);
                 Screen::Error(1)
             }
-        }
+        };
+        // Totals follow every change to entries and prices
+        self.calculate_total_investment();
+        screen
     }
 
-    fn view(&self) -> Element<'_, Message> {
+    fn view(&self, settings: &settings::Settings) -> Element<'_, Message> {
+        let number_format = format::NumberFormat::new(settings);
+        let money = |value: f32| number_format.money(value, &settings.base_currency);
+        let tr = |text: Text| i18n::tr(settings.language, text);
+        let today = chrono::Local::now().date_naive();
         let security_details_container: Element<_> = if let Some(security_id) = self.open_security {
             // Find the matching security
             if let Some(security) = self.securities.iter().find(|s| s.id == security_id) {
+                let performance = security.get_performance(self.cost_basis_method);
+                let quoted_at = security
+                    .get_quoted_at()
+                    .and_then(|time| chrono::DateTime::parse_from_rfc3339(time).ok())
+                    .map(|time| {
+                        let time = time.with_timezone(&chrono::Local);
+                        format!(
+                            " ({} {})",
+                            tr(Text::QuotedAt),
+                            time.format(&format!("{} %H:%M", settings.date_format))
+                        )
+                    })
+                    .unwrap_or_default();
                 let entries_data = security.get_entries();
-                let entries_column =
-                    entries_data
-                        .iter()
-                        .fold(column![], |col, (date, quantity, value_per_unit)| {
-                            col.push(
-                                row![
-                                    text("BUY").width(Length::FillPortion(2)),
-                                    rule::vertical(1),
-                                    text(date.clone()).width(Length::FillPortion(3)),
-                                    rule::vertical(1),
-                                    text(quantity.clone()).width(Length::FillPortion(2)),
-                                    rule::vertical(1),
-                                    text(value_per_unit.clone()).width(Length::FillPortion(2))
-                                ]
-                                .height(Length::Shrink),
-                            )
-                        });
+                let entries_column = entries_data.iter().zip(security.get_fees()).fold(
+                    column![],
+                    |col, ((action, date, quantity, value_per_unit), fee)| {
+                        col.push(
+                            row![
+                                text(tr(action.label())).width(Length::FillPortion(2)),
+                                rule::vertical(1),
+                                text(format::format_date(date, &settings.date_format))
+                                    .width(Length::FillPortion(3)),
+                                rule::vertical(1),
+                                text(*quantity).width(Length::FillPortion(2)),
+                                rule::vertical(1),
+                                text(money(*value_per_unit)).width(Length::FillPortion(2)),
+                                rule::vertical(1),
+                                text(money(fee)).width(Length::FillPortion(2))
+                            ]
+                            .height(Length::Shrink),
+                        )
+                    },
+                );
                 container(column![
-                    text(format!("Security: {}", security.name)),
-                    text(format!("id: {}", security.id)),
-                    text(format!("quantity: {}", security.get_quantity())),
+                    text(format!("{}: {}", tr(Text::Security), security.name)),
+                    text(format!("{}: {}", tr(Text::Id), security.id)),
+                    text(format!(
+                        "{}: {}  {}: {}",
+                        tr(Text::Ticker),
+                        security.ticker,
+                        tr(Text::Isin),
+                        security.isin
+                    )),
+                    text(format!(
+                        "{}: {}",
+                        tr(Text::Quantity),
+                        security.get_quantity()
+                    )),
+                    stale_text(
+                        format!(
+                            "{}: {} ({} {}){}",
+                            tr(Text::CurrentValuePerUnit),
+                            money(security.get_current_price_per_unit()),
+                            tr(Text::AsOf),
+                            security
+                                .get_price_date()
+                                .map(|date| date.format(&settings.date_format).to_string())
+                                .unwrap_or_else(|| "-".to_string()),
+                            quoted_at
+                        ),
+                        security.is_price_stale(today, settings.stale_price_days)
+                    ),
                     text(format!(
-                        "current value per unit: {}",
-                        security.get_current_price_per_unit()
+                        "{}: {}",
+                        tr(Text::TotalInvestedValue),
+                        money(security.get_total_invested_value())
                     )),
                     text(format!(
-                        "total invested value: {}",
-                        security.get_total_invested_value()
+                        "{}: {}",
+                        tr(Text::TotalCurrentValue),
+                        money(security.get_total_current_value())
                     )),
+                    gain_text(
+                        format!(
+                            "{}: {} ({})",
+                            tr(Text::UnrealizedGain),
+                            money(performance.unrealized_gain),
+                            number_format.percent(performance.unrealized_gain_percent)
+                        ),
+                        performance.unrealized_gain
+                    ),
+                    gain_text(
+                        format!(
+                            "{}: {}",
+                            tr(Text::RealizedGain),
+                            money(performance.realized_gain)
+                        ),
+                        performance.realized_gain
+                    ),
                     text(format!(
-                        "total current value: {}",
-                        security.get_total_current_value()
+                        "{}: {}",
+                        tr(Text::Income),
+                        money(performance.income)
                     )),
+                    gain_text(
+                        format!(
+                            "{}: {} ({})",
+                            tr(Text::TotalReturn),
+                            money(performance.total_return),
+                            number_format.percent(performance.total_return_percent)
+                        ),
+                        performance.total_return
+                    ),
                     row![
-                        container(button("Add Entry").on_press(Message::OpenEntryInput))
+                        container(button(tr(Text::AddEntry)).on_press(Message::OpenEntryInput))
                             .padding(20),
                         container(
-                            button("Update Current Value")
+                            button(tr(Text::UpdateCurrentValue))
                                 .on_press(Message::OpenUpdateCurrentValue)
                         )
                         .padding(20),
+                        container(button(tr(Text::FetchPrices)).on_press(Message::FetchPrices))
+                            .padding(20),
+                        container(
+                            button(tr(Text::EditSecurity))
+                                .on_press(Message::OpenSecurityDetailsInput)
+                        )
+                        .padding(20),
+                        container(button(tr(Text::SetTarget)).on_press(Message::OpenTargetInput))
+                            .padding(20),
+                        container(
+                            if self.benchmark == Some(benchmark::Benchmark::Security(security.id)) {
+                                button(tr(Text::RemoveBenchmark))
+                                    .on_press(Message::SetBenchmark(None))
+                            } else {
+                                button(tr(Text::UseAsBenchmark)).on_press(Message::SetBenchmark(
+                                    Some(benchmark::Benchmark::Security(security.id)),
+                                ))
+                            }
+                        )
+                        .padding(20),
                     ],
+                    self.view_price_chart(security, settings),
+                    self.view_price_import(settings),
+                    self.view_savings_plans(security, settings),
                     rule::horizontal(1),
                     row![
-                        text("Action").width(Length::FillPortion(2)),
+                        text(tr(Text::Action)).width(Length::FillPortion(2)),
                         rule::vertical(1),
-                        text("Date").width(Length::FillPortion(3)),
+                        text(tr(Text::Date)).width(Length::FillPortion(3)),
                         rule::vertical(1),
-                        text("Quantity").width(Length::FillPortion(2)),
+                        text(tr(Text::Quantity)).width(Length::FillPortion(2)),
                         rule::vertical(1),
-                        text("Buy value per unit").width(Length::FillPortion(2)),
+                        text(tr(Text::ValuePerUnit)).width(Length::FillPortion(2)),
+                        rule::vertical(1),
+                        text(tr(Text::Fee)).width(Length::FillPortion(2)),
                     ]
                     .height(Length::Shrink),
                     rule::horizontal(1),
-                    entries_column
+                    entries_column,
+                    rule::horizontal(1),
+                    self.view_returns(&[security], settings),
                 ])
                 .padding(20)
                 .width(Length::FillPortion(2))
@@ -502,23 +3048,51 @@ impl Overview {
                 space::horizontal().width(Length::FillPortion(2)).into()
             }
         } else {
-            space::horizontal().width(Length::FillPortion(2)).into()
+            self.view_dashboard(settings)
         };
 
         row![
             container(
                 column![
-                    text("Portfolio"),
-                    text(format!("Total Invested: {}", self.total_invested)),
-                    container(button("Add Security").on_press(Message::OpenSecurityNameInput))
+                    button(tr(Text::Portfolio)).on_press(Message::OpenDashboard),
+                    text(format!(
+                        "{}: {}",
+                        tr(Text::TotalInvested),
+                        money(self.total_invested)
+                    )),
+                    container(
+                        button(tr(Text::AddSecurity)).on_press(Message::OpenSecurityNameInput)
+                    )
+                    .padding(20),
+                    container(button(tr(Text::GenerateReport)).on_press(Message::OpenReportInput))
                         .padding(20),
+                    container(
+                        button(tr(Text::CapitalGains)).on_press(Message::OpenCapitalGainsInput)
+                    )
+                    .padding(20),
                     rule::horizontal(1),
                     scrollable(
                         column(self.securities.iter().map(|security| {
+                            let performance = security.get_performance(self.cost_basis_method);
+                            let stale = security.is_price_stale(today, settings.stale_price_days);
                             container(
-                                button(security.name.as_str())
-                                    .on_press(Message::OpenSecurity(security.id))
-                                    .padding(10),
+                                button(
+                                    row![
+                                        text(security.name.as_str()),
+                                        gain_text(
+                                            number_format
+                                                .percent(performance.unrealized_gain_percent),
+                                            performance.unrealized_gain
+                                        ),
+                                    ]
+                                    .push(
+                                        stale
+                                            .then(|| stale_text(tr(Text::Stale).to_string(), true)),
+                                    )
+                                    .spacing(10),
+                                )
+                                .on_press(Message::OpenSecurity(security.id))
+                                .padding(10),
                             )
                             .padding(10)
                             .into()
@@ -537,6 +3111,1115 @@ impl Overview {
         .into()
     }
 
+    // Shown in the details pane while no security is open
+    fn view_dashboard(&self, settings: &settings::Settings) -> Element<'_, Message> {
+        let number_format = format::NumberFormat::new(settings);
+        let money = |value: f32| number_format.money(value, &settings.base_currency);
+        let tr = |text: Text| i18n::tr(settings.language, text);
+
+        let today = chrono::Local::now().date_naive();
+        let start_of_year = today.with_ordinal(1).unwrap_or(today);
+        let stale: Vec<&security::Security> = self
+            .securities
+            .iter()
+            .filter(|security| security.is_price_stale(today, settings.stale_price_days))
+            .collect();
+        let stale_value: f32 = stale
+            .iter()
+            .map(|security| security.get_total_current_value())
+            .sum();
+        let yesterday = today.pred_opt().unwrap_or(today);
+        let end_of_last_year = start_of_year.pred_opt().unwrap_or(start_of_year);
+
+        let securities_value: f32 = self
+            .securities
+            .iter()
+            .map(|security| security.get_total_current_value())
+            .sum();
+        let total_value = securities_value + self.cash;
+        let total_gain = securities_value - self.total_invested;
+        let total_gain_percent = if self.total_invested > 0.0 {
+            total_gain / self.total_invested * 100.0
+        } else {
+            0.0
+        };
+        let change_since = |date| -> f32 {
+            self.securities
+                .iter()
+                .map(|security| security.get_value_change_since(date))
+                .sum()
+        };
+        let today_change = change_since(yesterday);
+        let year_change = change_since(end_of_last_year);
+        let income_ytd: f32 = self
+            .securities
+            .iter()
+            .flat_map(|security| security.get_income())
+            .filter(|(date, _)| format::parse_date(date).is_some_and(|day| day >= start_of_year))
+            .map(|(_, amount)| amount)
+            .sum();
+
+        // Cash is held in the base currency and belongs to no security
+        let mut by_security = Vec::new();
+        let mut by_asset_class = std::collections::BTreeMap::new();
+        let mut by_currency = std::collections::BTreeMap::new();
+        let mut by_account = std::collections::BTreeMap::new();
+        let label = |value: &str| {
+            if value.is_empty() {
+                tr(Text::Unassigned).to_string()
+            } else {
+                value.to_string()
+            }
+        };
+        for security in self.securities.iter() {
+            let value = security.get_total_current_value();
+            by_security.push((security.name.clone(), value));
+            *by_asset_class
+                .entry(label(&security.asset_class))
+                .or_insert(0.0) += value;
+            let currency = if security.currency.is_empty() {
+                settings.base_currency.clone()
+            } else {
+                security.currency.clone()
+            };
+            *by_currency.entry(currency).or_insert(0.0) += value;
+            *by_account.entry(label(&security.account)).or_insert(0.0) += value;
+        }
+        if self.cash != 0.0 {
+            by_security.push((tr(Text::Cash).to_string(), self.cash));
+            *by_asset_class
+                .entry(tr(Text::Cash).to_string())
+                .or_insert(0.0) += self.cash;
+            *by_currency
+                .entry(settings.base_currency.clone())
+                .or_insert(0.0) += self.cash;
+            *by_account.entry(label("")).or_insert(0.0) += self.cash;
+        }
+
+        let allocation = |title: Text, values: Vec<(String, f32)>| -> Element<'_, Message> {
+            column![text(tr(title)).size(18)]
+                .extend(values.into_iter().map(|(name, value)| {
+                    let weight = if total_value != 0.0 {
+                        value / total_value * 100.0
+                    } else {
+                        0.0
+                    };
+                    row![
+                        text(name).width(Length::FillPortion(3)),
+                        text(money(value)).width(Length::FillPortion(2)),
+                        text(number_format.percent(weight)).width(Length::FillPortion(1)),
+                    ]
+                    .into()
+                }))
+                .spacing(5)
+                .into()
+        };
+
+        container(scrollable(
+            column![
+                text(tr(Text::Dashboard)).size(30),
+                text(format!("{}: {}", tr(Text::TotalValue), money(total_value))),
+                (!stale.is_empty()).then(|| stale_text(
+                    format!(
+                        "{}: {} ({})",
+                        tr(Text::StalePrices),
+                        money(stale_value),
+                        stale
+                            .iter()
+                            .map(|security| security.name.as_str())
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    ),
+                    true
+                )),
+                text(format!(
+                    "{}: {}",
+                    tr(Text::TotalInvested),
+                    money(self.total_invested)
+                )),
+                gain_text(
+                    format!(
+                        "{}: {} ({})",
+                        tr(Text::TotalGain),
+                        money(total_gain),
+                        number_format.percent(total_gain_percent)
+                    ),
+                    total_gain
+                ),
+                gain_text(
+                    format!("{}: {}", tr(Text::TodayChange), money(today_change)),
+                    today_change
+                ),
+                gain_text(
+                    format!("{}: {}", tr(Text::YearChange), money(year_change)),
+                    year_change
+                ),
+                row![
+                    text(format!("{}: {}", tr(Text::Cash), money(self.cash))),
+                    button(tr(Text::UpdateCash)).on_press(Message::OpenCashInput),
+                ]
+                .spacing(10),
+                text(format!("{}: {}", tr(Text::IncomeYtd), money(income_ytd))),
+                rule::horizontal(1),
+                self.view_value_chart(settings),
+                rule::horizontal(1),
+                self.view_allocation_chart(&by_security, settings),
+                allocation(Text::AllocationBySecurity, by_security),
+                allocation(
+                    Text::AllocationByAssetClass,
+                    by_asset_class.into_iter().collect()
+                ),
+                allocation(
+                    Text::AllocationByCurrency,
+                    by_currency.into_iter().collect()
+                ),
+                allocation(Text::AllocationByAccount, by_account.into_iter().collect()),
+                rule::horizontal(1),
+                row![
+                    text(tr(Text::Benchmark)),
+                    pick_list(
+                        self.benchmark_options(),
+                        self.benchmark_options()
+                            .into_iter()
+                            .find(|option| Some(&option.benchmark) == self.benchmark.as_ref()),
+                        Message::SelectBenchmark
+                    ),
+                    button(tr(Text::ImportIndex)).on_press(Message::ImportBenchmark),
+                ]
+                .spacing(10),
+                self.view_price_import(settings),
+                self.view_returns(&self.securities.iter().collect::<Vec<_>>(), settings),
+                rule::horizontal(1),
+                self.view_risk(settings),
+                self.view_pending_entries(settings),
+                self.view_goals(settings),
+                rule::horizontal(1),
+                self.view_projection(settings),
+                rule::horizontal(1),
+                self.view_income(settings),
+                rule::horizontal(1),
+                self.view_tax_losses(settings),
+                rule::horizontal(1),
+                self.view_rebalancing(settings),
+            ]
+            .spacing(10),
+        ))
+        .padding(20)
+        .width(Length::FillPortion(2))
+        .into()
+    }
+
+    fn chart_labels(settings: &settings::Settings) -> chart::Labels {
+        chart::Labels {
+            number_format: format::NumberFormat::new(settings),
+            currency: settings.base_currency.clone(),
+            date_format: settings.date_format.clone(),
+            language: settings.language,
+        }
+    }
+
+    // Value and cost of the holdings on every day with a trade or a recorded price
+    fn view_value_chart(&self, settings: &settings::Settings) -> Element<'_, Message> {
+        let tr = |text: Text| i18n::tr(settings.language, text);
+        let today = chrono::Local::now().date_naive();
+        let mut days: Vec<chrono::NaiveDate> = self
+            .securities
+            .iter()
+            .flat_map(|security| security.get_price_points())
+            .map(|(day, _)| day)
+            .filter(|day| *day < today)
+            .collect();
+        days.sort();
+        days.dedup();
+
+        // Every security is walked once for all days, the chart is drawn on every view
+        let mut values = vec![0.0; days.len()];
+        let mut costs = vec![0.0; days.len()];
+        for security in self.securities.iter() {
+            let security_values = security.get_values_on(&days);
+            let security_costs = security.get_invested_values_on(&days, self.cost_basis_method);
+            for (total, value) in values.iter_mut().zip(security_values) {
+                *total += value;
+            }
+            for (total, cost) in costs.iter_mut().zip(security_costs) {
+                *total += cost;
+            }
+        }
+        let mut value: Vec<(chrono::NaiveDate, f32)> = days.iter().copied().zip(values).collect();
+        let mut invested: Vec<(chrono::NaiveDate, f32)> = days.into_iter().zip(costs).collect();
+        if !value.is_empty() {
+            let current: f32 = self
+                .securities
+                .iter()
+                .map(|security| security.get_total_current_value())
+                .sum();
+            value.push((today, current));
+            invested.push((today, self.total_invested));
+        }
+
+        let palette = settings.theme.to_theme().palette();
+        let chart = chart::LineChart {
+            series: vec![
+                chart::Series {
+                    label: tr(Text::TotalValue).to_string(),
+                    color: palette.primary,
+                    points: value,
+                },
+                chart::Series {
+                    label: tr(Text::TotalInvested).to_string(),
+                    color: Color {
+                        a: 0.5,
+                        ..palette.text
+                    },
+                    points: invested,
+                },
+            ],
+            markers: Vec::new(),
+            labels: Self::chart_labels(settings),
+        };
+        column![
+            canvas(chart).width(Fill).height(250),
+            text(tr(Text::ChartHint)).size(12),
+        ]
+        .extend(self.view_benchmark_chart(today, settings))
+        .spacing(5)
+        .into()
+    }
+
+    // Both as growth of 100 from the first day the holdings had a value, so money
+    // put in or taken out doesn't count as performance
+    fn view_benchmark_chart(
+        &self,
+        today: chrono::NaiveDate,
+        settings: &settings::Settings,
+    ) -> Option<Element<'_, Message>> {
+        let tr = |text: Text| i18n::tr(settings.language, text);
+        let index = self.benchmark_index(today)?;
+        let growth = risk::growth_index(&self.securities.iter().collect::<Vec<_>>(), today);
+        let (first_day, _) = growth.first().copied()?;
+        let base = benchmark::level_on(&index, first_day)?;
+        let benchmark_points = growth
+            .iter()
+            .filter_map(|(day, _)| {
+                benchmark::level_on(&index, *day).map(|level| (*day, (level / base * 100.0) as f32))
+            })
+            .collect();
+
+        let palette = settings.theme.to_theme().palette();
+        let chart = chart::LineChart {
+            series: vec![
+                chart::Series {
+                    label: tr(Text::Portfolio).to_string(),
+                    color: palette.primary,
+                    points: growth
+                        .iter()
+                        .map(|(day, level)| (*day, (level * 100.0) as f32))
+                        .collect(),
+                },
+                chart::Series {
+                    label: self
+                        .get_benchmark_name()
+                        .unwrap_or_else(|| tr(Text::Benchmark).to_string()),
+                    color: palette.warning,
+                    points: benchmark_points,
+                },
+            ],
+            markers: Vec::new(),
+            labels: chart::Labels {
+                currency: String::new(),
+                ..Self::chart_labels(settings)
+            },
+        };
+        Some(
+            column![
+                text(tr(Text::GrowthOf100)),
+                canvas(chart).width(Fill).height(200),
+            ]
+            .into(),
+        )
+    }
+
+    fn view_price_chart(
+        &self,
+        security: &security::Security,
+        settings: &settings::Settings,
+    ) -> Element<'_, Message> {
+        let tr = |text: Text| i18n::tr(settings.language, text);
+        let mut points = security.get_price_points();
+        let today = chrono::Local::now().date_naive();
+        if !points.is_empty() && points.last().is_some_and(|(day, _)| *day < today) {
+            points.push((today, security.get_current_price_per_unit()));
+        }
+        let markers = security
+            .get_entries()
+            .into_iter()
+            .filter(|(action, _, _, _)| !action.is_income())
+            .filter_map(|(action, date, _, price)| {
+                format::parse_date(&date).map(|date| chart::Marker {
+                    date,
+                    value: price,
+                    action,
+                })
+            })
+            .collect();
+        let chart = chart::LineChart {
+            series: vec![chart::Series {
+                label: tr(Text::Price).to_string(),
+                color: settings.theme.to_theme().palette().primary,
+                points,
+            }],
+            markers,
+            labels: Self::chart_labels(settings),
+        };
+        column![
+            canvas(chart).width(Fill).height(200),
+            text(tr(Text::ChartHint)).size(12),
+        ]
+        .into()
+    }
+
+    fn view_allocation_chart(
+        &self,
+        slices: &[(String, f32)],
+        settings: &settings::Settings,
+    ) -> Element<'_, Message> {
+        let legend = column(slices.iter().enumerate().map(|(i, (name, _))| {
+            row![text("■").color(chart::slice_color(i)), text(name.clone())]
+                .spacing(5)
+                .into()
+        }))
+        .spacing(5);
+        let chart = chart::DonutChart {
+            slices: slices.to_vec(),
+            labels: Self::chart_labels(settings),
+        };
+        row![canvas(chart).width(220).height(220), legend]
+            .spacing(20)
+            .into()
+    }
+
+    // The whole portfolio first, then every security on its own
+    fn view_risk(&self, settings: &settings::Settings) -> Element<'_, Message> {
+        let number_format = format::NumberFormat::new(settings);
+        let tr = |text: Text| i18n::tr(settings.language, text);
+        let percent = |value: Option<f64>| match value {
+            Some(value) => number_format.percent((value * 100.0) as f32),
+            None => "-".to_string(),
+        };
+        let ratio = |value: Option<f64>| match value {
+            Some(value) => number_format.number(value as f32, 2),
+            None => "-".to_string(),
+        };
+        let drawdown = |drawdown: Option<risk::Drawdown>| match drawdown {
+            Some(drawdown) => format!(
+                "{} ({} - {})",
+                number_format.percent((-drawdown.depth * 100.0) as f32),
+                drawdown.peak.format(&settings.date_format),
+                drawdown.trough.format(&settings.date_format)
+            ),
+            None => "-".to_string(),
+        };
+
+        let all: Vec<&security::Security> = self.securities.iter().collect();
+        let subjects = std::iter::once((tr(Text::Portfolio).to_string(), all)).chain(
+            self.securities
+                .iter()
+                .map(|security| (security.name.clone(), vec![security])),
+        );
+        let rows = subjects.map(|(name, securities)| {
+            let metrics = self.risk_metrics(&securities, settings);
+            row![
+                text(name).width(Length::FillPortion(2)),
+                text(percent(metrics.volatility)).width(Length::FillPortion(2)),
+                text(drawdown(metrics.max_drawdown)).width(Length::FillPortion(4)),
+                text(ratio(metrics.sharpe)).width(Length::FillPortion(1)),
+                text(ratio(metrics.sortino)).width(Length::FillPortion(1)),
+                text(ratio(metrics.beta)).width(Length::FillPortion(1)),
+            ]
+            .into()
+        });
+
+        let benchmark = match self.get_benchmark_name() {
+            Some(name) => format!("{}: {}", tr(Text::Benchmark), name),
+            None => tr(Text::NoBenchmark).to_string(),
+        };
+        column![
+            text(tr(Text::Risk)).size(18),
+            text(benchmark).size(12),
+            row![
+                space::horizontal().width(Length::FillPortion(2)),
+                text(tr(Text::Volatility)).width(Length::FillPortion(2)),
+                text(tr(Text::MaxDrawdown)).width(Length::FillPortion(4)),
+                text(tr(Text::Sharpe)).width(Length::FillPortion(1)),
+                text(tr(Text::Sortino)).width(Length::FillPortion(1)),
+                text(tr(Text::Beta)).width(Length::FillPortion(1)),
+            ],
+        ]
+        .extend(rows)
+        .spacing(5)
+        .into()
+    }
+
+    // Savings plans of the open security with the period they run, each can be removed
+    fn view_savings_plans<'a>(
+        &self,
+        security: &'a security::Security,
+        settings: &settings::Settings,
+    ) -> Element<'a, Message> {
+        let number_format = format::NumberFormat::new(settings);
+        let tr = |text: Text| i18n::tr(settings.language, text);
+        let day = |date: Option<chrono::NaiveDate>| {
+            date.map(|date| date.format(&settings.date_format).to_string())
+                .unwrap_or_else(|| "-".to_string())
+        };
+        let plans = security
+            .savings_plans
+            .iter()
+            .enumerate()
+            .map(|(index, plan)| {
+                let size = match plan.size {
+                    savings_plan::PlanSize::Amount(amount) => {
+                        number_format.money(amount, &settings.base_currency)
+                    }
+                    savings_plan::PlanSize::Quantity(quantity) => {
+                        format!("{} {}", quantity, tr(Text::Quantity))
+                    }
+                };
+                row![
+                    text(format!(
+                        "{} {}, {} {}: {} - {}",
+                        size,
+                        tr(plan.frequency.label()),
+                        tr(Text::DayOfMonth),
+                        plan.day_of_month,
+                        day(plan.get_start()),
+                        day(plan.get_end())
+                    ))
+                    .width(Length::Fill),
+                    button(tr(Text::Remove)).on_press(Message::RemoveSavingsPlan(index)),
+                ]
+                .spacing(10)
+                .into()
+            });
+        column![
+            row![
+                text(tr(Text::SavingsPlans)).size(18),
+                button(tr(Text::AddSavingsPlan)).on_press(Message::OpenSavingsPlanInput),
+            ]
+            .spacing(10)
+        ]
+        .extend(plans)
+        .spacing(5)
+        .into()
+    }
+
+    fn view_goals(&self, settings: &settings::Settings) -> Element<'_, Message> {
+        let number_format = format::NumberFormat::new(settings);
+        let money = |value: f32| number_format.money(value, &settings.base_currency);
+        let day = |date: Option<chrono::NaiveDate>| {
+            date.map(|date| date.format(&settings.date_format).to_string())
+                .unwrap_or_else(|| "-".to_string())
+        };
+        let tr = |text: Text| i18n::tr(settings.language, text);
+        let today = chrono::Local::now().date_naive();
+
+        let rows = self.goals.iter().enumerate().map(|(index, goal)| {
+            let progress = goal.progress(&self.securities, self.cash, today);
+            let completion = text(format!(
+                "{}: {}",
+                tr(Text::ProjectedCompletion),
+                day(progress.completion)
+            ));
+            let on_track = progress
+                .completion
+                .zip(goal.get_target_date())
+                .is_some_and(|(completion, target_date)| completion <= target_date);
+            column![
+                row![
+                    text(goal.name.clone()).size(16).width(Length::Fill),
+                    button(tr(Text::Remove)).on_press(Message::RemoveGoal(index)),
+                ]
+                .spacing(10),
+                text(format!(
+                    "{} / {} ({}) {} {}",
+                    money(progress.current),
+                    money(goal.target_amount),
+                    number_format.percent(progress.progress),
+                    tr(Text::By),
+                    day(goal.get_target_date())
+                )),
+                progress_bar(0.0..=100.0, progress.progress.min(100.0)),
+                text(format!(
+                    "{}: {} ({} {}), {}: {}",
+                    tr(Text::RequiredMonthly),
+                    money(progress.required_monthly),
+                    number_format.percent(goal.assumed_return),
+                    tr(Text::AssumedReturn),
+                    tr(Text::PlannedMonthly),
+                    money(progress.planned_monthly)
+                )),
+                if on_track {
+                    completion
+                } else {
+                    completion.color(Color::from_rgb(0.9, 0.5, 0.0))
+                },
+            ]
+            .spacing(5)
+            .into()
+        });
+
+        column![
+            row![
+                text(tr(Text::Goals)).size(18),
+                button(tr(Text::AddGoal)).on_press(Message::OpenGoalInput),
+            ]
+            .spacing(10)
+        ]
+        .extend(rows)
+        .spacing(10)
+        .into()
+    }
+
+    // Percentile bands of a simulation, the assumptions are kept with the portfolio
+    fn view_projection(&self, settings: &settings::Settings) -> Element<'_, Message> {
+        let number_format = format::NumberFormat::new(settings);
+        let money = |value: f32| number_format.money(value, &settings.base_currency);
+        let tr = |text: Text| i18n::tr(settings.language, text);
+        let options = &self.simulation_options;
+
+        let header = row![
+            text(tr(Text::Projection)).size(18),
+            pick_list(
+                i18n::choices(
+                    settings.language,
+                    &simulation::ReturnModel::ALL,
+                    simulation::ReturnModel::label
+                ),
+                Some(i18n::Choice::new(
+                    settings.language,
+                    options.model,
+                    simulation::ReturnModel::label
+                )),
+                |choice| Message::SelectReturnModel(choice.value)
+            ),
+            button(tr(Text::SetAssumption)).on_press(Message::OpenAssumptionInput),
+            button(tr(Text::RunSimulation)).on_press(Message::OpenSimulationInput),
+        ]
+        .spacing(10);
+        let assumption_rows: Vec<Element<'_, Message>> = match options.model {
+            simulation::ReturnModel::Assumptions => self
+                .assumptions
+                .iter()
+                .enumerate()
+                .map(|(index, assumption)| {
+                    row![
+                        text(if assumption.asset_class.is_empty() {
+                            tr(Text::Unassigned).to_string()
+                        } else {
+                            assumption.asset_class.clone()
+                        })
+                        .width(Length::FillPortion(3)),
+                        text(number_format.percent(assumption.annual_return))
+                            .width(Length::FillPortion(2)),
+                        text(format!(
+                            "{} {}",
+                            tr(Text::Volatility),
+                            number_format.percent(assumption.volatility)
+                        ))
+                        .width(Length::FillPortion(2)),
+                        button(tr(Text::Remove)).on_press(Message::RemoveAssumption(index)),
+                    ]
+                    .spacing(10)
+                    .into()
+                })
+                .collect(),
+            simulation::ReturnModel::Bootstrap => vec![
+                text(format!(
+                    "{} {}",
+                    simulation::historical_returns(&self.securities).len(),
+                    tr(Text::MonthsOfHistory)
+                ))
+                .into(),
+            ],
+        };
+        let Some(last) = self.projection.last() else {
+            return column![header].extend(assumption_rows).spacing(5).into();
+        };
+
+        let today = chrono::Local::now().date_naive();
+        let palette = settings.theme.to_theme().palette();
+        let series = simulation::PERCENTILES
+            .iter()
+            .enumerate()
+            .map(|(i, percentile)| chart::Series {
+                label: number_format.percent(*percentile),
+                color: if i == 2 {
+                    palette.primary
+                } else {
+                    Color {
+                        a: if i == 0 || i == 4 { 0.35 } else { 0.6 },
+                        ..palette.primary
+                    }
+                },
+                points: self
+                    .projection
+                    .iter()
+                    .filter_map(|band| {
+                        today
+                            .checked_add_months(chrono::Months::new(band.year * 12))
+                            .map(|day| (day, band.values[i]))
+                    })
+                    .collect(),
+            })
+            .collect();
+        let chart = chart::LineChart {
+            series,
+            markers: Vec::new(),
+            labels: Self::chart_labels(settings),
+        };
+
+        // At most ten rows, always with the last year
+        let step = (last.year as usize).div_ceil(10).max(1);
+        let band_rows = self
+            .projection
+            .iter()
+            .filter(|band| {
+                band.year > 0
+                    && ((band.year as usize).is_multiple_of(step) || band.year == last.year)
+            })
+            .map(|band| {
+                row![text(band.year.to_string()).width(Length::FillPortion(1))]
+                    .extend(
+                        band.values
+                            .iter()
+                            .map(|value| text(money(*value)).width(Length::FillPortion(2)).into()),
+                    )
+                    .into()
+            });
+
+        column![header]
+            .extend(assumption_rows)
+            .push(text(format!(
+                "{}: {}, {}: {}, {}: {}",
+                tr(Text::Contribution),
+                money(options.monthly_contribution),
+                tr(Text::Paths),
+                options.paths,
+                tr(Text::Seed),
+                options.seed
+            )))
+            .push(canvas(chart).width(Fill).height(200))
+            .push(
+                row![text(tr(Text::Year)).width(Length::FillPortion(1))].extend(
+                    simulation::PERCENTILES.iter().map(|percentile| {
+                        text(number_format.percent(*percentile))
+                            .width(Length::FillPortion(2))
+                            .into()
+                    }),
+                ),
+            )
+            .extend(band_rows)
+            .spacing(5)
+            .into()
+    }
+
+    // Buys of the savings plans waiting to be confirmed, nothing when there are none
+    fn view_pending_entries(&self, settings: &settings::Settings) -> Element<'_, Message> {
+        let number_format = format::NumberFormat::new(settings);
+        let tr = |text: Text| i18n::tr(settings.language, text);
+        let today = chrono::Local::now().date_naive();
+        let pending = savings_plan::pending_entries(&self.securities, today);
+        if pending.is_empty() {
+            return column![].into();
+        }
+        let rows = pending.into_iter().map(|entry| {
+            let name = self
+                .securities
+                .iter()
+                .find(|s| s.id == entry.security_id)
+                .map(|security| security.name.clone())
+                .unwrap_or_default();
+            let price = entry
+                .price_per_unit
+                .map(|price| number_format.money(price, &settings.base_currency))
+                .unwrap_or_else(|| tr(Text::NoPrice).to_string());
+            row![
+                text(name).width(Length::FillPortion(3)),
+                text(entry.date.format(&settings.date_format).to_string())
+                    .width(Length::FillPortion(2)),
+                text(entry.quantity).width(Length::FillPortion(1)),
+                text(price).width(Length::FillPortion(2)),
+                button(tr(Text::Confirm)).on_press_maybe(entry.can_confirm().then_some(
+                    Message::ConfirmPlannedEntry(entry.security_id, entry.plan, entry.date)
+                )),
+                button(tr(Text::Skip)).on_press(Message::SkipPlannedEntry(
+                    entry.security_id,
+                    entry.plan,
+                    entry.date
+                )),
+            ]
+            .spacing(10)
+            .into()
+        });
+        column![text(tr(Text::PendingPlanEntries)).size(18)]
+            .extend(rows)
+            .push(rule::horizontal(1))
+            .spacing(5)
+            .into()
+    }
+
+    // Amounts are in the security's currency unless grouped by currency
+    fn view_income(&self, settings: &settings::Settings) -> Element<'_, Message> {
+        let number_format = format::NumberFormat::new(settings);
+        let tr = |text: Text| i18n::tr(settings.language, text);
+        let today = chrono::Local::now().date_naive();
+
+        let rows = income::summarize(
+            &self.securities,
+            self.income_grouping,
+            self.income_interval,
+            &settings.base_currency,
+        );
+        let income_rows = rows.iter().map(|row| {
+            let currency = match self.income_grouping {
+                income::IncomeGrouping::Currency => row.group.as_str(),
+                _ => settings.base_currency.as_str(),
+            };
+            let money = |value: f32| number_format.money(value, currency);
+            let group = if row.group.is_empty() {
+                "-".to_string()
+            } else {
+                row.group.clone()
+            };
+            row![
+                text(row.period.clone()).width(Length::FillPortion(2)),
+                text(group).width(Length::FillPortion(3)),
+                text(money(row.gross)).width(Length::FillPortion(2)),
+                text(money(row.tax_withheld)).width(Length::FillPortion(2)),
+                text(money(row.net())).width(Length::FillPortion(2)),
+            ]
+            .into()
+        });
+
+        let projection = income::project(&self.securities, today);
+        let projected_gross: f32 = projection.iter().map(|p| p.gross).sum();
+        let projected_net: f32 = projection.iter().map(income::Projection::net).sum();
+        let money = |value: f32| number_format.money(value, &settings.base_currency);
+
+        column![
+            row![
+                text(tr(Text::Income)).size(18),
+                pick_list(
+                    i18n::choices(
+                        settings.language,
+                        &income::IncomeGrouping::ALL,
+                        income::IncomeGrouping::label
+                    ),
+                    Some(i18n::Choice::new(
+                        settings.language,
+                        self.income_grouping,
+                        income::IncomeGrouping::label
+                    )),
+                    |choice| Message::SelectIncomeGrouping(choice.value)
+                ),
+                pick_list(
+                    i18n::choices(
+                        settings.language,
+                        &income::IncomeInterval::ALL,
+                        income::IncomeInterval::label
+                    ),
+                    Some(i18n::Choice::new(
+                        settings.language,
+                        self.income_interval,
+                        income::IncomeInterval::label
+                    )),
+                    |choice| Message::SelectIncomeInterval(choice.value)
+                ),
+            ]
+            .spacing(10),
+            row![
+                text(tr(Text::Period)).width(Length::FillPortion(2)),
+                text(tr(Text::Group)).width(Length::FillPortion(3)),
+                text(tr(Text::Gross)).width(Length::FillPortion(2)),
+                text(tr(Text::TaxWithheld)).width(Length::FillPortion(2)),
+                text(tr(Text::Net)).width(Length::FillPortion(2)),
+            ],
+        ]
+        .extend(income_rows)
+        .push(text(format!(
+            "{}: {} {}, {} {}",
+            tr(Text::ProjectedIncome),
+            money(projected_gross),
+            tr(Text::Gross),
+            money(projected_net),
+            tr(Text::Net)
+        )))
+        .spacing(5)
+        .into()
+    }
+
+    fn view_tax_losses(&self, settings: &settings::Settings) -> Element<'_, Message> {
+        let number_format = format::NumberFormat::new(settings);
+        let money = |value: f32| number_format.money(value, &settings.base_currency);
+        let day = |date: chrono::NaiveDate| date.format(&settings.date_format).to_string();
+        let tr = |text: Text| i18n::tr(settings.language, text);
+        let today = chrono::Local::now().date_naive();
+        let security_name = |security_id: u8| {
+            self.securities
+                .iter()
+                .find(|s| s.id == security_id)
+                .map(|security| security.name.clone())
+                .unwrap_or_default()
+        };
+
+        let wash_sales: Vec<wash_sale::WashSale> = self
+            .securities
+            .iter()
+            .flat_map(|security| {
+                wash_sale::wash_sales(
+                    security,
+                    self.cost_basis_method,
+                    settings.wash_sale_days,
+                    settings.wash_sale_rule,
+                )
+            })
+            .collect();
+        let wash_sale_rows = wash_sales.iter().map(|sale| {
+            let replacements: Vec<String> = sale
+                .replacements
+                .iter()
+                .map(|replacement| {
+                    format!(
+                        "{} ({} +{})",
+                        day(replacement.date),
+                        number_format.number(replacement.quantity, 0),
+                        money(replacement.basis_adjustment)
+                    )
+                })
+                .collect();
+            row![
+                text(security_name(sale.security_id)).width(Length::FillPortion(3)),
+                text(day(sale.date)).width(Length::FillPortion(2)),
+                text(money(sale.loss)).width(Length::FillPortion(2)),
+                text(money(sale.disallowed)).width(Length::FillPortion(2)),
+                text(replacements.join(", ")).width(Length::FillPortion(3)),
+            ]
+            .into()
+        });
+
+        let harvests = wash_sale::harvestable(
+            &self.securities,
+            self.cost_basis_method,
+            settings.wash_sale_days,
+            settings.wash_sale_rule,
+            today,
+        );
+        let harvest_rows = harvests.iter().map(|harvest| {
+            let warning = if harvest.recent_purchase {
+                text(tr(Text::RecentPurchase)).color(Color::from_rgb(0.9, 0.5, 0.0))
+            } else {
+                text("")
+            };
+            row![
+                text(security_name(harvest.security_id)).width(Length::FillPortion(3)),
+                text(money(harvest.cost_basis)).width(Length::FillPortion(2)),
+                text(money(harvest.value)).width(Length::FillPortion(2)),
+                text(money(harvest.loss())).width(Length::FillPortion(2)),
+                warning.width(Length::FillPortion(3)),
+            ]
+            .into()
+        });
+
+        column![
+            text(tr(Text::WashSales)).size(18),
+            row![
+                space::horizontal().width(Length::FillPortion(3)),
+                text(tr(Text::Date)).width(Length::FillPortion(2)),
+                text(tr(Text::Loss)).width(Length::FillPortion(2)),
+                text(tr(Text::Disallowed)).width(Length::FillPortion(2)),
+                text(tr(Text::Replacements)).width(Length::FillPortion(3)),
+            ],
+        ]
+        .extend(wash_sale_rows)
+        .push(text(tr(Text::HarvestableLosses)).size(18))
+        .push(row![
+            space::horizontal().width(Length::FillPortion(3)),
+            text(tr(Text::CostBasis)).width(Length::FillPortion(2)),
+            text(tr(Text::CurrentValue)).width(Length::FillPortion(2)),
+            text(tr(Text::Loss)).width(Length::FillPortion(2)),
+            space::horizontal().width(Length::FillPortion(3)),
+        ])
+        .extend(harvest_rows)
+        .spacing(5)
+        .into()
+    }
+
+    // Drift from the target weights and the trades that would correct it
+    fn view_rebalancing(&self, settings: &settings::Settings) -> Element<'_, Message> {
+        let number_format = format::NumberFormat::new(settings);
+        let money = |value: f32| number_format.money(value, &settings.base_currency);
+        let tr = |text: Text| i18n::tr(settings.language, text);
+        let security_name = |security_id: u8| {
+            self.securities
+                .iter()
+                .find(|s| s.id == security_id)
+                .map(|security| security.name.clone())
+                .unwrap_or_default()
+        };
+
+        let holdings: Vec<rebalance::Holding> = self
+            .securities
+            .iter()
+            .map(rebalance::Holding::from_security)
+            .collect();
+        let drift_rows = rebalance::drift(&holdings, &self.targets)
+            .into_iter()
+            .map(|drift| {
+                let name = match &drift.group {
+                    rebalance::TargetGroup::Security(security_id) => security_name(*security_id),
+                    rebalance::TargetGroup::AssetClass(asset_class) => {
+                        format!("{}: {}", tr(Text::AssetClass), asset_class)
+                    }
+                };
+                let drift_text = text(format!(
+                    "{} (± {})",
+                    number_format.percent(drift.drift()),
+                    number_format.number(drift.tolerance, 1)
+                ));
+                row![
+                    text(name).width(Length::FillPortion(3)),
+                    text(number_format.percent(drift.current)).width(Length::FillPortion(2)),
+                    text(number_format.percent(drift.target)).width(Length::FillPortion(2)),
+                    if drift.within_band() {
+                        drift_text
+                    } else {
+                        drift_text.color(Color::from_rgb(0.8, 0.0, 0.0))
+                    }
+                    .width(Length::FillPortion(2)),
+                ]
+                .into()
+            });
+
+        let options = rebalance::PlanOptions {
+            fractional: settings.fractional_shares,
+            ..self.rebalance_options
+        };
+        let trades = rebalance::plan(&holdings, &self.targets, options);
+        let decimals = if options.fractional { 4 } else { 0 };
+        let trade_rows = trades.iter().map(|trade| {
+            let action = if trade.quantity > 0.0 {
+                security::Action::Buy
+            } else {
+                security::Action::Sell
+            };
+            row![
+                text(security_name(trade.security_id)).width(Length::FillPortion(3)),
+                text(tr(action.label())).width(Length::FillPortion(2)),
+                text(number_format.number(trade.quantity.abs(), decimals))
+                    .width(Length::FillPortion(2)),
+                text(money(trade.value.abs())).width(Length::FillPortion(2)),
+            ]
+            .into()
+        });
+        let net: f32 = trades.iter().map(|trade| trade.value).sum();
+
+        column![
+            row![
+                text(tr(Text::Rebalancing)).size(18),
+                button(tr(Text::AssetClassTarget)).on_press(Message::OpenAssetClassTargetInput),
+                button(tr(Text::PlanRebalancing)).on_press(Message::OpenRebalanceInput),
+                checkbox(options.buy_only)
+                    .label(tr(Text::BuyOnly))
+                    .on_toggle(Message::ToggleBuyOnly),
+            ]
+            .spacing(10),
+            row![
+                space::horizontal().width(Length::FillPortion(3)),
+                text(tr(Text::CurrentWeight)).width(Length::FillPortion(2)),
+                text(tr(Text::TargetWeight)).width(Length::FillPortion(2)),
+                text(tr(Text::Drift)).width(Length::FillPortion(2)),
+            ],
+        ]
+        .extend(drift_rows)
+        .push(text(format!(
+            "{}: {}, {}: {}",
+            tr(Text::Contribution),
+            money(options.contribution),
+            tr(Text::MinTradeValue),
+            money(options.min_trade_value)
+        )))
+        .extend(trade_rows)
+        .push(text(format!(
+            "{}: {}",
+            tr(Text::Unallocated),
+            money(options.contribution - net)
+        )))
+        .spacing(5)
+        .into()
+    }
+
+    // Cash isn't part of the returns, it has no history to value it by
+    fn view_returns(
+        &self,
+        securities: &[&security::Security],
+        settings: &settings::Settings,
+    ) -> Element<'_, Message> {
+        let number_format = format::NumberFormat::new(settings);
+        let tr = |text: Text| i18n::tr(settings.language, text);
+        let today = chrono::Local::now().date_naive();
+        let percent = |value: Option<f64>| match value {
+            Some(value) => number_format.percent((value * 100.0) as f32),
+            None => "-".to_string(),
+        };
+
+        let benchmark_index = self.benchmark_index(today);
+        let periods = returns::ReturnPeriod::STANDARD
+            .into_iter()
+            .chain(self.custom_returns_period);
+        let rows = periods.map(|period| {
+            let label = match period {
+                returns::ReturnPeriod::SinceInception => tr(Text::SinceInception).to_string(),
+                returns::ReturnPeriod::Custom(start, end) => format!(
+                    "{} - {}",
+                    start.format(&settings.date_format),
+                    end.format(&settings.date_format)
+                ),
+                period => period.to_string(),
+            };
+            let result = returns::calculate(securities, benchmark_index.as_deref(), period, today);
+            row![
+                text(label).width(Length::FillPortion(2)),
+                text(percent(result.time_weighted)).width(Length::FillPortion(2)),
+                text(percent(result.money_weighted)).width(Length::FillPortion(2)),
+                text(percent(result.benchmark)).width(Length::FillPortion(2)),
+                gain_text(
+                    percent(result.tracking_difference()),
+                    result.tracking_difference().unwrap_or_default() as f32
+                )
+                .width(Length::FillPortion(2)),
+            ]
+            .into()
+        });
+
+        column![
+            row![
+                text(tr(Text::Returns)).size(18),
+                button(tr(Text::CustomPeriod)).on_press(Message::OpenReturnsPeriodInput),
+            ]
+            .spacing(10),
+            row![
+                text(tr(Text::Period)).width(Length::FillPortion(2)),
+                text(tr(Text::TimeWeighted)).width(Length::FillPortion(2)),
+                text(tr(Text::MoneyWeighted)).width(Length::FillPortion(2)),
+                text(tr(Text::Benchmark)).width(Length::FillPortion(2)),
+                text(tr(Text::TrackingDifference)).width(Length::FillPortion(2)),
+            ],
+        ]
+        .extend(rows)
+        .spacing(5)
+        .into()
+    }
+
     fn calculate_total_investment(&mut self) {
         self.total_invested = 0.0;
         for security in self.securities.iter() {
@@ -544,3 +4227,49 @@ impl Overview {
         }
     }
 }
+
+// History since the first trade, or the last month for a new security
+fn fetch_prices(
+    security: &security::Security,
+    provider: std::sync::Arc<dyn price::PriceProvider>,
+    stale_price_days: u32,
+) -> Task<Message> {
+    let security_id = security.id;
+    let Some(symbol) = security.symbol().map(str::to_string) else {
+        return Task::done(Message::PricesFetched(
+            security_id,
+            Err(price::PriceError::NoSymbol),
+        ));
+    };
+    let today = chrono::Local::now().date_naive();
+    let start = security
+        .get_cash_flows()
+        .into_iter()
+        .map(|(date, _)| date)
+        .min()
+        .unwrap_or(today - chrono::Duration::days(30));
+    Task::perform(
+        price::fetch(provider, symbol, start, today, stale_price_days),
+        move |result| Message::PricesFetched(security_id, result),
+    )
+}
+
+// Values relying on old prices are shown in orange
+fn stale_text<'a>(content: String, stale: bool) -> iced::widget::Text<'a> {
+    if stale {
+        text(content).color(Color::from_rgb(0.85, 0.5, 0.0))
+    } else {
+        text(content)
+    }
+}
+
+// Gains in green and losses in red, zero keeps the theme colour
+fn gain_text<'a>(content: String, value: f32) -> iced::widget::Text<'a> {
+    if value > 0.0 {
+        text(content).color(Color::from_rgb(0.0, 0.6, 0.0))
+    } else if value < 0.0 {
+        text(content).color(Color::from_rgb(0.8, 0.0, 0.0))
+    } else {
+        text(content)
+    }
+}
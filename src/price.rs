@@ -0,0 +1,463 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::format::{iso_date, parse_date};
+
+const TIMEOUT: Duration = Duration::from_secs(15);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Quote {
+    pub date: NaiveDate,
+    pub price: f32,
+    pub timestamp: Option<DateTime<Utc>>, // when the provider gives a time of day
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PriceError {
+    NoSymbol,
+    UnknownSymbol(String),
+    Timeout,
+    Network(String),
+    InvalidResponse(String),
+    Stale(NaiveDate),
+}
+
+impl std::fmt::Display for PriceError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PriceError::NoSymbol => write!(f, "no ticker or ISIN set"),
+            PriceError::UnknownSymbol(symbol) => write!(f, "unknown ticker {}", symbol),
+            PriceError::Timeout => write!(f, "timed out"),
+            PriceError::Network(e) => write!(f, "{}", e),
+            PriceError::InvalidResponse(e) => write!(f, "invalid response: {}", e),
+            PriceError::Stale(date) => write!(f, "latest quote is from {}", date),
+        }
+    }
+}
+
+impl From<ureq::Error> for PriceError {
+    fn from(e: ureq::Error) -> Self {
+        match e {
+            ureq::Error::Timeout(_) => PriceError::Timeout,
+            e => PriceError::Network(e.to_string()),
+        }
+    }
+}
+
+/// Looks up quotes by ticker or ISIN. Calls block, `fetch` runs them off the UI thread.
+pub trait PriceProvider: Send + Sync {
+    fn latest(&self, symbol: &str) -> Result<Quote, PriceError>;
+
+    /// Closing prices between both days, oldest first.
+    fn history(
+        &self,
+        symbol: &str,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<Vec<Quote>, PriceError>;
+}
+
+/// Where quotes come from, part of the settings. A quotes file wins over the URLs.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct QuoteSource {
+    pub latest_url: String, // {symbol} is replaced, e.g. https://example.com/quote/{symbol}
+    pub history_url: String, // also {start} and {end} as ISO dates, empty to skip history
+    pub price_field: String, // JSON pointer into a quote, e.g. /close
+    pub date_field: String, // ISO date or Unix timestamp
+    pub history_field: String, // JSON pointer to the list of quotes, empty when it's the root
+    pub file: Option<PathBuf>,
+}
+
+impl Default for QuoteSource {
+    fn default() -> Self {
+        Self {
+            latest_url: String::new(),
+            history_url: String::new(),
+            price_field: "/price".to_string(),
+            date_field: "/date".to_string(),
+            history_field: String::new(),
+            file: None,
+        }
+    }
+}
+
+/// The configured provider, `None` when neither a file nor a URL is set.
+pub fn from_source(source: &QuoteSource) -> Result<Option<Arc<dyn PriceProvider>>, PriceError> {
+    if let Some(path) = &source.file {
+        return Ok(Some(Arc::new(FileProvider::from_file(path)?)));
+    }
+    if source.latest_url.is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(Arc::new(HttpProvider::new(source.clone()))))
+}
+
+/// Latest quote and the history since `start`.
+#[derive(Debug, Clone)]
+pub struct PriceUpdate {
+    pub latest: Quote,
+    pub history: Vec<Quote>,
+}
+
+/// Fetches on its own thread so the UI keeps running, for use in a `Task`.
+/// A latest quote more than `stale_after_days` before `end` is reported as stale.
+pub async fn fetch(
+    provider: Arc<dyn PriceProvider>,
+    symbol: String,
+    start: NaiveDate,
+    end: NaiveDate,
+    stale_after_days: u32,
+) -> Result<PriceUpdate, PriceError> {
+    let (sender, receiver) = iced::futures::channel::oneshot::channel();
+    std::thread::spawn(move || {
+        let update = provider.history(&symbol, start, end).and_then(|history| {
+            let latest = provider.latest(&symbol)?;
+            if (end - latest.date).num_days() > stale_after_days as i64 {
+                return Err(PriceError::Stale(latest.date));
+            }
+            Ok(PriceUpdate { latest, history })
+        });
+        let _ = sender.send(update);
+    });
+    receiver
+        .await
+        .unwrap_or_else(|_| Err(PriceError::Network("fetch was cancelled".to_string())))
+}
+
+/// Securities still waiting for their prices during a refresh of all of them.
+#[derive(Debug, Default)]
+pub struct RefreshProgress {
+    pub total: usize,
+    pending: Vec<u8>,
+    pub failures: Vec<(String, PriceError)>, // security name and what went wrong
+}
+
+impl RefreshProgress {
+    pub fn new(security_ids: Vec<u8>) -> Self {
+        Self {
+            total: security_ids.len(),
+            pending: security_ids,
+            failures: Vec::new(),
+        }
+    }
+
+    /// Counts a security as done, fetches outside the refresh are ignored.
+    pub fn finish(&mut self, security_id: u8, name: &str, result: Result<(), PriceError>) {
+        let Some(index) = self.pending.iter().position(|id| *id == security_id) else {
+            return;
+        };
+        self.pending.remove(index);
+        if let Err(e) = result {
+            self.failures.push((name.to_string(), e));
+        }
+    }
+
+    pub fn done(&self) -> usize {
+        self.total - self.pending.len()
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.pending.is_empty()
+    }
+}
+
+/// JSON over HTTP from URL templates, which covers most free quote APIs.
+pub struct HttpProvider {
+    source: QuoteSource,
+    agent: ureq::Agent,
+}
+
+impl HttpProvider {
+    pub fn new(source: QuoteSource) -> Self {
+        let agent = ureq::Agent::config_builder()
+            .timeout_global(Some(TIMEOUT))
+            .build()
+            .into();
+        Self { source, agent }
+    }
+
+    fn get(&self, url: &str, symbol: &str) -> Result<serde_json::Value, PriceError> {
+        match self.agent.get(url).call() {
+            Ok(mut response) => response
+                .body_mut()
+                .read_json()
+                .map_err(|e| PriceError::InvalidResponse(e.to_string())),
+            Err(ureq::Error::StatusCode(404)) => Err(PriceError::UnknownSymbol(symbol.to_string())),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn quote(&self, value: &serde_json::Value) -> Result<Quote, PriceError> {
+        let field = |pointer: &str| {
+            value
+                .pointer(pointer)
+                .ok_or_else(|| PriceError::InvalidResponse(format!("no {}", pointer)))
+        };
+        let price = match field(&self.source.price_field)? {
+            serde_json::Value::Number(number) => number.as_f64(),
+            serde_json::Value::String(text) => text.trim().parse().ok(),
+            _ => None,
+        };
+        let (date, timestamp) = match field(&self.source.date_field)? {
+            serde_json::Value::Number(number) => {
+                let timestamp = number
+                    .as_i64()
+                    .and_then(|seconds| DateTime::from_timestamp(seconds, 0));
+                (timestamp.map(|time| time.date_naive()), timestamp)
+            }
+            // The day is taken as written, the time only when there is one
+            serde_json::Value::String(text) => (
+                text.get(..10).and_then(parse_date),
+                DateTime::parse_from_rfc3339(text)
+                    .ok()
+                    .map(|time| time.with_timezone(&Utc)),
+            ),
+            _ => (None, None),
+        };
+        match (date, price) {
+            (Some(date), Some(price)) => Ok(Quote {
+                date,
+                price: price as f32,
+                timestamp,
+            }),
+            _ => Err(PriceError::InvalidResponse(value.to_string())),
+        }
+    }
+}
+
+fn fill_template(template: &str, symbol: &str, start: NaiveDate, end: NaiveDate) -> String {
+    template
+        .replace("{symbol}", symbol)
+        .replace("{start}", &iso_date(start))
+        .replace("{end}", &iso_date(end))
+}
+
+impl PriceProvider for HttpProvider {
+    fn latest(&self, symbol: &str) -> Result<Quote, PriceError> {
+        let today = chrono::Local::now().date_naive();
+        let url = fill_template(&self.source.latest_url, symbol, today, today);
+        self.quote(&self.get(&url, symbol)?)
+    }
+
+    fn history(
+        &self,
+        symbol: &str,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<Vec<Quote>, PriceError> {
+        if self.source.history_url.is_empty() {
+            return Ok(Vec::new());
+        }
+        let url = fill_template(&self.source.history_url, symbol, start, end);
+        let response = self.get(&url, symbol)?;
+        let quotes = response
+            .pointer(&self.source.history_field)
+            .and_then(|list| list.as_array())
+            .ok_or_else(|| PriceError::InvalidResponse("no list of quotes".to_string()))?;
+        let mut quotes = quotes
+            .iter()
+            .map(|quote| self.quote(quote))
+            .collect::<Result<Vec<_>, _>>()?;
+        quotes.sort_by_key(|quote| quote.date);
+        Ok(quotes)
+    }
+}
+
+#[derive(Deserialize)]
+struct FileQuote {
+    date: String,
+    price: f32,
+}
+
+/// Quotes kept locally, for working offline and in tests. The file maps every
+/// symbol to its prices: {"VWCE": [{"date": "2024-01-02", "price": 105.2}]}.
+pub struct FileProvider {
+    quotes: HashMap<String, Vec<Quote>>,
+}
+
+impl FileProvider {
+    pub fn new(mut quotes: HashMap<String, Vec<Quote>>) -> Self {
+        for list in quotes.values_mut() {
+            list.sort_by_key(|quote| quote.date);
+        }
+        Self { quotes }
+    }
+
+    pub fn from_file(path: &Path) -> Result<Self, PriceError> {
+        let data = std::fs::read_to_string(path).map_err(|e| PriceError::Network(e.to_string()))?;
+        let file: HashMap<String, Vec<FileQuote>> =
+            serde_json::from_str(&data).map_err(|e| PriceError::InvalidResponse(e.to_string()))?;
+        let quotes = file
+            .into_iter()
+            .map(|(symbol, list)| {
+                let list = list
+                    .into_iter()
+                    .filter_map(|quote| {
+                        parse_date(&quote.date).map(|date| Quote {
+                            date,
+                            price: quote.price,
+                            timestamp: None,
+                        })
+                    })
+                    .collect();
+                (symbol, list)
+            })
+            .collect();
+        Ok(Self::new(quotes))
+    }
+
+    fn quotes(&self, symbol: &str) -> Result<&[Quote], PriceError> {
+        self.quotes
+            .get(symbol)
+            .map(Vec::as_slice)
+            .ok_or_else(|| PriceError::UnknownSymbol(symbol.to_string()))
+    }
+}
+
+impl PriceProvider for FileProvider {
+    fn latest(&self, symbol: &str) -> Result<Quote, PriceError> {
+        self.quotes(symbol)?
+            .last()
+            .copied()
+            .ok_or_else(|| PriceError::UnknownSymbol(symbol.to_string()))
+    }
+
+    fn history(
+        &self,
+        symbol: &str,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<Vec<Quote>, PriceError> {
+        Ok(self
+            .quotes(symbol)?
+            .iter()
+            .filter(|quote| quote.date >= start && quote.date <= end)
+            .copied()
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::security::test_support::date;
+
+    fn quote(date: NaiveDate, price: f32) -> Quote {
+        Quote {
+            date,
+            price,
+            timestamp: None,
+        }
+    }
+
+    fn provider() -> Arc<dyn PriceProvider> {
+        let quotes = HashMap::from([(
+            "VWCE".to_string(),
+            vec![
+                quote(date(2024, 1, 3), 101.0),
+                quote(date(2024, 1, 2), 100.0),
+                quote(date(2024, 1, 4), 102.0),
+            ],
+        )]);
+        Arc::new(FileProvider::new(quotes))
+    }
+
+    #[test]
+    fn fetch_returns_latest_and_history() {
+        let update = iced::futures::executor::block_on(fetch(
+            provider(),
+            "VWCE".to_string(),
+            date(2024, 1, 1),
+            date(2024, 1, 3),
+            7,
+        ))
+        .unwrap();
+        assert_eq!(update.latest.price, 102.0);
+        let prices: Vec<f32> = update.history.iter().map(|quote| quote.price).collect();
+        assert_eq!(prices, vec![100.0, 101.0]);
+    }
+
+    #[test]
+    fn old_quotes_are_stale() {
+        let result = iced::futures::executor::block_on(fetch(
+            provider(),
+            "VWCE".to_string(),
+            date(2024, 1, 1),
+            date(2024, 2, 1),
+            7,
+        ));
+        assert_eq!(result.unwrap_err(), PriceError::Stale(date(2024, 1, 4)));
+    }
+
+    #[test]
+    fn refresh_counts_every_security_once() {
+        let mut progress = RefreshProgress::new(vec![0, 1, 2]);
+        progress.finish(1, "B", Ok(()));
+        progress.finish(1, "B", Ok(()));
+        assert_eq!(progress.done(), 1);
+        progress.finish(0, "A", Err(PriceError::Timeout));
+        progress.finish(7, "Other", Err(PriceError::Timeout));
+        assert!(!progress.is_finished());
+        progress.finish(2, "C", Ok(()));
+        assert!(progress.is_finished());
+        assert_eq!(
+            progress.failures,
+            vec![("A".to_string(), PriceError::Timeout)]
+        );
+    }
+
+    #[test]
+    fn unknown_symbols_are_reported() {
+        assert_eq!(
+            provider().latest("XXXX"),
+            Err(PriceError::UnknownSymbol("XXXX".to_string()))
+        );
+    }
+
+    #[test]
+    fn http_quotes_are_read_through_the_pointers() {
+        let provider = HttpProvider::new(QuoteSource {
+            price_field: "/close".to_string(),
+            date_field: "/time".to_string(),
+            ..QuoteSource::default()
+        });
+        let quote = provider
+            .quote(&serde_json::json!({"close": "12.5", "time": 1704196800}))
+            .unwrap();
+        assert_eq!(quote.date, date(2024, 1, 2));
+        assert_eq!(quote.price, 12.5);
+        assert_eq!(quote.timestamp.unwrap().timestamp(), 1704196800);
+        let quote = provider
+            .quote(&serde_json::json!({"close": 3, "time": "2024-01-05T16:00:00+01:00"}))
+            .unwrap();
+        assert_eq!(quote.date, date(2024, 1, 5));
+        assert_eq!(
+            quote.timestamp.unwrap().to_rfc3339(),
+            "2024-01-05T15:00:00+00:00"
+        );
+        let quote = provider
+            .quote(&serde_json::json!({"close": 3, "time": "2024-01-08"}))
+            .unwrap();
+        assert_eq!(quote.timestamp, None);
+        assert!(provider.quote(&serde_json::json!({"close": 3})).is_err());
+    }
+
+    #[test]
+    fn templates_fill_symbol_and_dates() {
+        assert_eq!(
+            fill_template(
+                "https://example.com/{symbol}?from={start}&to={end}",
+                "IE00BK5BQT80",
+                date(2024, 1, 1),
+                date(2024, 2, 1)
+            ),
+            "https://example.com/IE00BK5BQT80?from=2024-01-01&to=2024-02-01"
+        );
+    }
+}
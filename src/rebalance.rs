@@ -0,0 +1,318 @@
+use serde::{Deserialize, Serialize};
+
+use crate::security::Security;
+
+pub const DEFAULT_TOLERANCE: f32 = 5.0;
+
+/// What a target weight applies to.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub enum TargetGroup {
+    Security(u8),
+    AssetClass(String),
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Target {
+    pub group: TargetGroup,
+    pub weight: f32,    // percent of the holdings
+    pub tolerance: f32, // percentage points either side before it counts as drifted
+}
+
+/// The part of a security the plan needs, so it can be worked out without entries.
+#[derive(Debug, Clone)]
+pub struct Holding {
+    pub security_id: u8,
+    pub asset_class: String,
+    pub quantity: f32,
+    pub price: f32,
+}
+
+impl Holding {
+    pub fn from_security(security: &Security) -> Self {
+        Self {
+            security_id: security.id,
+            asset_class: security.asset_class.clone(),
+            quantity: security.get_quantity() as f32,
+            price: security.get_current_price_per_unit(),
+        }
+    }
+
+    fn value(&self) -> f32 {
+        self.quantity * self.price
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Drift {
+    pub group: TargetGroup,
+    pub current: f32, // percent of the holdings
+    pub target: f32,
+    pub tolerance: f32,
+}
+
+impl Drift {
+    /// Percentage points above the target, negative when below.
+    pub fn drift(&self) -> f32 {
+        self.current - self.target
+    }
+
+    pub fn within_band(&self) -> bool {
+        self.drift().abs() <= self.tolerance
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct PlanOptions {
+    pub contribution: f32, // new money to invest, can be zero
+    pub buy_only: bool,
+    pub fractional: bool,
+    pub min_trade_value: f32, // smaller trades are left out
+}
+
+/// Buy when the quantity is positive, sell when negative.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Trade {
+    pub security_id: u8,
+    pub quantity: f32,
+    pub value: f32,
+}
+
+/// Current weight of every target group. Cash isn't part of the holdings,
+/// it's what the contribution is paid from.
+pub fn drift(holdings: &[Holding], targets: &[Target]) -> Vec<Drift> {
+    let total: f32 = holdings.iter().map(Holding::value).sum();
+    targets
+        .iter()
+        .map(|target| {
+            let value: f32 = holdings
+                .iter()
+                .filter(|holding| in_group(holding, &target.group))
+                .map(Holding::value)
+                .sum();
+            Drift {
+                group: target.group.clone(),
+                current: if total > 0.0 {
+                    value / total * 100.0
+                } else {
+                    0.0
+                },
+                target: target.weight,
+                tolerance: target.tolerance,
+            }
+        })
+        .collect()
+}
+
+/// Trades that bring the holdings plus the contribution closest to the targets.
+/// A security's own target wins over the target of its asset class, which is
+/// split among the rest of the class by current value. Securities without any
+/// target aren't traded.
+pub fn plan(holdings: &[Holding], targets: &[Target], options: PlanOptions) -> Vec<Trade> {
+    let total: f32 = holdings.iter().map(Holding::value).sum::<f32>() + options.contribution;
+    let target_values = target_values(holdings, targets, total);
+
+    let mut differences: Vec<(&Holding, f32)> = holdings
+        .iter()
+        .zip(target_values)
+        .filter_map(|(holding, target)| target.map(|target| (holding, target - holding.value())))
+        .filter(|(holding, _)| holding.price > 0.0)
+        .collect();
+    if options.buy_only {
+        // Only the contribution is spent, shared out by how far each one is behind
+        differences.retain(|(_, difference)| *difference > 0.0);
+        let behind: f32 = differences.iter().map(|(_, difference)| difference).sum();
+        if behind > options.contribution && behind > 0.0 {
+            let scale = options.contribution.max(0.0) / behind;
+            for (_, difference) in differences.iter_mut() {
+                *difference *= scale;
+            }
+        }
+    }
+
+    differences
+        .into_iter()
+        .filter_map(|(holding, difference)| {
+            let mut quantity = difference / holding.price;
+            if !options.fractional {
+                quantity = quantity.trunc();
+            }
+            // Never sell more than is held
+            quantity = quantity.max(-holding.quantity);
+            let value = quantity * holding.price;
+            (quantity != 0.0 && value.abs() >= options.min_trade_value).then_some(Trade {
+                security_id: holding.security_id,
+                quantity,
+                value,
+            })
+        })
+        .collect()
+}
+
+fn in_group(holding: &Holding, group: &TargetGroup) -> bool {
+    match group {
+        TargetGroup::Security(security_id) => holding.security_id == *security_id,
+        TargetGroup::AssetClass(asset_class) => holding.asset_class == *asset_class,
+    }
+}
+
+// Value every holding should have, None for holdings without a target
+fn target_values(holdings: &[Holding], targets: &[Target], total: f32) -> Vec<Option<f32>> {
+    let own_target = |holding: &Holding| {
+        targets
+            .iter()
+            .find(|target| target.group == TargetGroup::Security(holding.security_id))
+    };
+    holdings
+        .iter()
+        .map(|holding| {
+            if let Some(target) = own_target(holding) {
+                return Some(target.weight / 100.0 * total);
+            }
+            let class_target = targets.iter().find(|target| {
+                target.group == TargetGroup::AssetClass(holding.asset_class.clone())
+            })?;
+            let members: Vec<&Holding> = holdings
+                .iter()
+                .filter(|other| other.asset_class == holding.asset_class)
+                .filter(|other| own_target(other).is_none())
+                .collect();
+            let members_value: f32 = members.iter().map(|member| member.value()).sum();
+            let share = if members_value > 0.0 {
+                holding.value() / members_value
+            } else {
+                1.0 / members.len() as f32
+            };
+            Some(class_target.weight / 100.0 * total * share)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn holding(security_id: u8, asset_class: &str, quantity: f32, price: f32) -> Holding {
+        Holding {
+            security_id,
+            asset_class: asset_class.to_string(),
+            quantity,
+            price,
+        }
+    }
+
+    fn target(group: TargetGroup, weight: f32) -> Target {
+        Target {
+            group,
+            weight,
+            tolerance: DEFAULT_TOLERANCE,
+        }
+    }
+
+    #[test]
+    fn drift_against_the_tolerance_band() {
+        let holdings = [
+            holding(0, "Stocks", 7.0, 100.0),
+            holding(1, "Bonds", 3.0, 100.0),
+        ];
+        let targets = [
+            target(TargetGroup::AssetClass("Stocks".to_string()), 60.0),
+            target(TargetGroup::Security(1), 37.0),
+        ];
+        let drift = drift(&holdings, &targets);
+        assert_eq!(drift[0].current, 70.0);
+        assert_eq!(drift[0].drift(), 10.0);
+        assert!(!drift[0].within_band());
+        assert!((drift[1].drift() + 7.0).abs() < 1e-4);
+        assert!(!drift[1].within_band());
+    }
+
+    #[test]
+    fn rebalancing_sells_and_buys_towards_the_targets() {
+        let holdings = [
+            holding(0, "Stocks", 8.0, 100.0),
+            holding(1, "Bonds", 2.0, 50.0),
+        ];
+        let targets = [
+            target(TargetGroup::Security(0), 50.0),
+            target(TargetGroup::Security(1), 50.0),
+        ];
+        // 900 in total, 450 each
+        let trades = plan(&holdings, &targets, PlanOptions::default());
+        assert_eq!(
+            trades,
+            vec![
+                Trade {
+                    security_id: 0,
+                    quantity: -3.0,
+                    value: -300.0
+                },
+                Trade {
+                    security_id: 1,
+                    quantity: 7.0,
+                    value: 350.0
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn buy_only_spends_just_the_contribution() {
+        let holdings = [holding(0, "", 8.0, 100.0), holding(1, "", 2.0, 100.0)];
+        let targets = [
+            target(TargetGroup::Security(0), 50.0),
+            target(TargetGroup::Security(1), 50.0),
+        ];
+        let options = PlanOptions {
+            contribution: 200.0,
+            buy_only: true,
+            fractional: true,
+            min_trade_value: 0.0,
+        };
+        // 400 behind target on the second one, only 200 to spend
+        let trades = plan(&holdings, &targets, options);
+        assert_eq!(trades.len(), 1);
+        assert_eq!(trades[0].security_id, 1);
+        assert!((trades[0].quantity - 2.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn whole_units_and_minimum_trade_size() {
+        let holdings = [
+            holding(0, "Stocks", 0.0, 30.0),
+            holding(1, "Stocks", 0.0, 400.0),
+        ];
+        let targets = [target(TargetGroup::AssetClass("Stocks".to_string()), 100.0)];
+        let options = PlanOptions {
+            contribution: 1000.0,
+            min_trade_value: 100.0,
+            ..PlanOptions::default()
+        };
+        // 500 each: 16 units for 480, and one unit for 400
+        let trades = plan(&holdings, &targets, options);
+        assert_eq!(trades.len(), 2);
+        assert_eq!(trades[0].quantity, 16.0);
+        assert_eq!(trades[1].quantity, 1.0);
+
+        let options = PlanOptions {
+            min_trade_value: 450.0,
+            ..options
+        };
+        let trades = plan(&holdings, &targets, options);
+        assert_eq!(trades.len(), 1);
+        assert_eq!(trades[0].security_id, 0);
+    }
+
+    #[test]
+    fn security_target_wins_over_its_asset_class() {
+        let holdings = [
+            holding(0, "Stocks", 1.0, 100.0),
+            holding(1, "Stocks", 3.0, 100.0),
+        ];
+        let targets = [
+            target(TargetGroup::AssetClass("Stocks".to_string()), 20.0),
+            target(TargetGroup::Security(0), 80.0),
+        ];
+        let values = target_values(&holdings, &targets, 400.0);
+        assert_eq!(values, vec![Some(320.0), Some(80.0)]);
+    }
+}
@@ -0,0 +1,654 @@
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::Path;
+
+use chrono::NaiveDate;
+use printpdf::{BuiltinFont, IndirectFontRef, Mm, PdfDocument, PdfLayerReference};
+
+use crate::Overview;
+use crate::format::{NumberFormat, format_date, parse_date};
+use crate::i18n::{self, Text};
+use crate::returns::{self, ReturnPeriod};
+use crate::security::{Action, Security};
+use crate::settings::Settings;
+use crate::tax::{self, CapitalGain};
+
+const PAGE_WIDTH: f32 = 210.0;
+const PAGE_HEIGHT: f32 = 297.0;
+const MARGIN: f32 = 15.0;
+const LINE_HEIGHT: f32 = 5.0;
+
+#[derive(Debug, Clone, Copy)]
+pub struct ReportPeriod {
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+}
+
+impl ReportPeriod {
+    fn contains(&self, date: &str) -> bool {
+        parse_date(date).is_some_and(|date| date >= self.start && date <= self.end)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Transaction {
+    pub date: String,
+    pub security: String,
+    pub action: Action,
+    pub quantity: u8,
+    pub price_per_unit: f32,
+}
+
+/// Entries of all securities within the period, sorted by date.
+pub fn transactions_in(overview: &Overview, period: ReportPeriod) -> Vec<Transaction> {
+    let mut transactions: Vec<Transaction> = overview
+        .securities
+        .iter()
+        .flat_map(|security| {
+            security
+                .get_entries()
+                .into_iter()
+                .filter(|(_, date, _, _)| period.contains(date))
+                .map(|(action, date, quantity, price_per_unit)| Transaction {
+                    date,
+                    security: security.name.clone(),
+                    action,
+                    quantity,
+                    price_per_unit,
+                })
+        })
+        .collect();
+    transactions.sort_by_key(|t| parse_date(&t.date).unwrap_or(NaiveDate::MAX));
+    transactions
+}
+
+#[derive(Debug)]
+pub enum ReportError {
+    Io(std::io::Error),
+    Pdf(printpdf::Error),
+    UnsupportedFormat(String),
+}
+
+impl std::fmt::Display for ReportError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReportError::Io(e) => write!(f, "{}", e),
+            ReportError::Pdf(e) => write!(f, "{}", e),
+            ReportError::UnsupportedFormat(extension) => {
+                write!(f, "unsupported report format: {:?}", extension)
+            }
+        }
+    }
+}
+
+impl From<std::io::Error> for ReportError {
+    fn from(e: std::io::Error) -> Self {
+        ReportError::Io(e)
+    }
+}
+
+impl From<printpdf::Error> for ReportError {
+    fn from(e: printpdf::Error) -> Self {
+        ReportError::Pdf(e)
+    }
+}
+
+// Every part of the report is a titled table, so HTML and PDF render the same content
+struct Section {
+    title: String,
+    header: Vec<String>,
+    rows: Vec<Vec<String>>,
+}
+
+pub struct Report {
+    title: String,
+    subtitle: String,
+    sections: Vec<Section>,
+}
+
+impl Report {
+    /// Holdings and values as they were at the end of the period, the returns and
+    /// everything that happened within it. Amounts are in the base currency.
+    pub fn build(
+        overview: &Overview,
+        transactions: &[Transaction],
+        period: ReportPeriod,
+        today: NaiveDate,
+        settings: &Settings,
+    ) -> Self {
+        let base_currency = settings.base_currency.as_str();
+        let number_format = NumberFormat::new(settings);
+        let money = |value: f32| number_format.money(value, base_currency);
+        let percent = |value: Option<f64>| match value {
+            Some(value) => number_format.percent((value * 100.0) as f32),
+            None => "-".to_string(),
+        };
+        let date = |raw: &str| format_date(raw, &settings.date_format);
+        let tr = |text: Text| i18n::tr(settings.language, text).to_string();
+        let header = |titles: &[Text]| titles.iter().map(|title| tr(*title)).collect();
+
+        // The latest prices only count when the period runs up to today
+        let value_on = |security: &Security, day: NaiveDate| {
+            if day >= today {
+                security.get_total_current_value()
+            } else {
+                security.get_value_on(day)
+            }
+        };
+        let start_day = period.start.pred_opt().unwrap_or(period.start);
+        let held: Vec<&Security> = overview
+            .securities
+            .iter()
+            .filter(|security| security.get_quantity_on(period.end) > 0.0)
+            .collect();
+        let total_value: f32 = held.iter().map(|s| value_on(s, period.end)).sum();
+        let start_value: f32 = overview
+            .securities
+            .iter()
+            .map(|s| value_on(s, start_day))
+            .sum();
+        let total_invested: f32 = held
+            .iter()
+            .map(|s| s.get_invested_value_on(period.end, overview.cost_basis_method))
+            .sum();
+
+        let holdings = Section {
+            title: tr(Text::Holdings),
+            header: header(&[
+                Text::Security,
+                Text::Quantity,
+                Text::PricePerUnit,
+                Text::Invested,
+                Text::Value,
+                Text::Gain,
+            ]),
+            rows: held
+                .iter()
+                .map(|security| {
+                    let quantity = security.get_quantity_on(period.end);
+                    let invested =
+                        security.get_invested_value_on(period.end, overview.cost_basis_method);
+                    let value = value_on(security, period.end);
+                    vec![
+                        security.name.clone(),
+                        number_format.number(quantity, 0),
+                        money(value / quantity),
+                        money(invested),
+                        money(value),
+                        money(value - invested),
+                    ]
+                })
+                .collect(),
+        };
+
+        let allocation = Section {
+            title: tr(Text::Allocation),
+            header: header(&[Text::Security, Text::Value, Text::Weight]),
+            rows: held
+                .iter()
+                .map(|security| {
+                    let value = value_on(security, period.end);
+                    let weight = if total_value > 0.0 {
+                        value / total_value * 100.0
+                    } else {
+                        0.0
+                    };
+                    vec![
+                        security.name.clone(),
+                        money(value),
+                        number_format.percent(weight),
+                    ]
+                })
+                .collect(),
+        };
+
+        let mut income = Vec::new();
+        let mut realized = Vec::new();
+        for security in overview.securities.iter() {
+            for payment in security.get_income_payments() {
+                if period.contains(&payment.date) {
+                    income.push((payment.date.clone(), security.name.clone(), payment));
+                }
+            }
+            for (entry_date, gain) in security.get_realized_gains(overview.cost_basis_method) {
+                if period.contains(&entry_date) {
+                    realized.push((entry_date, security.name.clone(), gain));
+                }
+            }
+        }
+        let by_date = |raw: &String| parse_date(raw).unwrap_or(NaiveDate::MAX);
+        income.sort_by_key(|(entry_date, _, _)| by_date(entry_date));
+        realized.sort_by_key(|(entry_date, _, _)| by_date(entry_date));
+
+        let total_of = |action: Action| -> f32 {
+            transactions
+                .iter()
+                .filter(|t| t.action == action)
+                .map(|t| t.quantity as f32 * t.price_per_unit)
+                .sum()
+        };
+        let purchases = total_of(Action::Buy);
+        let sales = total_of(Action::Sell);
+
+        let total_income: f32 = income.iter().map(|(_, _, payment)| payment.net()).sum();
+        let total_realized: f32 = realized.iter().map(|(_, _, gain)| gain).sum();
+        let returns = returns::calculate(
+            &overview.securities.iter().collect::<Vec<_>>(),
+            None,
+            ReturnPeriod::Custom(period.start, period.end),
+            today,
+        );
+
+        let performance = Section {
+            title: tr(Text::Performance),
+            header: vec![String::new(), tr(Text::Value)],
+            rows: vec![
+                vec![tr(Text::ValueAtStart), money(start_value)],
+                vec![tr(Text::ValueAtEnd), money(total_value)],
+                vec![tr(Text::Invested), money(total_invested)],
+                vec![
+                    tr(Text::UnrealizedGain),
+                    money(total_value - total_invested),
+                ],
+                vec![tr(Text::PurchasesInPeriod), money(purchases)],
+                vec![tr(Text::SalesInPeriod), money(sales)],
+                vec![tr(Text::IncomeInPeriod), money(total_income)],
+                vec![tr(Text::RealizedGainsInPeriod), money(total_realized)],
+                vec![tr(Text::TimeWeighted), percent(returns.time_weighted)],
+                vec![tr(Text::MoneyWeighted), percent(returns.money_weighted)],
+            ],
+        };
+
+        let income = Section {
+            title: tr(Text::Income),
+            header: header(&[
+                Text::Date,
+                Text::Security,
+                Text::Type,
+                Text::Gross,
+                Text::TaxWithheld,
+                Text::Net,
+            ]),
+            rows: income
+                .iter()
+                .map(|(entry_date, name, payment)| {
+                    vec![
+                        date(entry_date),
+                        name.clone(),
+                        tr(payment.action.label()),
+                        money(payment.gross()),
+                        money(payment.tax_withheld),
+                        money(payment.net()),
+                    ]
+                })
+                .collect(),
+        };
+
+        let realized = Section {
+            title: tr(Text::RealizedGains),
+            header: header(&[Text::Date, Text::Security, Text::Gain]),
+            rows: realized
+                .iter()
+                .map(|(entry_date, name, gain)| vec![date(entry_date), name.clone(), money(*gain)])
+                .collect(),
+        };
+
+        let transactions = Section {
+            title: tr(Text::Transactions),
+            header: header(&[
+                Text::Date,
+                Text::Security,
+                Text::Action,
+                Text::Quantity,
+                Text::PricePerUnit,
+                Text::Total,
+            ]),
+            rows: transactions
+                .iter()
+                .map(|t| {
+                    vec![
+                        date(&t.date),
+                        t.security.clone(),
+                        tr(t.action.label()),
+                        t.quantity.to_string(),
+                        money(t.price_per_unit),
+                        money(t.quantity as f32 * t.price_per_unit),
+                    ]
+                })
+                .collect(),
+        };
+
+        Self {
+            title: tr(Text::ReportTitle),
+            subtitle: format!(
+                "{} - {} ({})",
+                period.start.format(&settings.date_format),
+                period.end.format(&settings.date_format),
+                base_currency
+            ),
+            sections: vec![
+                holdings,
+                allocation,
+                performance,
+                income,
+                realized,
+                transactions,
+            ],
+        }
+    }
+
+    /// Every sale of the tax year and the short and long-term totals.
+    pub fn capital_gains(
+        gains: &[CapitalGain],
+        year: i32,
+        base_currency: &str,
+        settings: &Settings,
+    ) -> Self {
+        let number_format = NumberFormat::new(settings);
+        let money = |value: f32| number_format.money(value, base_currency);
+        let day = |date: &NaiveDate| date.format(&settings.date_format).to_string();
+        let tr = |text: Text| i18n::tr(settings.language, text);
+        let header = |titles: &[Text]| titles.iter().map(|&t| tr(t).to_string()).collect();
+
+        let disposals = Section {
+            title: tr(Text::Disposals).to_string(),
+            header: header(&[
+                Text::Security,
+                Text::Acquired,
+                Text::Disposed,
+                Text::Quantity,
+                Text::Proceeds,
+                Text::CostBasis,
+                Text::Fee,
+                Text::Disallowed,
+                Text::Gain,
+                Text::Term,
+            ]),
+            rows: gains
+                .iter()
+                .map(|gain| {
+                    let acquired: Vec<String> = gain.acquired.iter().map(day).collect();
+                    vec![
+                        gain.security.clone(),
+                        acquired.join(", "),
+                        day(&gain.disposed),
+                        number_format.number(gain.quantity, 0),
+                        money(gain.proceeds),
+                        money(gain.cost_basis),
+                        money(gain.fees),
+                        money(gain.disallowed),
+                        money(gain.gain()),
+                        tr(gain.term.label()).to_string(),
+                    ]
+                })
+                .collect(),
+        };
+
+        let (short_term, long_term) = tax::totals(gains);
+        let summary = Section {
+            title: tr(Text::Summary).to_string(),
+            header: vec![String::new(), tr(Text::Gain).to_string()],
+            rows: vec![
+                vec![tr(Text::ShortTerm).to_string(), money(short_term)],
+                vec![tr(Text::LongTerm).to_string(), money(long_term)],
+                vec![tr(Text::Total).to_string(), money(short_term + long_term)],
+            ],
+        };
+
+        Self {
+            title: tr(Text::CapitalGains).to_string(),
+            subtitle: format!(
+                "{} ({}, {})",
+                year,
+                base_currency,
+                tr(Text::LongTermAfter).replace("{}", &settings.long_term_days.to_string())
+            ),
+            sections: vec![disposals, summary],
+        }
+    }
+
+    /// Writes the report as HTML or PDF depending on the file extension.
+    pub fn write(&self, path: &Path) -> Result<(), ReportError> {
+        let extension = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .unwrap_or_default()
+            .to_lowercase();
+        match extension.as_str() {
+            "html" | "htm" => Ok(std::fs::write(path, self.to_html())?),
+            "pdf" => self.write_pdf(path),
+            _ => Err(ReportError::UnsupportedFormat(extension)),
+        }
+    }
+
+    fn to_html(&self) -> String {
+        let mut html = String::new();
+        html.push_str("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n");
+        html.push_str(&format!("<title>{}</title>\n", escape_html(&self.title)));
+        html.push_str(
+            "<style>\
+             body { font-family: sans-serif; margin: 2em; }\
+             table { border-collapse: collapse; margin-bottom: 2em; }\
+             th, td { border: 1px solid #999; padding: 4px 8px; text-align: left; }\
+             th { background: #eee; }\
+             </style>\n",
+        );
+        html.push_str("</head>\n<body>\n");
+        html.push_str(&format!("<h1>{}</h1>\n", escape_html(&self.title)));
+        html.push_str(&format!("<p>{}</p>\n", escape_html(&self.subtitle)));
+        for section in self.sections.iter() {
+            html.push_str(&format!(
+                "<h2>{}</h2>\n<table>\n<tr>",
+                escape_html(&section.title)
+            ));
+            for title in section.header.iter() {
+                html.push_str(&format!("<th>{}</th>", escape_html(title)));
+            }
+            html.push_str("</tr>\n");
+            for row in section.rows.iter() {
+                html.push_str("<tr>");
+                for cell in row.iter() {
+                    html.push_str(&format!("<td>{}</td>", escape_html(cell)));
+                }
+                html.push_str("</tr>\n");
+            }
+            html.push_str("</table>\n");
+        }
+        html.push_str("</body>\n</html>\n");
+        html
+    }
+
+    fn write_pdf(&self, path: &Path) -> Result<(), ReportError> {
+        let (document, page, layer) = PdfDocument::new(
+            self.title.as_str(),
+            Mm(PAGE_WIDTH),
+            Mm(PAGE_HEIGHT),
+            "Layer 1",
+        );
+        let font = document.add_builtin_font(BuiltinFont::Helvetica)?;
+        let bold = document.add_builtin_font(BuiltinFont::HelveticaBold)?;
+        let mut writer = PdfWriter {
+            layer: document.get_page(page).get_layer(layer),
+            y: PAGE_HEIGHT - MARGIN,
+        };
+
+        writer.line(&document, &self.title, 16.0, &bold);
+        writer.line(&document, &self.subtitle, 10.0, &font);
+        for section in self.sections.iter() {
+            writer.y -= LINE_HEIGHT;
+            writer.line(&document, &section.title, 12.0, &bold);
+            let column_width = (PAGE_WIDTH - 2.0 * MARGIN) / section.header.len().max(1) as f32;
+            writer.row(&document, &section.header, column_width, &bold);
+            for row in section.rows.iter() {
+                writer.row(&document, row, column_width, &font);
+            }
+        }
+
+        document.save(&mut BufWriter::new(File::create(path)?))?;
+        Ok(())
+    }
+}
+
+// Keeps track of the current layer and height, starting a new page when one is full
+struct PdfWriter {
+    layer: PdfLayerReference,
+    y: f32,
+}
+
+impl PdfWriter {
+    fn next_line(&mut self, document: &printpdf::PdfDocumentReference) {
+        self.y -= LINE_HEIGHT;
+        if self.y < MARGIN {
+            let (page, layer) = document.add_page(Mm(PAGE_WIDTH), Mm(PAGE_HEIGHT), "Layer 1");
+            self.layer = document.get_page(page).get_layer(layer);
+            self.y = PAGE_HEIGHT - MARGIN - LINE_HEIGHT;
+        }
+    }
+
+    fn line(
+        &mut self,
+        document: &printpdf::PdfDocumentReference,
+        text: &str,
+        size: f32,
+        font: &IndirectFontRef,
+    ) {
+        self.next_line(document);
+        self.layer
+            .use_text(text, size, Mm(MARGIN), Mm(self.y), font);
+    }
+
+    fn row(
+        &mut self,
+        document: &printpdf::PdfDocumentReference,
+        cells: &[String],
+        column_width: f32,
+        font: &IndirectFontRef,
+    ) {
+        self.next_line(document);
+        for (col, cell) in cells.iter().enumerate() {
+            let x = MARGIN + col as f32 * column_width;
+            self.layer
+                .use_text(cell.as_str(), 8.0, Mm(x), Mm(self.y), font);
+        }
+    }
+}
+
+fn escape_html(input: &str) -> String {
+    input
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::i18n::Language;
+    use crate::security::test_support::{date, security};
+
+    fn overview() -> Overview {
+        let mut security = security(&[
+            (Action::Buy, "2024-01-10", 10, 100.0, 0.0, 0.0),
+            (Action::Buy, "2024-06-03", 5, 130.0, 0.0, 0.0),
+        ]);
+        security.update_current_price(120.0, "2024-03-28".to_string());
+        security.update_current_price(150.0, "2024-12-02".to_string());
+        security.calculate_total_invested_value(crate::security::CostBasisMethod::Fifo);
+        security.calculate_total_current_value();
+        let mut overview = Overview::new();
+        overview.securities.push(security);
+        overview
+    }
+
+    fn build(period: ReportPeriod, settings: &Settings) -> Report {
+        let overview = overview();
+        let transactions = transactions_in(&overview, period);
+        Report::build(
+            &overview,
+            &transactions,
+            period,
+            date(2024, 12, 31),
+            settings,
+        )
+    }
+
+    fn row<'a>(report: &'a Report, section: usize, label: &str) -> &'a [String] {
+        report.sections[section]
+            .rows
+            .iter()
+            .find(|row| row[0] == label)
+            .unwrap()
+    }
+
+    #[test]
+    fn holdings_as_of_the_end_of_the_period() {
+        let period = ReportPeriod {
+            start: date(2024, 1, 1),
+            end: date(2024, 3, 31),
+        };
+        let report = build(period, &Settings::default());
+        assert_eq!(
+            row(&report, 0, "ETF"),
+            ["ETF", "10", "€120.00", "€1,000.00", "€1,200.00", "€200.00"]
+        );
+        assert_eq!(row(&report, 1, "ETF")[2], "100.00 %");
+        assert_eq!(report.sections[5].rows.len(), 1);
+    }
+
+    #[test]
+    fn performance_of_the_period() {
+        let period = ReportPeriod {
+            start: date(2024, 4, 1),
+            end: date(2024, 12, 31),
+        };
+        let report = build(period, &Settings::default());
+        let value = |label: &str| row(&report, 2, label)[1].clone();
+        assert_eq!(value("Value at the start"), "€1,200.00");
+        assert_eq!(value("Value at the end"), "€2,250.00");
+        assert_eq!(value("Purchases in period"), "€650.00");
+        // 120 to 130 until the purchase and 130 to 150 after it
+        let time_weighted = 130.0 / 120.0 * 150.0 / 130.0 - 1.0;
+        assert_eq!(
+            value("Time-weighted"),
+            format!("{:.2} %", time_weighted * 100.0)
+        );
+    }
+
+    #[test]
+    fn labels_follow_the_language() {
+        let mut settings = Settings::default();
+        settings.language = Language::German;
+        let period = ReportPeriod {
+            start: date(2024, 1, 1),
+            end: date(2024, 12, 31),
+        };
+        let report = build(period, &settings);
+        assert_eq!(report.title, "Portfoliobericht");
+        assert_eq!(report.sections[0].title, "Bestand");
+        assert_eq!(report.sections[0].header[0], "Wertpapier");
+        assert!(report.to_html().contains("<h2>Wertentwicklung</h2>"));
+    }
+
+    #[test]
+    fn capital_gains_labels_follow_the_language() {
+        let mut settings = Settings::default();
+        settings.language = Language::German;
+        let gains = [crate::tax::CapitalGain {
+            security: "ETF".to_string(),
+            acquired: vec![date(2023, 1, 10)],
+            disposed: date(2024, 6, 3),
+            quantity: 5.0,
+            proceeds: 650.0,
+            cost_basis: 500.0,
+            fees: 0.0,
+            disallowed: 0.0,
+            term: crate::tax::Term::Long,
+        }];
+        let report = Report::capital_gains(&gains, 2024, "EUR", &settings);
+        assert_eq!(report.sections[0].title, "Veräußerungen");
+        assert_eq!(report.sections[0].rows[0][9], "Langfristig");
+        assert_eq!(report.sections[1].rows[1][0], "Langfristig");
+        assert_eq!(report.subtitle, "2024 (EUR, langfristig nach 365 Tagen)");
+    }
+}
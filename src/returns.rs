@@ -0,0 +1,317 @@
+use chrono::{Datelike, Months, NaiveDate};
+
+use crate::benchmark;
+use crate::security::Security;
+
+const DAYS_PER_YEAR: f64 = 365.0;
+const XIRR_MAX_ITERATIONS: usize = 100;
+const XIRR_TOLERANCE: f64 = 1e-9;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ReturnPeriod {
+    OneMonth,
+    ThreeMonths,
+    YearToDate,
+    OneYear,
+    ThreeYears,
+    SinceInception,
+    Custom(NaiveDate, NaiveDate),
+}
+
+impl ReturnPeriod {
+    pub const STANDARD: [ReturnPeriod; 6] = [
+        ReturnPeriod::OneMonth,
+        ReturnPeriod::ThreeMonths,
+        ReturnPeriod::YearToDate,
+        ReturnPeriod::OneYear,
+        ReturnPeriod::ThreeYears,
+        ReturnPeriod::SinceInception,
+    ];
+
+    // The start is the end of the day before the period, its value is the starting capital
+    fn range(self, today: NaiveDate, inception: NaiveDate) -> (NaiveDate, NaiveDate) {
+        let before_inception = inception.pred_opt().unwrap_or(inception);
+        let months_back = |months| today.checked_sub_months(Months::new(months));
+        let (start, end) = match self {
+            ReturnPeriod::OneMonth => (months_back(1), today),
+            ReturnPeriod::ThreeMonths => (months_back(3), today),
+            ReturnPeriod::YearToDate => {
+                (today.with_ordinal(1).and_then(|day| day.pred_opt()), today)
+            }
+            ReturnPeriod::OneYear => (months_back(12), today),
+            ReturnPeriod::ThreeYears => (months_back(36), today),
+            ReturnPeriod::SinceInception => (Some(before_inception), today),
+            ReturnPeriod::Custom(start, end) => (start.pred_opt(), end),
+        };
+        (start.unwrap_or(before_inception).max(before_inception), end)
+    }
+}
+
+impl std::fmt::Display for ReturnPeriod {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReturnPeriod::OneMonth => write!(f, "1M"),
+            ReturnPeriod::ThreeMonths => write!(f, "3M"),
+            ReturnPeriod::YearToDate => write!(f, "YTD"),
+            ReturnPeriod::OneYear => write!(f, "1Y"),
+            ReturnPeriod::ThreeYears => write!(f, "3Y"),
+            ReturnPeriod::SinceInception => write!(f, "Max"),
+            ReturnPeriod::Custom(start, end) => write!(f, "{} - {}", start, end),
+        }
+    }
+}
+
+/// Both returns as fractions (0.1 is 10%), `None` when there isn't enough data.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Returns {
+    pub time_weighted: Option<f64>,  // cumulative over the period
+    pub money_weighted: Option<f64>, // annualized (XIRR)
+    pub benchmark: Option<f64>,      // cumulative over the same period
+}
+
+impl Returns {
+    /// How far the time-weighted return is ahead of the benchmark.
+    pub fn tracking_difference(&self) -> Option<f64> {
+        Some(self.time_weighted? - self.benchmark?)
+    }
+}
+
+/// Returns of the given securities together, valued at the end of every day with cash flows.
+/// Today's value uses the current price, earlier days the price history.
+/// The benchmark is a growth index measured over the same days.
+pub fn calculate(
+    securities: &[&Security],
+    benchmark: Option<&[(NaiveDate, f64)]>,
+    period: ReturnPeriod,
+    today: NaiveDate,
+) -> Returns {
+    let flows: Vec<(NaiveDate, f64)> = securities
+        .iter()
+        .flat_map(|security| security.get_cash_flows())
+        .map(|(date, amount)| (date, amount as f64))
+        .collect();
+    let Some(inception) = flows.iter().map(|(date, _)| *date).min() else {
+        return Returns::default();
+    };
+    let (start, end) = period.range(today, inception);
+    if start >= end {
+        return Returns::default();
+    }
+
+    let value_on = |date: NaiveDate| -> f64 {
+        securities
+            .iter()
+            .map(|security| {
+                if date >= today {
+                    security.get_total_current_value() as f64
+                } else {
+                    security.get_value_on(date) as f64
+                }
+            })
+            .sum()
+    };
+    let flow_on = |date: NaiveDate| -> f64 {
+        flows
+            .iter()
+            .filter(|(day, _)| *day == date)
+            .map(|(_, amount)| amount)
+            .sum()
+    };
+
+    let mut days: Vec<NaiveDate> = flows
+        .iter()
+        .map(|(date, _)| *date)
+        .filter(|date| *date > start && *date < end)
+        .collect();
+    days.push(end);
+    days.sort();
+    days.dedup();
+
+    let start_value = value_on(start);
+    let mut valuations = vec![(start_value, 0.0)];
+    valuations.extend(days.iter().map(|day| (value_on(*day), flow_on(*day))));
+
+    // The investor's side: money in is negative, money out and the final value positive
+    let mut investor_flows = vec![(start, -start_value)];
+    investor_flows.extend(days.iter().map(|day| (*day, -flow_on(*day))));
+    if let Some(last) = investor_flows.last_mut() {
+        last.1 += value_on(end);
+    }
+
+    Returns {
+        time_weighted: time_weighted_return(&valuations),
+        money_weighted: xirr(&investor_flows),
+        benchmark: benchmark.and_then(|index| benchmark::period_return(index, start, end)),
+    }
+}
+
+/// Chains the growth of every sub-period between cash flows. Each valuation is
+/// (value at the end of the day, cash flow into the investment that day), the
+/// first one is the starting point. Sub-periods starting without value are skipped.
+pub fn time_weighted_return(valuations: &[(f64, f64)]) -> Option<f64> {
+    let mut growth = 1.0;
+    let mut measured = false;
+    for pair in valuations.windows(2) {
+        let (previous_value, _) = pair[0];
+        let (value, flow) = pair[1];
+        if previous_value > 0.0 {
+            growth *= (value - flow) / previous_value;
+            measured = true;
+        }
+    }
+    measured.then_some(growth - 1.0)
+}
+
+/// Annual rate that brings the dated cash flows to a net present value of zero,
+/// using actual days over 365 like spreadsheet XIRR. Needs money in and out.
+pub fn xirr(flows: &[(NaiveDate, f64)]) -> Option<f64> {
+    let first = flows.iter().map(|(date, _)| *date).min()?;
+    let flows: Vec<(f64, f64)> = flows
+        .iter()
+        .filter(|(_, amount)| *amount != 0.0)
+        .map(|(date, amount)| ((*date - first).num_days() as f64 / DAYS_PER_YEAR, *amount))
+        .collect();
+    if !flows.iter().any(|(_, amount)| *amount < 0.0)
+        || !flows.iter().any(|(_, amount)| *amount > 0.0)
+    {
+        return None;
+    }
+
+    let npv = |rate: f64| -> f64 {
+        flows
+            .iter()
+            .map(|(years, amount)| amount / (1.0 + rate).powf(*years))
+            .sum()
+    };
+    let derivative = |rate: f64| -> f64 {
+        flows
+            .iter()
+            .map(|(years, amount)| -years * amount / (1.0 + rate).powf(years + 1.0))
+            .sum()
+    };
+
+    // Newton's method first, it's fast for the usual rates
+    let mut rate = 0.1;
+    for _ in 0..XIRR_MAX_ITERATIONS {
+        let value = npv(rate);
+        if value.abs() < XIRR_TOLERANCE {
+            return Some(rate);
+        }
+        let slope = derivative(rate);
+        if slope == 0.0 || !slope.is_finite() {
+            break;
+        }
+        let next = rate - value / slope;
+        if !next.is_finite() || next <= -1.0 {
+            break;
+        }
+        if (next - rate).abs() < XIRR_TOLERANCE {
+            return Some(next);
+        }
+        rate = next;
+    }
+
+    // Bisection as fallback, the net present value falls as the rate grows
+    let (mut low, mut high) = (-0.9999, 10.0);
+    if npv(low).signum() == npv(high).signum() {
+        return None;
+    }
+    for _ in 0..1000 {
+        let middle = (low + high) / 2.0;
+        if npv(middle).signum() == npv(low).signum() {
+            low = middle;
+        } else {
+            high = middle;
+        }
+        if high - low < XIRR_TOLERANCE {
+            break;
+        }
+    }
+    Some((low + high) / 2.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    fn assert_close(actual: Option<f64>, expected: f64) {
+        let actual = actual.expect("no return calculated");
+        assert!(
+            (actual - expected).abs() < 1e-6,
+            "expected {}, got {}",
+            expected,
+            actual
+        );
+    }
+
+    #[test]
+    fn xirr_of_a_single_year() {
+        let flows = [(date(2021, 1, 1), -1000.0), (date(2022, 1, 1), 1100.0)];
+        assert_close(xirr(&flows), 0.1);
+    }
+
+    #[test]
+    fn xirr_matches_spreadsheet_example() {
+        let flows = [
+            (date(2008, 1, 1), -10000.0),
+            (date(2008, 3, 1), 2750.0),
+            (date(2008, 10, 30), 4250.0),
+            (date(2009, 2, 15), 3250.0),
+            (date(2009, 4, 1), 2750.0),
+        ];
+        assert_close(xirr(&flows), 0.373362535);
+    }
+
+    #[test]
+    fn xirr_of_a_loss() {
+        let flows = [(date(2021, 1, 1), -1000.0), (date(2023, 1, 1), 810.0)];
+        // 730 days, so two years of -10%
+        assert_close(xirr(&flows), -0.1);
+    }
+
+    #[test]
+    fn xirr_needs_money_in_and_out() {
+        assert_eq!(xirr(&[(date(2021, 1, 1), -1000.0)]), None);
+        assert_eq!(xirr(&[]), None);
+    }
+
+    #[test]
+    fn twr_ignores_the_size_of_deposits() {
+        // +10% on 100, then 50 deposited and +10% again
+        let valuations = [(100.0, 0.0), (160.0, 50.0), (176.0, 0.0)];
+        assert_close(time_weighted_return(&valuations), 0.21);
+    }
+
+    #[test]
+    fn twr_counts_withdrawals_as_part_of_the_return() {
+        // +20%, then a dividend of 20 paid out leaving the value at 100
+        let valuations = [(100.0, 0.0), (100.0, -20.0)];
+        assert_close(time_weighted_return(&valuations), 0.2);
+    }
+
+    #[test]
+    fn twr_skips_the_period_before_the_first_purchase() {
+        let valuations = [(0.0, 0.0), (100.0, 100.0), (105.0, 0.0)];
+        assert_close(time_weighted_return(&valuations), 0.05);
+        assert_eq!(time_weighted_return(&[(0.0, 0.0), (100.0, 100.0)]), None);
+    }
+
+    #[test]
+    fn custom_period_starts_the_day_before() {
+        let today = date(2024, 6, 30);
+        let period = ReturnPeriod::Custom(date(2024, 1, 1), date(2024, 3, 31));
+        assert_eq!(
+            period.range(today, date(2020, 1, 1)),
+            (date(2023, 12, 31), date(2024, 3, 31))
+        );
+        // Periods reaching back further than the first entry start there
+        assert_eq!(
+            ReturnPeriod::ThreeYears.range(today, date(2023, 5, 10)),
+            (date(2023, 5, 9), today)
+        );
+    }
+}
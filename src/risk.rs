@@ -0,0 +1,293 @@
+use chrono::NaiveDate;
+
+use crate::benchmark::level_on;
+use crate::security::Security;
+
+const DAYS_PER_YEAR: f64 = 365.0;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Drawdown {
+    pub depth: f64, // fraction lost from the peak, 0.2 is -20%
+    pub peak: NaiveDate,
+    pub trough: NaiveDate,
+}
+
+/// Risk statistics as fractions, `None` when there are too few prices to tell.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct RiskMetrics {
+    pub volatility: Option<f64>, // annualized
+    pub max_drawdown: Option<Drawdown>,
+    pub sharpe: Option<f64>,
+    pub sortino: Option<f64>,
+    pub beta: Option<f64>,
+}
+
+/// Value of one unit invested at the start, on every day the holdings could be valued.
+/// Money put in or taken out doesn't move it, only price changes and income do.
+pub fn growth_index(securities: &[&Security], today: NaiveDate) -> Vec<(NaiveDate, f64)> {
+    let mut days: Vec<NaiveDate> = securities
+        .iter()
+        .flat_map(|security| security.get_price_points())
+        .map(|(day, _)| day)
+        .filter(|day| *day < today)
+        .collect();
+    days.push(today);
+    days.sort();
+    days.dedup();
+
+    let flows: Vec<(NaiveDate, f64)> = securities
+        .iter()
+        .flat_map(|security| security.get_cash_flows())
+        .map(|(day, amount)| (day, amount as f64))
+        .collect();
+    // Each security is valued once for all days, today at its current value
+    let mut values = vec![0.0; days.len()];
+    for security in securities.iter() {
+        for ((total, value), day) in values
+            .iter_mut()
+            .zip(security.get_values_on(&days))
+            .zip(days.iter())
+        {
+            *total += if *day >= today {
+                security.get_total_current_value() as f64
+            } else {
+                value as f64
+            };
+        }
+    }
+
+    let mut index = Vec::new();
+    let mut previous_day: Option<NaiveDate> = None;
+    let mut previous_value = 0.0;
+    let mut level = 1.0;
+    for (day, value) in days.into_iter().zip(values) {
+        // Income is often paid on a day without a price, it counts for the next valuation
+        let flow: f64 = flows
+            .iter()
+            .filter(|(flow_day, _)| {
+                *flow_day <= day && previous_day.is_none_or(|previous| *flow_day > previous)
+            })
+            .map(|(_, amount)| amount)
+            .sum();
+        if previous_value > 0.0 {
+            level *= (value - flow) / previous_value;
+        }
+        // Nothing is measured before the first purchase
+        if previous_value > 0.0 || value > 0.0 {
+            index.push((day, level));
+        }
+        previous_day = Some(day);
+        previous_value = value;
+    }
+    index
+}
+
+/// Statistics of a growth index, the benchmark index is only needed for beta.
+/// Prices aren't recorded daily, so returns are annualized with the average
+/// number of days between them. The risk-free rate is annual, e.g. 0.02.
+pub fn calculate(
+    index: &[(NaiveDate, f64)],
+    benchmark: Option<&[(NaiveDate, f64)]>,
+    risk_free_rate: f64,
+) -> RiskMetrics {
+    let returns = period_returns(index);
+    let mut metrics = RiskMetrics {
+        max_drawdown: max_drawdown(index),
+        ..RiskMetrics::default()
+    };
+    if returns.len() < 2 {
+        return metrics;
+    }
+
+    let (first, last) = (index[0].0, index[index.len() - 1].0);
+    let periods_per_year = DAYS_PER_YEAR * returns.len() as f64 / (last - first).num_days() as f64;
+    let values: Vec<f64> = returns.iter().map(|(_, _, value)| *value).collect();
+    let mean = mean(&values);
+    let volatility = standard_deviation(&values) * periods_per_year.sqrt();
+    let excess_return = mean * periods_per_year - risk_free_rate;
+
+    // Only returns below the risk-free rate count as downside
+    let risk_free_per_period = risk_free_rate / periods_per_year;
+    let downside = (values
+        .iter()
+        .map(|value| (value - risk_free_per_period).min(0.0).powi(2))
+        .sum::<f64>()
+        / values.len() as f64)
+        .sqrt()
+        * periods_per_year.sqrt();
+
+    metrics.volatility = Some(volatility);
+    metrics.sharpe = (volatility > 0.0).then(|| excess_return / volatility);
+    metrics.sortino = (downside > 0.0).then(|| excess_return / downside);
+    metrics.beta = benchmark.and_then(|benchmark| beta(&returns, benchmark));
+    metrics
+}
+
+// (start, end, return) between consecutive points
+fn period_returns(index: &[(NaiveDate, f64)]) -> Vec<(NaiveDate, NaiveDate, f64)> {
+    index
+        .windows(2)
+        .filter(|pair| pair[0].1 > 0.0)
+        .map(|pair| (pair[0].0, pair[1].0, pair[1].1 / pair[0].1 - 1.0))
+        .collect()
+}
+
+pub fn max_drawdown(index: &[(NaiveDate, f64)]) -> Option<Drawdown> {
+    let (mut peak_day, mut peak) = *index.first()?;
+    let mut worst: Option<Drawdown> = None;
+    for (day, level) in index.iter().copied() {
+        if level > peak {
+            (peak_day, peak) = (day, level);
+        } else if peak > 0.0 {
+            let depth = 1.0 - level / peak;
+            if depth > worst.map_or(0.0, |drawdown| drawdown.depth) {
+                worst = Some(Drawdown {
+                    depth,
+                    peak: peak_day,
+                    trough: day,
+                });
+            }
+        }
+    }
+    worst
+}
+
+// The benchmark is valued at the same dates as the returns it's compared to
+fn beta(returns: &[(NaiveDate, NaiveDate, f64)], benchmark: &[(NaiveDate, f64)]) -> Option<f64> {
+    let pairs: Vec<(f64, f64)> = returns
+        .iter()
+        .filter_map(|(start, end, value)| {
+            let benchmark_return = level_on(benchmark, *end)? / level_on(benchmark, *start)? - 1.0;
+            Some((*value, benchmark_return))
+        })
+        .collect();
+    if pairs.len() < 2 {
+        return None;
+    }
+    let subject: Vec<f64> = pairs.iter().map(|(value, _)| *value).collect();
+    let market: Vec<f64> = pairs.iter().map(|(_, value)| *value).collect();
+    let (subject_mean, market_mean) = (mean(&subject), mean(&market));
+    let covariance: f64 = pairs
+        .iter()
+        .map(|(value, market_value)| (value - subject_mean) * (market_value - market_mean))
+        .sum::<f64>()
+        / (pairs.len() - 1) as f64;
+    let variance = standard_deviation(&market).powi(2);
+    (variance > 0.0).then(|| covariance / variance)
+}
+
+fn mean(values: &[f64]) -> f64 {
+    values.iter().sum::<f64>() / values.len() as f64
+}
+
+// Sample standard deviation
+fn standard_deviation(values: &[f64]) -> f64 {
+    let mean = mean(values);
+    (values
+        .iter()
+        .map(|value| (value - mean).powi(2))
+        .sum::<f64>()
+        / (values.len() - 1) as f64)
+        .sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::security::Action;
+    use crate::security::test_support::{date, security};
+
+    // One point at the start of every month of 2021, 365 days apart in total
+    fn monthly(levels: &[f64]) -> Vec<(NaiveDate, f64)> {
+        levels
+            .iter()
+            .enumerate()
+            .map(|(i, level)| {
+                let day = if i == 12 {
+                    date(2022, 1, 1)
+                } else {
+                    date(2021, i as u32 + 1, 1)
+                };
+                (day, *level)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn drawdown_finds_the_deepest_fall_and_its_dates() {
+        let index = [
+            (date(2021, 1, 1), 100.0),
+            (date(2021, 2, 1), 120.0),
+            (date(2021, 3, 1), 90.0),
+            (date(2021, 4, 1), 130.0),
+            (date(2021, 5, 1), 110.0),
+        ];
+        let drawdown = max_drawdown(&index).unwrap();
+        assert!((drawdown.depth - 0.25).abs() < 1e-9);
+        assert_eq!(drawdown.peak, date(2021, 2, 1));
+        assert_eq!(drawdown.trough, date(2021, 3, 1));
+        assert_eq!(
+            max_drawdown(&[(date(2021, 1, 1), 1.0), (date(2021, 2, 1), 2.0)]),
+            None
+        );
+    }
+
+    #[test]
+    fn volatility_is_annualized_from_the_spacing() {
+        // Alternating +10% and -10% months
+        let mut levels = vec![1.0];
+        for i in 0..12 {
+            let last = levels[levels.len() - 1];
+            levels.push(if i % 2 == 0 { last * 1.1 } else { last * 0.9 });
+        }
+        let metrics = calculate(&monthly(&levels), None, 0.0);
+        // Sample deviation of six +0.1 and six -0.1 is 0.1 * sqrt(12 / 11)
+        let expected = 0.1 * (12.0f64 / 11.0).sqrt() * 12.0f64.sqrt();
+        assert!((metrics.volatility.unwrap() - expected).abs() < 1e-9);
+        assert!(metrics.sharpe.unwrap().abs() < 1e-9);
+        assert!(metrics.sortino.unwrap().abs() < 1e-9);
+    }
+
+    #[test]
+    fn beta_of_twice_the_benchmark_moves() {
+        let benchmark: Vec<f64> = (0..13)
+            .scan(1.0, |level, i| {
+                let current = *level;
+                *level *= if i % 3 == 0 { 1.02 } else { 0.99 };
+                Some(current)
+            })
+            .collect();
+        let doubled: Vec<f64> = std::iter::once(1.0)
+            .chain(benchmark.windows(2).scan(1.0, |level, pair| {
+                *level *= 1.0 + 2.0 * (pair[1] / pair[0] - 1.0);
+                Some(*level)
+            }))
+            .collect();
+        let metrics = calculate(&monthly(&doubled), Some(&monthly(&benchmark)), 0.0);
+        assert!((metrics.beta.unwrap() - 2.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn steady_growth_has_no_sortino() {
+        let levels: Vec<f64> = (0..13).map(|i| 1.01f64.powi(i)).collect();
+        let metrics = calculate(&monthly(&levels), None, 0.0);
+        assert!(metrics.volatility.unwrap() < 1e-9);
+        assert_eq!(metrics.sortino, None);
+        assert_eq!(metrics.max_drawdown, None);
+    }
+
+    #[test]
+    fn income_between_prices_counts() {
+        let mut security = security(&[
+            (Action::Buy, "2021-01-04", 10, 100.0, 0.0, 0.0),
+            // Paid on a day without a price
+            (Action::Dividend, "2021-02-15", 10, 5.0, 0.0, 0.0),
+        ]);
+        security.update_current_price(100.0, "2021-02-01".to_string());
+        security.update_current_price(100.0, "2021-03-01".to_string());
+        security.calculate_total_current_value();
+
+        let index = growth_index(&[&security], date(2021, 3, 1));
+        assert_eq!(index.last(), Some(&(date(2021, 3, 1), 1.05)));
+    }
+}
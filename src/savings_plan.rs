@@ -0,0 +1,318 @@
+use chrono::{Datelike, Months, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+use crate::format::{iso_date, parse_date};
+use crate::i18n::{self, Language, Text};
+use crate::security::Security;
+
+/// How much every execution of a plan buys.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+pub enum PlanSize {
+    Amount(f32), // money, buys as many whole units as it pays for
+    Quantity(u8),
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Frequency {
+    #[default]
+    Monthly,
+    Quarterly,
+    SemiAnnually,
+    Annually,
+}
+
+impl Frequency {
+    pub const ALL: [Frequency; 4] = [
+        Frequency::Monthly,
+        Frequency::Quarterly,
+        Frequency::SemiAnnually,
+        Frequency::Annually,
+    ];
+
+    /// Reads the frequency in any of the UI languages.
+    pub fn parse(input: &str) -> Option<Self> {
+        let input = input.trim().to_lowercase();
+        match input.as_str() {
+            "" => return Some(Frequency::Monthly),
+            "semi-annually" => return Some(Frequency::SemiAnnually),
+            "yearly" => return Some(Frequency::Annually),
+            _ => {}
+        }
+        Frequency::ALL.into_iter().find(|frequency| {
+            Language::ALL
+                .iter()
+                .any(|language| i18n::tr(*language, frequency.label()) == input)
+        })
+    }
+
+    pub fn label(&self) -> Text {
+        match self {
+            Frequency::Monthly => Text::FrequencyMonthly,
+            Frequency::Quarterly => Text::FrequencyQuarterly,
+            Frequency::SemiAnnually => Text::FrequencySemiAnnually,
+            Frequency::Annually => Text::FrequencyAnnually,
+        }
+    }
+
+    fn months(self) -> u32 {
+        match self {
+            Frequency::Monthly => 1,
+            Frequency::Quarterly => 3,
+            Frequency::SemiAnnually => 6,
+            Frequency::Annually => 12,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct SavingsPlan {
+    pub size: PlanSize,
+    pub frequency: Frequency,
+    start: String,         // ISO
+    end: Option<String>,   // ISO, open ended when None
+    pub day_of_month: u32, // days past the end of a month fall on its last day
+    #[serde(default)]
+    handled: Vec<String>, // ISO dates of the buys that were confirmed or skipped
+}
+
+impl SavingsPlan {
+    pub fn new(
+        size: PlanSize,
+        frequency: Frequency,
+        start: NaiveDate,
+        end: Option<NaiveDate>,
+        day_of_month: u32,
+    ) -> Self {
+        Self {
+            size,
+            frequency,
+            start: iso_date(start),
+            end: end.map(iso_date),
+            day_of_month,
+            handled: Vec::new(),
+        }
+    }
+
+    pub fn get_start(&self) -> Option<NaiveDate> {
+        parse_date(&self.start)
+    }
+
+    pub fn get_end(&self) -> Option<NaiveDate> {
+        self.end.as_deref().and_then(parse_date)
+    }
+
+    /// Takes the due date out of the pending ones, after it was confirmed or skipped.
+    pub fn mark_handled(&mut self, date: NaiveDate) {
+        let date = iso_date(date);
+        if !self.handled.contains(&date) {
+            self.handled.push(date);
+        }
+    }
+
+    /// Days the plan is due on from its start up to the given day.
+    pub fn due_dates(&self, until: NaiveDate) -> Vec<NaiveDate> {
+        let Some(start) = self.get_start() else {
+            return Vec::new();
+        };
+        let first_month = NaiveDate::from_ymd_opt(start.year(), start.month(), 1)
+            .expect("the first of a month is always valid");
+        let until = self.get_end().map_or(until, |end| end.min(until));
+        let mut dates = Vec::new();
+        for step in 0.. {
+            let Some(month) =
+                first_month.checked_add_months(Months::new(step * self.frequency.months()))
+            else {
+                break;
+            };
+            let date = day_in_month(month, self.day_of_month);
+            if date > until {
+                break;
+            }
+            if date >= start {
+                dates.push(date);
+            }
+        }
+        dates
+    }
+
+    /// What the plan invests per month on average while it runs, units at the given price.
+    pub fn monthly_amount(&self, price_per_unit: f32, today: NaiveDate) -> f32 {
+        if self.get_end().is_some_and(|end| end < today) {
+            return 0.0;
+        }
+        let per_execution = match self.size {
+            PlanSize::Amount(amount) => amount,
+            PlanSize::Quantity(quantity) => quantity as f32 * price_per_unit,
+        };
+        per_execution / self.frequency.months() as f32
+    }
+
+    /// Due dates that still wait to be confirmed or skipped.
+    pub fn pending_dates(&self, today: NaiveDate) -> Vec<NaiveDate> {
+        self.due_dates(today)
+            .into_iter()
+            .filter(|date| !self.handled.contains(&iso_date(*date)))
+            .collect()
+    }
+}
+
+/// A buy a plan is due for, priced with the latest price on its day.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PendingEntry {
+    pub security_id: u8,
+    pub plan: usize, // index into the security's plans
+    pub date: NaiveDate,
+    pub quantity: u8,
+    pub price_per_unit: Option<f32>, // None when no price is known yet
+}
+
+impl PendingEntry {
+    /// Whether there is anything to buy at a known price.
+    pub fn can_confirm(&self) -> bool {
+        self.quantity > 0 && self.price_per_unit.is_some()
+    }
+}
+
+/// Buys of every plan of the securities due up to today, oldest first.
+pub fn pending_entries(securities: &[Security], today: NaiveDate) -> Vec<PendingEntry> {
+    let mut entries: Vec<PendingEntry> = securities
+        .iter()
+        .flat_map(|security| {
+            security
+                .savings_plans
+                .iter()
+                .enumerate()
+                .flat_map(move |(plan_index, plan)| {
+                    plan.pending_dates(today).into_iter().map(move |date| {
+                        let price_per_unit = security.price_on(date);
+                        let quantity = match (plan.size, price_per_unit) {
+                            (PlanSize::Quantity(quantity), _) => quantity,
+                            (PlanSize::Amount(amount), Some(price)) if price > 0.0 => {
+                                (amount / price).floor().min(u8::MAX as f32) as u8
+                            }
+                            (PlanSize::Amount(_), _) => 0,
+                        };
+                        PendingEntry {
+                            security_id: security.id,
+                            plan: plan_index,
+                            date,
+                            quantity,
+                            price_per_unit,
+                        }
+                    })
+                })
+        })
+        .collect();
+    entries.sort_by_key(|entry| entry.date);
+    entries
+}
+
+fn day_in_month(first_of_month: NaiveDate, day: u32) -> NaiveDate {
+    let last_day = first_of_month
+        .checked_add_months(Months::new(1))
+        .and_then(|next| next.pred_opt())
+        .map_or(28, |last| last.day());
+    first_of_month
+        .with_day(day.clamp(1, last_day))
+        .unwrap_or(first_of_month)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::security::Action;
+    use crate::security::test_support::{date, security};
+
+    #[test]
+    fn frequencies_are_read_in_every_language() {
+        assert_eq!(Frequency::parse("Quarterly"), Some(Frequency::Quarterly));
+        assert_eq!(Frequency::parse("semestral"), Some(Frequency::SemiAnnually));
+        assert_eq!(Frequency::parse(" Jährlich "), Some(Frequency::Annually));
+        assert_eq!(Frequency::parse("weekly"), None);
+    }
+
+    #[test]
+    fn due_dates_fall_on_the_last_day_of_short_months() {
+        let plan = SavingsPlan::new(
+            PlanSize::Quantity(1),
+            Frequency::Monthly,
+            date(2024, 1, 15),
+            None,
+            31,
+        );
+        assert_eq!(
+            plan.due_dates(date(2024, 4, 29)),
+            vec![date(2024, 1, 31), date(2024, 2, 29), date(2024, 3, 31)]
+        );
+    }
+
+    #[test]
+    fn due_dates_start_after_the_start_and_stop_at_the_end() {
+        let plan = SavingsPlan::new(
+            PlanSize::Quantity(1),
+            Frequency::Quarterly,
+            date(2024, 1, 20),
+            Some(date(2024, 10, 1)),
+            15,
+        );
+        assert_eq!(
+            plan.due_dates(date(2025, 12, 31)),
+            vec![date(2024, 4, 15), date(2024, 7, 15)]
+        );
+    }
+
+    #[test]
+    fn amounts_buy_whole_units_at_the_price_of_the_day() {
+        let mut security = security(&[
+            (Action::Buy, "2024-01-02", 1, 30.0, 0.0, 0.0),
+            (Action::Buy, "2024-02-10", 1, 45.0, 0.0, 0.0),
+        ]);
+        security.id = 3;
+        let mut plan = SavingsPlan::new(
+            PlanSize::Amount(100.0),
+            Frequency::Monthly,
+            date(2024, 1, 1),
+            None,
+            5,
+        );
+        plan.mark_handled(date(2024, 1, 5));
+        security.savings_plans.push(plan);
+
+        let pending = pending_entries(&[security], date(2024, 3, 10));
+        assert_eq!(
+            pending,
+            vec![
+                PendingEntry {
+                    security_id: 3,
+                    plan: 0,
+                    date: date(2024, 2, 5),
+                    quantity: 3,
+                    price_per_unit: Some(30.0),
+                },
+                PendingEntry {
+                    security_id: 3,
+                    plan: 0,
+                    date: date(2024, 3, 5),
+                    quantity: 2,
+                    price_per_unit: Some(45.0),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn nothing_to_confirm_without_a_price() {
+        let mut security = security(&[]);
+        security.savings_plans.push(SavingsPlan::new(
+            PlanSize::Amount(100.0),
+            Frequency::Monthly,
+            date(2024, 1, 1),
+            None,
+            1,
+        ));
+        let pending = pending_entries(&[security], date(2024, 1, 1));
+        assert_eq!(pending.len(), 1);
+        assert!(!pending[0].can_confirm());
+    }
+}
@@ -1,4 +1,13 @@
+use std::collections::BTreeMap;
+
+use chrono::NaiveDate;
 use serde::{Deserialize, Serialize};
+
+use crate::format::{iso_date, parse_date};
+use crate::i18n::{self, Language, Text};
+use crate::savings_plan::SavingsPlan;
+use crate::wash_sale::Replacement;
+
 #[derive(Default, Debug, Clone, Deserialize, Serialize)]
 pub struct Security {
     pub id: u8, // id needed inside Security because parent Vector might mutate
@@ -6,31 +15,181 @@ pub struct Security {
     quantity: u8,
     entries: Vec<Entry>,
     current_price_per_unit: f32,
+    #[serde(default)]
+    current_price_date: Option<String>, // ISO, the day the current price is from
     current_total_invested_value: f32,
     current_total_value: f32,
+    #[serde(default)]
+    pub asset_class: String,
+    #[serde(default)]
+    pub currency: String, // empty means the base currency
+    #[serde(default)]
+    pub account: String,
+    #[serde(default)]
+    pub ticker: String,
+    #[serde(default)]
+    pub isin: String,
+    #[serde(default)]
+    pub country: String, // where income is taxed at source
+    #[serde(default)]
+    pub savings_plans: Vec<SavingsPlan>,
+    #[serde(default)]
+    price_history: Vec<PricePoint>,
 }
 
 impl Security {
     pub fn new(id: u8, name: String, quantity: u8) -> Self {
         Self {
-            id: id,
-            name: name,
-            quantity: quantity,
+            id,
+            name,
+            quantity,
             entries: Vec::new(),
             current_price_per_unit: 0.0,
+            current_price_date: None,
             current_total_invested_value: 0.0,
             current_total_value: 0.0,
+            asset_class: String::new(),
+            currency: String::new(),
+            account: String::new(),
+            ticker: String::new(),
+            isin: String::new(),
+            country: String::new(),
+            savings_plans: Vec::new(),
+            price_history: Vec::new(),
         }
     }
 
-    pub fn add_entry(&mut self, date: String, quantity: u8, price_per_unit: f32) {
-        self.entries
-            .push(Entry::new(date, quantity, price_per_unit));
-        self.quantity += quantity;
+    pub fn add_entry(
+        &mut self,
+        action: Action,
+        date: String,
+        quantity: u8,
+        price_per_unit: f32,
+        fee: f32,
+        tax_withheld: f32,
+    ) -> Result<(), QuantityOverflow> {
+        // Checked first, a refused buy leaves no entry behind
+        let held = match action {
+            Action::Buy => self
+                .quantity
+                .checked_add(quantity)
+                .ok_or(QuantityOverflow)?,
+            Action::Sell => self.quantity.saturating_sub(quantity),
+            Action::Dividend | Action::Interest | Action::Distribution => self.quantity,
+        };
+        self.entries.push(
+            Entry::new(action, date, quantity, price_per_unit)
+                .with_fee(fee)
+                .with_tax_withheld(tax_withheld),
+        );
+        self.quantity = held;
+        Ok(())
+    }
+
+    /// Whether a buy of that many units still fits the holding.
+    pub fn can_buy(&self, quantity: u8) -> bool {
+        self.quantity.checked_add(quantity).is_some()
     }
 
-    pub fn update_current_price(&mut self, price_per_unit: f32) {
+    // Every update is kept in the price history, the last one of a day wins
+    pub fn update_current_price(&mut self, price_per_unit: f32, date: String) {
+        self.update_quote(price_per_unit, date, None);
+    }
+
+    /// Like `update_current_price`, with the time the provider quoted the price (RFC 3339).
+    pub fn update_quote(&mut self, price_per_unit: f32, date: String, quoted_at: Option<String>) {
         self.current_price_per_unit = price_per_unit;
+        self.current_price_date = Some(date.clone());
+        match self
+            .price_history
+            .iter_mut()
+            .find(|point| point.date == date)
+        {
+            Some(point) => {
+                point.price_per_unit = price_per_unit;
+                point.quoted_at = quoted_at;
+            }
+            None => {
+                self.price_history.push(PricePoint {
+                    date,
+                    price_per_unit,
+                    quoted_at,
+                });
+                // Imported history can reach past today's update
+                self.price_history.sort_by(|a, b| a.date.cmp(&b.date));
+            }
+        }
+    }
+
+    // Files from before the date was kept only added to the history with the current price
+    fn current_price_point(&self) -> Option<&PricePoint> {
+        match &self.current_price_date {
+            Some(date) => self.price_history.iter().find(|point| point.date == *date),
+            None => self.price_history.last(),
+        }
+    }
+
+    /// When the provider quoted the current price, if it came from one.
+    pub fn get_quoted_at(&self) -> Option<&str> {
+        self.current_price_point()?.quoted_at.as_deref()
+    }
+
+    /// The day the current price is from, `None` if it was never set.
+    pub fn get_price_date(&self) -> Option<NaiveDate> {
+        match &self.current_price_date {
+            Some(date) => parse_date(date),
+            None => parse_date(&self.current_price_point()?.date),
+        }
+    }
+
+    /// Whether the value of the units held relies on a price older than `max_age_days`.
+    pub fn is_price_stale(&self, today: NaiveDate, max_age_days: u32) -> bool {
+        self.quantity > 0
+            && self
+                .get_price_date()
+                .is_none_or(|date| (today - date).num_days() > max_age_days as i64)
+    }
+
+    /// Merges closing prices into the history. Prices of days already in it
+    /// replace the recorded ones only when `overwrite` is set.
+    pub fn add_price_history(
+        &mut self,
+        points: &[(NaiveDate, f32)],
+        overwrite: bool,
+    ) -> PriceMerge {
+        let mut merge = PriceMerge::default();
+        for (day, price_per_unit) in points {
+            let date = iso_date(*day);
+            match self
+                .price_history
+                .iter_mut()
+                .find(|point| point.date == date)
+            {
+                Some(point) if overwrite => {
+                    point.price_per_unit = *price_per_unit;
+                    point.quoted_at = None;
+                    merge.replaced += 1;
+                }
+                Some(_) => merge.kept += 1,
+                None => {
+                    self.price_history.push(PricePoint {
+                        date,
+                        price_per_unit: *price_per_unit,
+                        quoted_at: None,
+                    });
+                    merge.added += 1;
+                }
+            }
+        }
+        self.price_history.sort_by(|a, b| a.date.cmp(&b.date));
+        merge
+    }
+
+    /// What quotes are looked up by, the ticker if there is one.
+    pub fn symbol(&self) -> Option<&str> {
+        [self.ticker.as_str(), self.isin.as_str()]
+            .into_iter()
+            .find(|symbol| !symbol.is_empty())
     }
 
     pub fn get_current_price_per_unit(&self) -> f32 {
@@ -40,10 +199,78 @@ impl Security {
         self.quantity
     }
 
-    pub fn get_entries(&self) -> Vec<(String, u8, f32)> {
+    pub fn get_entries(&self) -> Vec<(Action, String, u8, f32)> {
+        self.entries.iter().map(Entry::to_tuple).collect()
+    }
+
+    /// Fee of every entry, in the order of get_entries.
+    pub fn get_fees(&self) -> Vec<f32> {
+        self.entries.iter().map(|entry| entry.fee).collect()
+    }
+
+    /// Tax withheld at source of every entry, in the order of get_entries.
+    pub fn get_taxes_withheld(&self) -> Vec<f32> {
+        self.entries
+            .iter()
+            .map(|entry| entry.tax_withheld)
+            .collect()
+    }
+
+    // Entries can be typed in any order, cost basis has to follow the calendar
+    fn entries_by_date(&self) -> Vec<&Entry> {
+        let mut entries: Vec<&Entry> = self.entries.iter().collect();
+        entries.sort_by_key(|entry| parse_date(&entry.date).unwrap_or(NaiveDate::MAX));
+        entries
+    }
+
+    /// Realized gain of every sale as (date, gain).
+    pub fn get_realized_gains(&self, method: CostBasisMethod) -> Vec<(String, f32)> {
+        self.get_disposals(method)
+            .into_iter()
+            .map(|disposal| {
+                let gain = disposal.gain();
+                (disposal.date, gain)
+            })
+            .collect()
+    }
+
+    /// Every sale with the lots it was taken from.
+    pub fn get_disposals(&self, method: CostBasisMethod) -> Vec<Disposal> {
+        self.track_lots(method, NaiveDate::MAX).1
+    }
+
+    /// Open lots and every sale, with the losses deferred to replacement units added to their cost.
+    pub fn get_lots_with(
+        &self,
+        method: CostBasisMethod,
+        replacements: &[Replacement],
+    ) -> (Vec<Lot>, Vec<Disposal>) {
+        self.track_lots_with(method, NaiveDate::MAX, replacements)
+    }
+
+    /// Income received after tax withheld at source as (date, amount).
+    pub fn get_income(&self) -> Vec<(String, f32)> {
+        self.get_income_payments()
+            .into_iter()
+            .map(|payment| {
+                let net = payment.net();
+                (payment.date, net)
+            })
+            .collect()
+    }
+
+    /// Dividends, interest and distributions with the tax withheld on them.
+    pub fn get_income_payments(&self) -> Vec<IncomePayment> {
         self.entries
             .iter()
-            .map(|entry| (entry.date.clone(), entry.quantity, entry.price_per_unit))
+            .filter(|entry| entry.action.is_income())
+            .map(|entry| IncomePayment {
+                date: entry.date.clone(),
+                action: entry.action,
+                units: entry.quantity as f32,
+                per_unit: entry.price_per_unit,
+                tax_withheld: entry.tax_withheld,
+            })
             .collect()
     }
 
@@ -51,11 +278,45 @@ impl Security {
         self.current_total_invested_value
     }
 
-    pub fn calculate_total_invested_value(&mut self) {
-        self.current_total_invested_value = 0.0;
-        for entry in self.entries.iter() {
-            self.current_total_invested_value += entry.price_per_unit * entry.quantity as f32;
+    // Invested value is the cost of the units still held
+    pub fn calculate_total_invested_value(&mut self, method: CostBasisMethod) {
+        self.current_total_invested_value = self
+            .track_lots(method, NaiveDate::MAX)
+            .0
+            .iter()
+            .map(|lot| lot.quantity * lot.price_per_unit)
+            .sum();
+    }
+
+    /// Cost of the units held at the end of the given day.
+    pub fn get_invested_value_on(&self, date: NaiveDate, method: CostBasisMethod) -> f32 {
+        self.track_lots(method, date)
+            .0
+            .iter()
+            .map(|lot| lot.quantity * lot.price_per_unit)
+            .sum()
+    }
+
+    fn track_lots(&self, method: CostBasisMethod, until: NaiveDate) -> (Vec<Lot>, Vec<Disposal>) {
+        self.track_lots_with(method, until, &[])
+    }
+
+    // Walks the entries up to the given day in date order. Entries without a readable
+    // date only count up to NaiveDate::MAX.
+    fn track_lots_with(
+        &self,
+        method: CostBasisMethod,
+        until: NaiveDate,
+        replacements: &[Replacement],
+    ) -> (Vec<Lot>, Vec<Disposal>) {
+        let mut tracker = LotTracker::new(method, replacements);
+        for entry in self.entries_by_date() {
+            if parse_date(&entry.date).unwrap_or(NaiveDate::MAX) > until {
+                break;
+            }
+            tracker.add(entry);
         }
+        (tracker.lots, tracker.disposals)
     }
 
     pub fn get_total_current_value(&self) -> f32 {
@@ -65,21 +326,605 @@ impl Security {
     pub fn calculate_total_current_value(&mut self) {
         self.current_total_value = self.current_price_per_unit * self.quantity as f32;
     }
+
+    /// Known prices by day from the trades and the price history,
+    /// a recorded price wins over a trade on the same day.
+    pub fn get_price_points(&self) -> Vec<(NaiveDate, f32)> {
+        let mut points = BTreeMap::new();
+        for entry in self.entries.iter() {
+            if !entry.action.is_income()
+                && let Some(day) = parse_date(&entry.date)
+            {
+                points.insert(day, entry.price_per_unit);
+            }
+        }
+        for point in self.price_history.iter() {
+            if let Some(day) = parse_date(&point.date) {
+                points.insert(day, point.price_per_unit);
+            }
+        }
+        points.into_iter().collect()
+    }
+
+    /// Latest known price on or before the given day.
+    pub fn price_on(&self, date: NaiveDate) -> Option<f32> {
+        // Same choice as get_price_points without collecting them all
+        let trades = self
+            .entries
+            .iter()
+            .filter(|entry| !entry.action.is_income())
+            .filter_map(|entry| Some((parse_date(&entry.date)?, false, entry.price_per_unit)));
+        let recorded = self
+            .price_history
+            .iter()
+            .filter_map(|point| Some((parse_date(&point.date)?, true, point.price_per_unit)));
+        trades
+            .chain(recorded)
+            .filter(|(day, _, _)| *day <= date)
+            .max_by_key(|(day, recorded, _)| (*day, *recorded))
+            .map(|(_, _, price)| price)
+    }
+
+    /// Units held at the end of the given day.
+    pub fn get_quantity_on(&self, date: NaiveDate) -> f32 {
+        self.entries
+            .iter()
+            .filter(|entry| parse_date(&entry.date).is_some_and(|day| day <= date))
+            .map(|entry| match entry.action {
+                Action::Buy => entry.quantity as f32,
+                Action::Sell => -(entry.quantity as f32),
+                Action::Dividend | Action::Interest | Action::Distribution => 0.0,
+            })
+            .sum()
+    }
+
+    /// Value of the units held at the end of the given day.
+    pub fn get_value_on(&self, date: NaiveDate) -> f32 {
+        self.get_quantity_on(date) * self.price_on(date).unwrap_or(0.0)
+    }
+
+    /// Value of the units held at the end of each of the days, which have to be in
+    /// ascending order. Prices and entries are walked once for all of them.
+    pub fn get_values_on(&self, days: &[NaiveDate]) -> Vec<f32> {
+        let points = self.get_price_points();
+        let entries = self.entries_by_date();
+        let (mut next_point, mut next_entry) = (0, 0);
+        let (mut price, mut quantity) = (0.0, 0.0);
+        days.iter()
+            .map(|day| {
+                while let Some((point_day, point_price)) = points.get(next_point)
+                    && point_day <= day
+                {
+                    price = *point_price;
+                    next_point += 1;
+                }
+                while let Some(entry) = entries.get(next_entry)
+                    && parse_date(&entry.date).is_some_and(|entry_day| entry_day <= *day)
+                {
+                    quantity += match entry.action {
+                        Action::Buy => entry.quantity as f32,
+                        Action::Sell => -(entry.quantity as f32),
+                        Action::Dividend | Action::Interest | Action::Distribution => 0.0,
+                    };
+                    next_entry += 1;
+                }
+                quantity * price
+            })
+            .collect()
+    }
+
+    /// Cost of the units held at the end of each of the days, which have to be in
+    /// ascending order. The lots are tracked once for all of them.
+    pub fn get_invested_values_on(&self, days: &[NaiveDate], method: CostBasisMethod) -> Vec<f32> {
+        let entries = self.entries_by_date();
+        let mut tracker = LotTracker::new(method, &[]);
+        let mut next_entry = 0;
+        days.iter()
+            .map(|day| {
+                while let Some(entry) = entries.get(next_entry)
+                    && parse_date(&entry.date).unwrap_or(NaiveDate::MAX) <= *day
+                {
+                    tracker.add(entry);
+                    next_entry += 1;
+                }
+                tracker.cost()
+            })
+            .collect()
+    }
+
+    /// Money moved into the security (buys and fees) or out of it (sales and dividends)
+    /// as (date, amount).
+    pub fn get_cash_flows(&self) -> Vec<(NaiveDate, f32)> {
+        self.entries
+            .iter()
+            .filter_map(|entry| {
+                let amount = entry.quantity as f32 * entry.price_per_unit;
+                let flow = match entry.action {
+                    Action::Buy => amount + entry.fee,
+                    Action::Sell => -amount + entry.fee,
+                    Action::Dividend | Action::Interest | Action::Distribution => {
+                        -amount + entry.tax_withheld
+                    }
+                };
+                parse_date(&entry.date).map(|date| (date, flow))
+            })
+            .collect()
+    }
+
+    /// Change in value since the end of the given day, without the money put in or taken out after it.
+    pub fn get_value_change_since(&self, date: NaiveDate) -> f32 {
+        let start_value = self.get_value_on(date);
+        let net_flows: f32 = self
+            .entries
+            .iter()
+            .filter(|entry| parse_date(&entry.date).is_some_and(|day| day > date))
+            .map(|entry| {
+                let amount = entry.quantity as f32 * entry.price_per_unit;
+                match entry.action {
+                    Action::Buy => amount + entry.fee,
+                    Action::Sell => -amount + entry.fee,
+                    Action::Dividend | Action::Interest | Action::Distribution => 0.0,
+                }
+            })
+            .sum();
+        self.current_total_value - start_value - net_flows
+    }
+
+    /// Gains and income from the cached values and the entries, so it's always up to date.
+    pub fn get_performance(&self, method: CostBasisMethod) -> Performance {
+        let unrealized_gain = self.current_total_value - self.current_total_invested_value;
+        let realized_gain: f32 = self
+            .get_realized_gains(method)
+            .iter()
+            .map(|(_, gain)| gain)
+            .sum();
+        let income: f32 = self.get_income().iter().map(|(_, amount)| amount).sum();
+        let total_return = unrealized_gain + realized_gain + income;
+        // Total return is relative to everything that was ever bought
+        let total_bought: f32 = self
+            .entries
+            .iter()
+            .filter(|entry| entry.action == Action::Buy)
+            .map(|entry| entry.quantity as f32 * entry.price_per_unit)
+            .sum();
+        Performance {
+            unrealized_gain,
+            unrealized_gain_percent: percent_of(unrealized_gain, self.current_total_invested_value),
+            realized_gain,
+            income,
+            total_return,
+            total_return_percent: percent_of(total_return, total_bought),
+        }
+    }
+}
+
+fn percent_of(value: f32, base: f32) -> f32 {
+    if base > 0.0 {
+        value / base * 100.0
+    } else {
+        0.0
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Performance {
+    pub unrealized_gain: f32,
+    pub unrealized_gain_percent: f32,
+    pub realized_gain: f32,
+    pub income: f32,
+    pub total_return: f32,
+    pub total_return_percent: f32,
+}
+
+/// A dividend, interest or distribution paid on `units` units.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IncomePayment {
+    pub date: String,
+    pub action: Action,
+    pub units: f32,
+    pub per_unit: f32,
+    pub tax_withheld: f32,
+}
+
+impl IncomePayment {
+    pub fn gross(&self) -> f32 {
+        self.units * self.per_unit
+    }
+
+    pub fn net(&self) -> f32 {
+        self.gross() - self.tax_withheld
+    }
+}
+
+/// Imported prices that were new, replaced a recorded one or were left out for it.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct PriceMerge {
+    pub added: usize,
+    pub replaced: usize,
+    pub kept: usize,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+struct PricePoint {
+    date: String, // ISO
+    price_per_unit: f32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    quoted_at: Option<String>,
+}
+
+/// A buy that would take the holding past the most units a security can record.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct QuantityOverflow;
+
+/// A sale, the fees are the sale's own, buy fees are in the cost of the lots.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Disposal {
+    pub date: String,
+    pub quantity: f32,
+    pub proceeds: f32,
+    pub fees: f32,
+    pub disallowed: f32, // part of the loss not deductible because of a wash sale
+    pub lots: Vec<DisposedLot>,
+}
+
+impl Disposal {
+    pub fn cost_basis(&self) -> f32 {
+        self.lots.iter().map(|lot| lot.cost).sum()
+    }
+
+    pub fn gain(&self) -> f32 {
+        self.proceeds - self.cost_basis() - self.fees + self.disallowed
+    }
+}
+
+/// The part of a lot that was sold.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DisposedLot {
+    pub acquired: Option<NaiveDate>,
+    pub quantity: f32,
+    pub cost: f32,
+}
+
+/// Units bought together that are still held, the price includes the buy fee.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Lot {
+    pub acquired: Option<NaiveDate>,
+    pub quantity: f32,
+    pub price_per_unit: f32,
+}
+
+// Every buy opens a lot and sales consume lots in the order given by the method.
+// Buy fees are part of the lot's cost, units replacing a wash sale get a lot of their
+// own that also carries the deferred loss.
+struct LotTracker {
+    method: CostBasisMethod,
+    deferred: Vec<(NaiveDate, f32, f32)>, // units still to be matched and the loss per unit
+    lots: Vec<Lot>,
+    disposals: Vec<Disposal>,
+}
+
+impl LotTracker {
+    fn new(method: CostBasisMethod, replacements: &[Replacement]) -> Self {
+        let deferred = replacements
+            .iter()
+            .filter(|replacement| replacement.basis_adjustment > 0.0 && replacement.quantity > 0.0)
+            .map(|replacement| {
+                (
+                    replacement.date,
+                    replacement.quantity,
+                    replacement.basis_adjustment / replacement.quantity,
+                )
+            })
+            .collect();
+        Self {
+            method,
+            deferred,
+            lots: Vec::new(),
+            disposals: Vec::new(),
+        }
+    }
+
+    fn add(&mut self, entry: &Entry) {
+        let quantity = entry.quantity as f32;
+        match entry.action {
+            Action::Buy => {
+                let acquired = parse_date(&entry.date);
+                let price_per_unit = if quantity > 0.0 {
+                    entry.price_per_unit + entry.fee / quantity
+                } else {
+                    entry.price_per_unit
+                };
+                let mut unmatched = quantity;
+                for (_, units, loss_per_unit) in self
+                    .deferred
+                    .iter_mut()
+                    .filter(|(day, _, _)| acquired == Some(*day))
+                {
+                    let replaced = units.min(unmatched);
+                    if replaced <= 0.0 {
+                        continue;
+                    }
+                    self.lots.push(Lot {
+                        acquired,
+                        quantity: replaced,
+                        price_per_unit: price_per_unit + *loss_per_unit,
+                    });
+                    *units -= replaced;
+                    unmatched -= replaced;
+                }
+                if unmatched > 0.0 || quantity <= 0.0 {
+                    self.lots.push(Lot {
+                        acquired,
+                        quantity: unmatched,
+                        price_per_unit,
+                    });
+                }
+            }
+            Action::Sell => {
+                if self.method == CostBasisMethod::Average {
+                    let held: f32 = self.lots.iter().map(|lot| lot.quantity).sum();
+                    let average_cost = if held > 0.0 { self.cost() / held } else { 0.0 };
+                    self.lots
+                        .iter_mut()
+                        .for_each(|lot| lot.price_per_unit = average_cost);
+                }
+                let mut remaining = quantity;
+                let mut used_lots = Vec::new();
+                while remaining > 0.0 && !self.lots.is_empty() {
+                    let index = match self.method {
+                        CostBasisMethod::Lifo => self.lots.len() - 1,
+                        CostBasisMethod::Fifo | CostBasisMethod::Average => 0,
+                    };
+                    let lot = &mut self.lots[index];
+                    let used = remaining.min(lot.quantity);
+                    used_lots.push(DisposedLot {
+                        acquired: lot.acquired,
+                        quantity: used,
+                        cost: used * lot.price_per_unit,
+                    });
+                    lot.quantity -= used;
+                    remaining -= used;
+                    if lot.quantity <= 0.0 {
+                        self.lots.remove(index);
+                    }
+                }
+                let sold = quantity - remaining;
+                self.disposals.push(Disposal {
+                    date: entry.date.clone(),
+                    quantity: sold,
+                    proceeds: sold * entry.price_per_unit,
+                    fees: entry.fee,
+                    disallowed: 0.0,
+                    lots: used_lots,
+                });
+            }
+            Action::Dividend | Action::Interest | Action::Distribution => {}
+        }
+    }
+
+    fn cost(&self) -> f32 {
+        self.lots
+            .iter()
+            .map(|lot| lot.quantity * lot.price_per_unit)
+            .sum()
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CostBasisMethod {
+    Fifo,
+    Lifo,
+    #[default]
+    Average,
+}
+
+impl CostBasisMethod {
+    pub const ALL: [CostBasisMethod; 3] = [
+        CostBasisMethod::Fifo,
+        CostBasisMethod::Lifo,
+        CostBasisMethod::Average,
+    ];
+}
+
+impl std::fmt::Display for CostBasisMethod {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CostBasisMethod::Fifo => write!(f, "FIFO"),
+            CostBasisMethod::Lifo => write!(f, "LIFO"),
+            CostBasisMethod::Average => write!(f, "Average Cost"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize, Serialize)]
+pub enum Action {
+    #[default]
+    Buy,
+    Sell,
+    Dividend,
+    Interest,
+    Distribution, // e.g. fund payouts that aren't dividends
+}
+
+impl Action {
+    pub const ALL: [Action; 5] = [
+        Action::Buy,
+        Action::Sell,
+        Action::Dividend,
+        Action::Interest,
+        Action::Distribution,
+    ];
+
+    pub fn is_income(self) -> bool {
+        matches!(
+            self,
+            Action::Dividend | Action::Interest | Action::Distribution
+        )
+    }
+
+    /// Reads the action in any of the UI languages.
+    pub fn parse(input: &str) -> Option<Self> {
+        let input = input.trim().to_lowercase();
+        if input.is_empty() {
+            return Some(Action::Buy);
+        }
+        Action::ALL.into_iter().find(|action| {
+            Language::ALL
+                .iter()
+                .any(|language| i18n::tr(*language, action.label()).to_lowercase() == input)
+        })
+    }
+
+    pub fn label(&self) -> Text {
+        match self {
+            Action::Buy => Text::ActionBuy,
+            Action::Sell => Text::ActionSell,
+            Action::Dividend => Text::ActionDividend,
+            Action::Interest => Text::ActionInterest,
+            Action::Distribution => Text::ActionDistribution,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
-struct Entry {
+pub struct Entry {
+    #[serde(default)] // files written before actions existed only contain buys
+    action: Action,
     date: String,
     quantity: u8,
     price_per_unit: f32,
+    #[serde(default)]
+    fee: f32,
+    #[serde(default)]
+    tax_withheld: f32, // on income, kept back at source
 }
 
 impl Entry {
-    pub fn new(date: String, quantity: u8, price_per_unit: f32) -> Self {
+    pub fn new(action: Action, date: String, quantity: u8, price_per_unit: f32) -> Self {
         Self {
-            date: date,
-            quantity: quantity,
-            price_per_unit: price_per_unit,
+            action,
+            date,
+            quantity,
+            price_per_unit,
+            fee: 0.0,
+            tax_withheld: 0.0,
+        }
+    }
+
+    pub fn with_fee(mut self, fee: f32) -> Self {
+        self.fee = fee;
+        self
+    }
+
+    pub fn with_tax_withheld(mut self, tax_withheld: f32) -> Self {
+        self.tax_withheld = tax_withheld;
+        self
+    }
+
+    pub fn to_tuple(&self) -> (Action, String, u8, f32) {
+        (
+            self.action,
+            self.date.clone(),
+            self.quantity,
+            self.price_per_unit,
+        )
+    }
+}
+
+/// Helpers for the tests of every module that works on securities.
+#[cfg(test)]
+pub mod test_support {
+    use chrono::NaiveDate;
+
+    use super::{Action, Security};
+
+    pub fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    /// An "ETF" with the entries (action, date, quantity, price per unit, fee, tax withheld).
+    pub fn security(entries: &[(Action, &str, u8, f32, f32, f32)]) -> Security {
+        let mut security = Security::new(0, "ETF".to_string(), 0);
+        for (action, day, quantity, price, fee, tax_withheld) in entries {
+            security
+                .add_entry(
+                    *action,
+                    day.to_string(),
+                    *quantity,
+                    *price,
+                    *fee,
+                    *tax_withheld,
+                )
+                .unwrap();
+        }
+        security
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::test_support::{date, security};
+    use super::*;
+
+    #[test]
+    fn series_match_the_single_days() {
+        let mut security = security(&[
+            (Action::Buy, "2024-01-10", 10, 100.0, 2.0, 0.0),
+            (Action::Buy, "2024-03-05", 5, 110.0, 0.0, 0.0),
+            (Action::Sell, "2024-04-02", 8, 120.0, 1.0, 0.0),
+        ]);
+        security.update_current_price(105.0, "2024-02-01".to_string());
+        // A recorded price wins over the trade on the same day
+        security.update_current_price(111.0, "2024-03-05".to_string());
+
+        let days: Vec<NaiveDate> = [
+            date(2024, 1, 1),
+            date(2024, 1, 10),
+            date(2024, 2, 15),
+            date(2024, 3, 5),
+            date(2024, 4, 2),
+            date(2024, 6, 30),
+        ]
+        .into();
+        let values = security.get_values_on(&days);
+        let costs = security.get_invested_values_on(&days, CostBasisMethod::Fifo);
+        for (i, day) in days.iter().enumerate() {
+            assert_eq!(values[i], security.get_value_on(*day));
+            assert_eq!(
+                costs[i],
+                security.get_invested_value_on(*day, CostBasisMethod::Fifo)
+            );
+        }
+        assert_eq!(values[3], 15.0 * 111.0);
+        assert_eq!(costs[5], 2.0 * 100.2 + 5.0 * 110.0);
+    }
+
+    #[test]
+    fn actions_are_read_in_every_language() {
+        assert_eq!(Action::parse(""), Some(Action::Buy));
+        assert_eq!(Action::parse("sell"), Some(Action::Sell));
+        assert_eq!(Action::parse("Juros"), Some(Action::Interest));
+        assert_eq!(Action::parse(" ausschüttung "), Some(Action::Distribution));
+        assert_eq!(Action::parse("split"), None);
+    }
+
+    #[test]
+    fn buys_past_the_largest_quantity_are_refused() {
+        let mut security = security(&[]);
+        for month in 1..=12 {
+            let date = format!("2024-{:02}-01", month);
+            security
+                .add_entry(Action::Buy, date, 20, 100.0, 0.0, 0.0)
+                .unwrap();
         }
+        assert!(!security.can_buy(16));
+        assert_eq!(
+            security.add_entry(Action::Buy, "2025-01-02".to_string(), 16, 100.0, 0.0, 0.0),
+            Err(QuantityOverflow)
+        );
+        assert_eq!(security.get_quantity(), 240);
+        assert_eq!(security.get_entries().len(), 12);
+        assert!(security.can_buy(15));
     }
 }
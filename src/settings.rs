@@ -0,0 +1,235 @@
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::format::Locale;
+use crate::i18n::Language;
+use crate::price::QuoteSource;
+use crate::security::CostBasisMethod;
+use crate::wash_sale::WashSaleRule;
+
+const MAX_RECENT_FILES: usize = 10;
+const ENV_PREFIX: &str = "PORTFOLIO";
+const ENV_SETTINGS_FILE: &str = "PORTFOLIO_SETTINGS_FILE";
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ThemeChoice {
+    #[default]
+    Light,
+    Dark,
+}
+
+impl ThemeChoice {
+    pub const ALL: [ThemeChoice; 2] = [ThemeChoice::Light, ThemeChoice::Dark];
+
+    pub fn to_theme(self) -> iced::Theme {
+        match self {
+            ThemeChoice::Light => iced::Theme::Light,
+            ThemeChoice::Dark => iced::Theme::Dark,
+        }
+    }
+}
+
+impl std::fmt::Display for ThemeChoice {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ThemeChoice::Light => write!(f, "Light"),
+            ThemeChoice::Dark => write!(f, "Dark"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Settings {
+    pub base_currency: String,
+    pub decimal_separator: char,
+    pub locale: Locale, // thousands separator and currency symbol placement
+    pub language: Language,
+    pub date_format: String,        // chrono format string, e.g. "%d.%m.%Y"
+    pub recent_files: Vec<PathBuf>, // most recent first
+    pub reopen_last: bool,
+    pub theme: ThemeChoice,
+    pub cost_basis_method: CostBasisMethod, // used for new portfolios
+    pub autosave_minutes: u32,              // 0 disables autosave
+    pub default_directory: Option<PathBuf>, // where file dialogs start
+    pub risk_free_rate: f32,                // annual, in percent
+    pub fractional_shares: bool,            // whether rebalancing may suggest parts of units
+    pub quotes: QuoteSource,
+    pub stale_price_days: u32, // prices older than this are flagged
+    pub long_term_days: u32,   // gains on units held longer than this are long-term
+    pub wash_sale_days: u32,   // repurchases this close to a loss sale disallow the loss
+    pub wash_sale_rule: WashSaleRule,
+    #[serde(skip)]
+    loaded: Loaded,
+}
+
+// The values as loaded with and without the environment variables, so that
+// saving keeps the file's own value of every setting an override didn't change
+#[derive(Debug, Clone, Default)]
+struct Loaded {
+    merged: serde_yaml::Mapping,
+    file: serde_yaml::Mapping,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            base_currency: "EUR".to_string(),
+            decimal_separator: '.',
+            locale: Locale::default(),
+            language: Language::default(),
+            date_format: "%Y-%m-%d".to_string(),
+            recent_files: Vec::new(),
+            reopen_last: false,
+            theme: ThemeChoice::default(),
+            cost_basis_method: CostBasisMethod::default(),
+            autosave_minutes: 0,
+            default_directory: None,
+            risk_free_rate: 0.0,
+            fractional_shares: false,
+            quotes: QuoteSource::default(),
+            stale_price_days: 7, // long enough for weekends and holidays
+            long_term_days: 365,
+            wash_sale_days: 30,
+            wash_sale_rule: WashSaleRule::default(),
+            loaded: Loaded::default(),
+        }
+    }
+}
+
+impl Settings {
+    // PORTFOLIO_SETTINGS_FILE points the app at another settings file, e.g. for tests
+    fn file_path() -> Option<PathBuf> {
+        match std::env::var_os(ENV_SETTINGS_FILE) {
+            Some(path) => Some(PathBuf::from(path)),
+            None => dirs::config_dir().map(|dir| dir.join("portfolio").join("settings.yaml")),
+        }
+    }
+
+    /// Layers defaults, the settings file and PORTFOLIO_* environment variables
+    /// (e.g. PORTFOLIO_BASE_CURRENCY=USD), falling back to defaults on errors.
+    pub fn load() -> Self {
+        let (mut settings, file) = match (Self::build_config(true), Self::build_config(false)) {
+            (Ok(settings), Ok(file)) => (settings, file),
+            (Err(e), _) | (_, Err(e)) => {
+                println!("Failed to load settings: {}", e);
+                return Self::default();
+            }
+        };
+        settings.loaded = Loaded {
+            merged: to_mapping(&settings),
+            file: to_mapping(&file),
+        };
+        settings
+    }
+
+    fn build_config(with_environment: bool) -> Result<Self, config::ConfigError> {
+        let mut builder =
+            config::Config::builder().add_source(config::Config::try_from(&Self::default())?);
+        if let Some(path) = Self::file_path() {
+            builder = builder.add_source(
+                config::File::from(path)
+                    .format(config::FileFormat::Yaml)
+                    .required(false),
+            );
+        }
+        if with_environment {
+            builder =
+                builder.add_source(config::Environment::with_prefix(ENV_PREFIX).try_parsing(true));
+        }
+        builder.build()?.try_deserialize()
+    }
+
+    // Settings still holding the value an environment variable gave them are
+    // written with the value from the file instead
+    fn without_overrides(&self) -> serde_yaml::Mapping {
+        let mut values = to_mapping(self);
+        for (key, value) in values.iter_mut() {
+            if self.loaded.merged.get(key) == Some(&*value)
+                && let Some(file_value) = self.loaded.file.get(key)
+            {
+                *value = file_value.clone();
+            }
+        }
+        values
+    }
+
+    pub fn save(&self) {
+        let Some(path) = Self::file_path() else {
+            println!("No config directory to save settings to");
+            return;
+        };
+        if let Some(dir) = path.parent()
+            && let Err(e) = std::fs::create_dir_all(dir)
+        {
+            println!("Failed to create {:?}: {}", dir, e);
+            return;
+        }
+        match serde_yaml::to_string(&self.without_overrides()) {
+            Ok(yaml) => {
+                if let Err(e) = std::fs::write(&path, yaml) {
+                    println!("Failed to save settings: {}", e);
+                }
+            }
+            Err(e) => println!("Failed to serialize settings: {}", e),
+        }
+    }
+
+    pub fn add_recent_file(&mut self, path: &Path) {
+        self.recent_files.retain(|p| p != path);
+        self.recent_files.insert(0, path.to_path_buf());
+        self.recent_files.truncate(MAX_RECENT_FILES);
+    }
+
+    pub fn remove_recent_file(&mut self, path: &Path) {
+        self.recent_files.retain(|p| p != path);
+    }
+
+    /// Drops recent files that no longer exist, returns true if any were removed.
+    pub fn prune_recent_files(&mut self) -> bool {
+        let count = self.recent_files.len();
+        self.recent_files.retain(|p| p.exists());
+        self.recent_files.len() != count
+    }
+}
+
+fn to_mapping(settings: &Settings) -> serde_yaml::Mapping {
+    match serde_yaml::to_value(settings) {
+        Ok(serde_yaml::Value::Mapping(values)) => values,
+        _ => serde_yaml::Mapping::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn overrides_are_not_saved() {
+        let file = Settings {
+            base_currency: "EUR".to_string(),
+            ..Settings::default()
+        };
+        // As if PORTFOLIO_BASE_CURRENCY=USD and PORTFOLIO_THEME=dark were set
+        let mut settings = Settings {
+            base_currency: "USD".to_string(),
+            theme: ThemeChoice::Dark,
+            ..Settings::default()
+        };
+        settings.loaded = Loaded {
+            merged: to_mapping(&settings),
+            file: to_mapping(&file),
+        };
+        settings.theme = ThemeChoice::Light;
+        settings.add_recent_file(Path::new("portfolio.json"));
+
+        let saved: Settings =
+            serde_yaml::from_value(serde_yaml::Value::Mapping(settings.without_overrides()))
+                .unwrap();
+        assert_eq!(saved.base_currency, "EUR");
+        assert_eq!(saved.theme, ThemeChoice::Light);
+        assert_eq!(saved.recent_files, vec![PathBuf::from("portfolio.json")]);
+    }
+}
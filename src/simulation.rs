@@ -0,0 +1,425 @@
+use std::collections::BTreeMap;
+
+use chrono::{Datelike, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+use crate::i18n::Text;
+use crate::security::Security;
+
+/// Percentiles of the projected values every band holds, lowest first.
+pub const PERCENTILES: [f32; 5] = [10.0, 25.0, 50.0, 75.0, 90.0];
+
+/// Expected growth of one asset class, both in percent a year.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Assumption {
+    pub asset_class: String,
+    pub annual_return: f32,
+    pub volatility: f32,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ReturnModel {
+    #[default]
+    Assumptions, // random returns per asset class
+    Bootstrap, // months drawn from the price history of the holdings
+}
+
+impl ReturnModel {
+    pub const ALL: [ReturnModel; 2] = [ReturnModel::Assumptions, ReturnModel::Bootstrap];
+
+    pub fn label(&self) -> Text {
+        match self {
+            ReturnModel::Assumptions => Text::Assumptions,
+            ReturnModel::Bootstrap => Text::PriceHistory,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Options {
+    pub years: u32,
+    pub paths: u32,
+    pub seed: u64,
+    pub monthly_contribution: f32,
+    pub model: ReturnModel,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            years: 10,
+            paths: 1000,
+            seed: 1,
+            monthly_contribution: 0.0,
+            model: ReturnModel::default(),
+        }
+    }
+}
+
+/// Projected values at the end of a year, one for each of `PERCENTILES`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Band {
+    pub year: u32,
+    pub values: [f32; 5],
+}
+
+// SplitMix64, small and good enough to draw returns from
+struct Rng(u64);
+
+impl Rng {
+    fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    // Uniform in [0, 1)
+    fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    fn below(&mut self, n: usize) -> usize {
+        (self.next_f64() * n as f64) as usize % n
+    }
+
+    // Standard normal by Box-Muller
+    fn normal(&mut self) -> f64 {
+        let u1 = 1.0 - self.next_f64(); // never zero
+        let u2 = self.next_f64();
+        (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos()
+    }
+}
+
+enum Returns<'a> {
+    Fixed,                                 // cash
+    Normal { log_mean: f64, log_sd: f64 }, // monthly, of the log growth
+    History(&'a [f64]),
+}
+
+struct Bucket<'a> {
+    value: f64,
+    share: f64, // of every contribution
+    returns: Returns<'a>,
+}
+
+/// Monthly returns of the holdings as they are weighted now, from the last known
+/// price in every month. Months where no security had a price in the month
+/// before are left out.
+pub fn historical_returns(securities: &[Security]) -> Vec<f64> {
+    let mut months: BTreeMap<(i32, u32), (f64, f64)> = BTreeMap::new();
+    for security in securities.iter() {
+        let weight = security.get_total_current_value() as f64;
+        if weight <= 0.0 {
+            continue;
+        }
+        let mut prices: BTreeMap<(i32, u32), f32> = BTreeMap::new();
+        for (day, price) in security.get_price_points() {
+            prices.insert((day.year(), day.month()), price);
+        }
+        let prices: Vec<((i32, u32), f32)> = prices.into_iter().collect();
+        for pair in prices.windows(2) {
+            let ((year, month), before) = pair[0];
+            let (next_month, after) = pair[1];
+            let following = if month == 12 {
+                (year + 1, 1)
+            } else {
+                (year, month + 1)
+            };
+            if next_month != following || before <= 0.0 {
+                continue;
+            }
+            let total = months.entry(next_month).or_default();
+            total.0 += weight * (after as f64 / before as f64 - 1.0);
+            total.1 += weight;
+        }
+    }
+    months
+        .into_values()
+        .map(|(weighted, weight)| weighted / weight)
+        .collect()
+}
+
+/// What the savings plans of the securities invest per month on average.
+pub fn planned_contribution(securities: &[Security], today: NaiveDate) -> f32 {
+    securities
+        .iter()
+        .flat_map(|security| {
+            security
+                .savings_plans
+                .iter()
+                .map(|plan| plan.monthly_amount(security.get_current_price_per_unit(), today))
+        })
+        .sum()
+}
+
+/// Projects the value of the securities and the cash over the coming years,
+/// from year 0 (today) to `options.years`. Contributions are shared by the
+/// current weights. Asset classes without an assumption and the cash don't grow.
+/// The same seed always gives the same bands.
+pub fn project(
+    securities: &[Security],
+    cash: f32,
+    assumptions: &[Assumption],
+    history: &[f64],
+    options: &Options,
+) -> Vec<Band> {
+    let mut buckets = vec![Bucket {
+        value: cash.max(0.0) as f64,
+        share: 0.0,
+        returns: Returns::Fixed,
+    }];
+    match options.model {
+        ReturnModel::Assumptions => {
+            let mut by_asset_class: BTreeMap<&str, f64> = BTreeMap::new();
+            for security in securities.iter() {
+                *by_asset_class.entry(&security.asset_class).or_default() +=
+                    security.get_total_current_value() as f64;
+            }
+            for (asset_class, value) in by_asset_class {
+                let returns = match assumptions.iter().find(|a| a.asset_class == asset_class) {
+                    Some(assumption) => {
+                        let log_sd = assumption.volatility as f64 / 100.0 / 12f64.sqrt();
+                        // The mean of the growth, not of its log, compounds to the return
+                        let log_mean = (1.0 + assumption.annual_return as f64 / 100.0).ln() / 12.0
+                            - log_sd * log_sd / 2.0;
+                        Returns::Normal { log_mean, log_sd }
+                    }
+                    None => Returns::Fixed,
+                };
+                buckets.push(Bucket {
+                    value,
+                    share: 0.0,
+                    returns,
+                });
+            }
+        }
+        ReturnModel::Bootstrap => {
+            let value: f32 = securities.iter().map(|s| s.get_total_current_value()).sum();
+            buckets.push(Bucket {
+                value: value as f64,
+                share: 0.0,
+                returns: Returns::History(history),
+            });
+        }
+    }
+    let total: f64 = buckets.iter().map(|bucket| bucket.value).sum();
+    let count = buckets.len() as f64;
+    for bucket in buckets.iter_mut() {
+        bucket.share = if total > 0.0 {
+            bucket.value / total
+        } else {
+            1.0 / count
+        };
+    }
+
+    let paths = options.paths.max(1) as usize;
+    let years = options.years as usize;
+    let contribution = options.monthly_contribution as f64;
+    let mut rng = Rng::new(options.seed);
+    // Values of every path at the end of every year
+    let mut by_year = vec![Vec::with_capacity(paths); years + 1];
+    for _ in 0..paths {
+        let mut values: Vec<f64> = buckets.iter().map(|bucket| bucket.value).collect();
+        by_year[0].push(values.iter().sum::<f64>());
+        for year_end in by_year.iter_mut().skip(1) {
+            for _ in 0..12 {
+                for (value, bucket) in values.iter_mut().zip(buckets.iter()) {
+                    let growth = match bucket.returns {
+                        Returns::Fixed => 1.0,
+                        Returns::Normal { log_mean, log_sd } => {
+                            (log_mean + log_sd * rng.normal()).exp()
+                        }
+                        Returns::History([]) => 1.0,
+                        Returns::History(months) => 1.0 + months[rng.below(months.len())],
+                    };
+                    *value = *value * growth + contribution * bucket.share;
+                }
+            }
+            year_end.push(values.iter().sum::<f64>());
+        }
+    }
+
+    by_year
+        .into_iter()
+        .enumerate()
+        .map(|(year, mut values)| {
+            values.sort_by(f64::total_cmp);
+            Band {
+                year: year as u32,
+                values: PERCENTILES.map(|p| percentile(&values, p) as f32),
+            }
+        })
+        .collect()
+}
+
+// Linear between the closest ranks of the sorted values
+fn percentile(sorted: &[f64], p: f32) -> f64 {
+    if sorted.is_empty() {
+        return 0.0;
+    }
+    let rank = p as f64 / 100.0 * (sorted.len() - 1) as f64;
+    let below = rank.floor() as usize;
+    let above = rank.ceil() as usize;
+    sorted[below] + (sorted[above] - sorted[below]) * (rank - below as f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::savings_plan::{Frequency, PlanSize, SavingsPlan};
+    use crate::security::Action;
+    use crate::security::test_support::{date, security};
+
+    // Ten units bought at the first price
+    fn holding(asset_class: &str, entries: &[(&str, f32)]) -> Security {
+        let (first_day, first_price) = entries[0];
+        let mut security = security(&[(Action::Buy, first_day, 10, first_price, 0.0, 0.0)]);
+        security.asset_class = asset_class.to_string();
+        for (day, price) in entries.iter() {
+            security.update_current_price(*price, day.to_string());
+        }
+        security.calculate_total_current_value();
+        security
+    }
+
+    fn assumption(asset_class: &str, annual_return: f32, volatility: f32) -> Assumption {
+        Assumption {
+            asset_class: asset_class.to_string(),
+            annual_return,
+            volatility,
+        }
+    }
+
+    #[test]
+    fn without_volatility_it_compounds() {
+        let securities = [holding("Equity", &[("2024-01-02", 100.0)])];
+        let options = Options {
+            years: 2,
+            paths: 10,
+            monthly_contribution: 0.0,
+            ..Options::default()
+        };
+        let bands = project(
+            &securities,
+            500.0,
+            &[assumption("Equity", 10.0, 0.0)],
+            &[],
+            &options,
+        );
+        assert_eq!(bands.len(), 3);
+        for band in bands.iter() {
+            let expected = 500.0 + 1000.0 * 1.1f32.powi(band.year as i32);
+            for value in band.values {
+                assert!((value - expected).abs() < 0.01, "got {:?}", band);
+            }
+        }
+    }
+
+    #[test]
+    fn contributions_add_up_without_growth() {
+        let options = Options {
+            years: 3,
+            paths: 5,
+            monthly_contribution: 100.0,
+            ..Options::default()
+        };
+        let bands = project(&[], 0.0, &[], &[], &options);
+        assert_eq!(bands[3].values, [3600.0; 5]);
+    }
+
+    #[test]
+    fn same_seed_same_bands() {
+        let securities = [holding("Equity", &[("2024-01-02", 100.0)])];
+        let assumptions = [assumption("Equity", 7.0, 15.0)];
+        let options = Options {
+            years: 5,
+            paths: 200,
+            seed: 42,
+            monthly_contribution: 50.0,
+            model: ReturnModel::Assumptions,
+        };
+        let bands = project(&securities, 0.0, &assumptions, &[], &options);
+        assert_eq!(
+            bands,
+            project(&securities, 0.0, &assumptions, &[], &options)
+        );
+        assert_ne!(
+            bands,
+            project(
+                &securities,
+                0.0,
+                &assumptions,
+                &[],
+                &Options {
+                    seed: 43,
+                    ..options
+                }
+            )
+        );
+        for band in bands.iter().skip(1) {
+            assert!(band.values.windows(2).all(|pair| pair[0] < pair[1]));
+        }
+    }
+
+    #[test]
+    fn bootstrap_draws_from_the_monthly_returns() {
+        let securities = [holding(
+            "",
+            &[
+                ("2024-01-02", 100.0),
+                ("2024-01-31", 100.0),
+                ("2024-02-29", 110.0),
+                ("2024-04-30", 99.0),
+                ("2024-05-31", 108.9),
+            ],
+        )];
+        // March has no price, so April doesn't count
+        let history = historical_returns(&securities);
+        assert_eq!(history.len(), 2);
+        assert!(history.iter().all(|r| (r - 0.1).abs() < 1e-6));
+
+        let options = Options {
+            years: 1,
+            paths: 3,
+            model: ReturnModel::Bootstrap,
+            ..Options::default()
+        };
+        let bands = project(&securities, 0.0, &[], &history, &options);
+        let expected = 1089.0 * 1.1f32.powi(12);
+        assert!(
+            (bands[1].values[2] - expected).abs() < 0.5,
+            "got {:?}",
+            bands[1]
+        );
+    }
+
+    #[test]
+    fn contributions_of_the_running_plans() {
+        let mut security = holding("Equity", &[("2024-01-02", 50.0)]);
+        security.savings_plans.push(SavingsPlan::new(
+            PlanSize::Quantity(6),
+            Frequency::Quarterly,
+            date(2024, 1, 1),
+            None,
+            1,
+        ));
+        security.savings_plans.push(SavingsPlan::new(
+            PlanSize::Amount(100.0),
+            Frequency::Monthly,
+            date(2024, 1, 1),
+            Some(date(2024, 6, 30)),
+            1,
+        ));
+        assert_eq!(
+            planned_contribution(std::slice::from_ref(&security), date(2024, 3, 1)),
+            200.0
+        );
+        assert_eq!(planned_contribution(&[security], date(2024, 7, 1)), 100.0);
+    }
+}
@@ -0,0 +1,423 @@
+use std::path::{Path, PathBuf};
+
+use chrono::NaiveDate;
+use rusqlite::{Connection, OptionalExtension, params};
+use serde_json::Value;
+
+use crate::Overview;
+use crate::crypto::{self, CryptoError};
+use crate::format::parse_date;
+use crate::report::Transaction;
+use crate::security::Entry;
+
+pub const SQLITE_EXTENSION: &str = "db";
+
+pub trait Storage {
+    fn load(&self) -> Result<Overview, StorageError>;
+    fn save(&mut self, overview: &Overview) -> Result<(), StorageError>;
+}
+
+#[derive(Debug)]
+pub enum StorageError {
+    Io(std::io::Error),
+    Json(serde_json::Error),
+    Crypto(CryptoError),
+    Sqlite(rusqlite::Error),
+}
+
+impl std::fmt::Display for StorageError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StorageError::Io(e) => write!(f, "{}", e),
+            StorageError::Json(e) => write!(f, "{}", e),
+            StorageError::Crypto(e) => write!(f, "{}", e),
+            StorageError::Sqlite(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl From<std::io::Error> for StorageError {
+    fn from(e: std::io::Error) -> Self {
+        StorageError::Io(e)
+    }
+}
+
+impl From<serde_json::Error> for StorageError {
+    fn from(e: serde_json::Error) -> Self {
+        StorageError::Json(e)
+    }
+}
+
+impl From<CryptoError> for StorageError {
+    fn from(e: CryptoError) -> Self {
+        StorageError::Crypto(e)
+    }
+}
+
+impl From<rusqlite::Error> for StorageError {
+    fn from(e: rusqlite::Error) -> Self {
+        StorageError::Sqlite(e)
+    }
+}
+
+pub fn is_sqlite(path: &Path) -> bool {
+    path.extension().is_some_and(|e| e == SQLITE_EXTENSION)
+}
+
+/// Picks the backend from the file extension, the password only applies to JSON files.
+pub fn open(path: &Path, password: Option<String>) -> Result<Box<dyn Storage>, StorageError> {
+    if is_sqlite(path) {
+        Ok(Box::new(SqliteStorage::open(path)?))
+    } else {
+        Ok(Box::new(JsonStorage {
+            path: path.to_path_buf(),
+            password,
+        }))
+    }
+}
+
+// Plain or encrypted pretty-printed JSON, rewritten on every save
+pub struct JsonStorage {
+    path: PathBuf,
+    password: Option<String>,
+}
+
+impl Storage for JsonStorage {
+    fn load(&self) -> Result<Overview, StorageError> {
+        let data = std::fs::read(&self.path)?;
+        let json = match &self.password {
+            Some(password) => crypto::decrypt(&data, password)?,
+            None => data,
+        };
+        Ok(serde_json::from_slice(&json)?)
+    }
+
+    fn save(&mut self, overview: &Overview) -> Result<(), StorageError> {
+        let json = serde_json::to_string_pretty(overview)?;
+        let data = match &self.password {
+            Some(password) => crypto::encrypt(json.as_bytes(), password)?,
+            None => json.into_bytes(),
+        };
+        std::fs::write(&self.path, data)?;
+        Ok(())
+    }
+}
+
+// Securities and the portfolio itself are small and stored as JSON rows,
+// entries and prices get their own tables keyed by security and indexed by
+// ISO date, so they can be queried by date range and saving only writes the
+// rows that changed.
+pub struct SqliteStorage {
+    connection: Connection,
+}
+
+impl SqliteStorage {
+    pub fn open(path: &Path) -> Result<Self, StorageError> {
+        Self::with_connection(Connection::open(path)?)
+    }
+
+    fn with_connection(connection: Connection) -> Result<Self, StorageError> {
+        connection.execute_batch(
+            "CREATE TABLE IF NOT EXISTS portfolio (
+                 id INTEGER PRIMARY KEY CHECK (id = 0),
+                 data TEXT NOT NULL
+             );
+             CREATE TABLE IF NOT EXISTS securities (
+                 id INTEGER PRIMARY KEY,
+                 data TEXT NOT NULL
+             );
+             CREATE TABLE IF NOT EXISTS entries (
+                 security_id INTEGER NOT NULL,
+                 seq INTEGER NOT NULL,
+                 date TEXT,
+                 data TEXT NOT NULL,
+                 PRIMARY KEY (security_id, seq)
+             );
+             CREATE INDEX IF NOT EXISTS entries_by_date ON entries (date);
+             CREATE TABLE IF NOT EXISTS prices (
+                 security_id INTEGER NOT NULL,
+                 date TEXT NOT NULL,
+                 data TEXT NOT NULL,
+                 PRIMARY KEY (security_id, date)
+             ) WITHOUT ROWID;",
+        )?;
+        Ok(Self { connection })
+    }
+
+    /// Entries of all securities between both dates (inclusive), using the date index.
+    pub fn transactions_between(
+        &self,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<Vec<Transaction>, StorageError> {
+        let mut statement = self.connection.prepare(
+            "SELECT json_extract(securities.data, '$.name'), entries.data
+             FROM entries JOIN securities ON securities.id = entries.security_id
+             WHERE entries.date BETWEEN ?1 AND ?2
+             ORDER BY entries.date, entries.security_id, entries.seq",
+        )?;
+        let rows = statement.query_map(params![iso(start), iso(end)], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        })?;
+
+        let mut transactions = Vec::new();
+        for row in rows {
+            let (security, data) = row?;
+            let entry: Entry = serde_json::from_str(&data)?;
+            let (action, date, quantity, price_per_unit) = entry.to_tuple();
+            transactions.push(Transaction {
+                date,
+                security,
+                action,
+                quantity,
+                price_per_unit,
+            });
+        }
+        Ok(transactions)
+    }
+
+    fn rows(&self, query: &str, security_id: i64) -> Result<Vec<Value>, StorageError> {
+        let mut statement = self.connection.prepare_cached(query)?;
+        let rows = statement.query_map([security_id], |row| row.get::<_, String>(0))?;
+        let mut values = Vec::new();
+        for row in rows {
+            values.push(serde_json::from_str(&row?)?);
+        }
+        Ok(values)
+    }
+}
+
+impl Storage for SqliteStorage {
+    fn load(&self) -> Result<Overview, StorageError> {
+        let mut portfolio: Value = self
+            .connection
+            .query_row("SELECT data FROM portfolio WHERE id = 0", [], |row| {
+                row.get::<_, String>(0)
+            })
+            .optional()?
+            .map(|data| serde_json::from_str(&data))
+            .transpose()?
+            .unwrap_or_else(|| serde_json::to_value(Overview::new()).unwrap_or_default());
+
+        let mut securities = Vec::new();
+        let mut statement = self
+            .connection
+            .prepare("SELECT id, data FROM securities ORDER BY id")?;
+        let rows = statement.query_map([], |row| {
+            Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?))
+        })?;
+        for row in rows {
+            let (id, data) = row?;
+            let mut security: Value = serde_json::from_str(&data)?;
+            security["entries"] = Value::Array(self.rows(
+                "SELECT data FROM entries WHERE security_id = ?1 ORDER BY seq",
+                id,
+            )?);
+            // Files written before prices had their own table keep them in the security row
+            let prices = self.rows(
+                "SELECT data FROM prices WHERE security_id = ?1 ORDER BY date",
+                id,
+            )?;
+            if !prices.is_empty() {
+                security["price_history"] = Value::Array(prices);
+            }
+            securities.push(security);
+        }
+        portfolio["securities"] = Value::Array(securities);
+
+        let mut overview: Overview = serde_json::from_value(portfolio)?;
+        overview.calculate_total_investment();
+        Ok(overview)
+    }
+
+    // Every row is upserted, but only written when its JSON differs from the stored one
+    fn save(&mut self, overview: &Overview) -> Result<(), StorageError> {
+        let transaction = self.connection.transaction()?;
+
+        let mut portfolio = serde_json::to_value(overview)?;
+        let securities = take_array(&mut portfolio, "securities");
+        transaction.execute(
+            "INSERT INTO portfolio (id, data) VALUES (0, ?1)
+             ON CONFLICT (id) DO UPDATE SET data = excluded.data
+             WHERE data <> excluded.data",
+            [portfolio.to_string()],
+        )?;
+
+        let mut ids = Vec::new();
+        for mut security in securities {
+            let id = security["id"].as_i64().unwrap_or_default();
+            let entries = take_array(&mut security, "entries");
+            let prices = take_array(&mut security, "price_history");
+            transaction.execute(
+                "INSERT INTO securities (id, data) VALUES (?1, ?2)
+                 ON CONFLICT (id) DO UPDATE SET data = excluded.data
+                 WHERE data <> excluded.data",
+                params![id, security.to_string()],
+            )?;
+
+            for (seq, entry) in entries.iter().enumerate() {
+                let date = entry["date"].as_str().and_then(parse_date).map(iso);
+                transaction.execute(
+                    "INSERT INTO entries (security_id, seq, date, data) VALUES (?1, ?2, ?3, ?4)
+                     ON CONFLICT (security_id, seq) DO UPDATE
+                     SET date = excluded.date, data = excluded.data
+                     WHERE data <> excluded.data",
+                    params![id, seq as i64, date, entry.to_string()],
+                )?;
+            }
+            transaction.execute(
+                "DELETE FROM entries WHERE security_id = ?1 AND seq >= ?2",
+                params![id, entries.len() as i64],
+            )?;
+
+            let mut dates = Vec::new();
+            for price in prices.iter() {
+                let Some(date) = price["date"].as_str() else {
+                    continue;
+                };
+                transaction.execute(
+                    "INSERT INTO prices (security_id, date, data) VALUES (?1, ?2, ?3)
+                     ON CONFLICT (security_id, date) DO UPDATE SET data = excluded.data
+                     WHERE data <> excluded.data",
+                    params![id, date, price.to_string()],
+                )?;
+                dates.push(date.to_string());
+            }
+            transaction.execute(
+                "DELETE FROM prices WHERE security_id = ?1
+                 AND date NOT IN (SELECT value FROM json_each(?2))",
+                params![id, Value::from(dates).to_string()],
+            )?;
+            ids.push(id);
+        }
+
+        // Securities removed from the portfolio
+        let ids = Value::from(ids).to_string();
+        for table in ["entries", "prices"] {
+            transaction.execute(
+                &format!(
+                    "DELETE FROM {table} WHERE security_id NOT IN (SELECT value FROM json_each(?1))"
+                ),
+                [&ids],
+            )?;
+        }
+        transaction.execute(
+            "DELETE FROM securities WHERE id NOT IN (SELECT value FROM json_each(?1))",
+            [&ids],
+        )?;
+
+        transaction.commit()?;
+        Ok(())
+    }
+}
+
+fn take_array(value: &mut Value, key: &str) -> Vec<Value> {
+    match value.as_object_mut().and_then(|object| object.remove(key)) {
+        Some(Value::Array(values)) => values,
+        _ => Vec::new(),
+    }
+}
+
+fn iso(date: NaiveDate) -> String {
+    date.format("%Y-%m-%d").to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::security::{Action, Security};
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    fn overview() -> Overview {
+        let mut etf = Security::new(0, "ETF".to_string(), 0);
+        etf.add_entry(Action::Buy, "2024-01-10".to_string(), 10, 100.0, 1.0, 0.0)
+            .unwrap();
+        etf.update_current_price(110.0, "2024-02-01".to_string());
+        etf.add_entry(Action::Sell, "2024-05-02".to_string(), 4, 120.0, 1.0, 0.0)
+            .unwrap();
+        etf.update_current_price(125.0, "2024-06-03".to_string());
+        let mut bond = Security::new(1, "Bond".to_string(), 1);
+        bond.add_entry(Action::Buy, "2024-03-15".to_string(), 5, 90.0, 0.0, 0.0)
+            .unwrap();
+        bond.update_current_price(92.0, "2024-03-29".to_string());
+        let mut overview = Overview::new();
+        overview.securities.push(etf);
+        overview.securities.push(bond);
+        overview.calculate_total_investment();
+        overview
+    }
+
+    fn sqlite() -> SqliteStorage {
+        SqliteStorage::with_connection(Connection::open_in_memory().unwrap()).unwrap()
+    }
+
+    #[test]
+    fn json_and_sqlite_hold_the_same_portfolio() {
+        let path = std::env::temp_dir().join(format!("portfolio-{}.json", std::process::id()));
+        let mut json = JsonStorage {
+            path: path.clone(),
+            password: None,
+        };
+        json.save(&overview()).unwrap();
+        let from_json = json.load().unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let mut sqlite = sqlite();
+        sqlite.save(&from_json).unwrap();
+        let from_sqlite = sqlite.load().unwrap();
+        assert_eq!(
+            serde_json::to_value(&from_sqlite).unwrap(),
+            serde_json::to_value(&from_json).unwrap()
+        );
+
+        let prices: i64 = sqlite
+            .connection
+            .query_row("SELECT COUNT(*) FROM prices", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(prices, 3);
+    }
+
+    #[test]
+    fn only_changed_rows_are_written() {
+        let mut storage = sqlite();
+        let mut overview = overview();
+        storage.save(&overview).unwrap();
+        let written = storage.connection.total_changes();
+        storage.save(&overview).unwrap();
+        assert_eq!(storage.connection.total_changes(), written);
+
+        overview.securities[1].update_current_price(93.0, "2024-04-02".to_string());
+        storage.save(&overview).unwrap();
+        // The new price and the bond's current value
+        assert_eq!(storage.connection.total_changes(), written + 2);
+        assert_eq!(
+            serde_json::to_value(storage.load().unwrap()).unwrap(),
+            serde_json::to_value(&overview).unwrap()
+        );
+    }
+
+    #[test]
+    fn transactions_within_the_dates() {
+        let mut storage = sqlite();
+        storage.save(&overview()).unwrap();
+
+        let transactions = storage
+            .transactions_between(date(2024, 3, 15), date(2024, 5, 2))
+            .unwrap();
+        let found: Vec<_> = transactions
+            .iter()
+            .map(|t| (t.date.as_str(), t.security.as_str(), t.quantity))
+            .collect();
+        assert_eq!(found, [("2024-03-15", "Bond", 5), ("2024-05-02", "ETF", 4)]);
+
+        assert!(
+            storage
+                .transactions_between(date(2024, 6, 1), date(2024, 12, 31))
+                .unwrap()
+                .is_empty()
+        );
+    }
+}
@@ -0,0 +1,240 @@
+use chrono::{Datelike, NaiveDate};
+
+use crate::format::parse_date;
+use crate::i18n::Text;
+use crate::security::{CostBasisMethod, Disposal, DisposedLot, Security};
+use crate::wash_sale::{self, WashSaleRule};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Term {
+    Short,
+    Long,
+}
+
+impl Term {
+    pub fn label(self) -> Text {
+        match self {
+            Term::Short => Text::ShortTerm,
+            Term::Long => Text::LongTerm,
+        }
+    }
+}
+
+/// One line of the capital gains report, a sale or the part of it with the same term.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CapitalGain {
+    pub security: String,
+    pub acquired: Vec<NaiveDate>,
+    pub disposed: NaiveDate,
+    pub quantity: f32,
+    pub proceeds: f32,
+    pub cost_basis: f32,
+    pub fees: f32,
+    pub disallowed: f32, // wash sale loss that can't be deducted
+    pub term: Term,
+}
+
+impl CapitalGain {
+    pub fn gain(&self) -> f32 {
+        self.proceeds - self.cost_basis - self.fees + self.disallowed
+    }
+}
+
+/// Sales within the calendar year, sorted by date. Units held for more than
+/// `long_term_days` are long-term, units bought on an unknown day short-term.
+/// Losses and costs follow the wash sale rule.
+pub fn capital_gains(
+    securities: &[Security],
+    method: CostBasisMethod,
+    year: i32,
+    long_term_days: u32,
+    wash_sale_days: u32,
+    wash_sale_rule: WashSaleRule,
+) -> Vec<CapitalGain> {
+    let mut gains: Vec<CapitalGain> = securities
+        .iter()
+        .flat_map(|security| {
+            wash_sale::adjusted_lots(security, method, wash_sale_days, wash_sale_rule)
+                .1
+                .into_iter()
+                .filter_map(|disposal| {
+                    let disposed = parse_date(&disposal.date).filter(|day| day.year() == year)?;
+                    Some(split_by_term(
+                        &security.name,
+                        &disposal,
+                        disposed,
+                        long_term_days,
+                    ))
+                })
+                .flatten()
+                .collect::<Vec<_>>()
+        })
+        .collect();
+    gains.sort_by_key(|gain| gain.disposed);
+    gains
+}
+
+/// Total gain of the short-term and of the long-term lines.
+pub fn totals(gains: &[CapitalGain]) -> (f32, f32) {
+    let total = |term: Term| {
+        gains
+            .iter()
+            .filter(|gain| gain.term == term)
+            .map(CapitalGain::gain)
+            .sum()
+    };
+    (total(Term::Short), total(Term::Long))
+}
+
+fn term_of(lot: &DisposedLot, disposed: NaiveDate, long_term_days: u32) -> Term {
+    match lot.acquired {
+        Some(acquired) if (disposed - acquired).num_days() > long_term_days as i64 => Term::Long,
+        _ => Term::Short,
+    }
+}
+
+// A sale that took units of both terms is reported as two lines, with the
+// proceeds and fees shared by quantity
+fn split_by_term(
+    security: &str,
+    disposal: &Disposal,
+    disposed: NaiveDate,
+    long_term_days: u32,
+) -> Vec<CapitalGain> {
+    [Term::Short, Term::Long]
+        .into_iter()
+        .filter_map(|term| {
+            let lots: Vec<&DisposedLot> = disposal
+                .lots
+                .iter()
+                .filter(|lot| term_of(lot, disposed, long_term_days) == term)
+                .collect();
+            let quantity: f32 = lots.iter().map(|lot| lot.quantity).sum();
+            if quantity <= 0.0 {
+                return None;
+            }
+            let share = quantity / disposal.quantity;
+            let mut acquired: Vec<NaiveDate> = lots.iter().filter_map(|lot| lot.acquired).collect();
+            acquired.sort();
+            acquired.dedup();
+            Some(CapitalGain {
+                security: security.to_string(),
+                acquired,
+                disposed,
+                quantity,
+                proceeds: disposal.proceeds * share,
+                cost_basis: lots.iter().map(|lot| lot.cost).sum(),
+                fees: disposal.fees * share,
+                disallowed: disposal.disallowed * share,
+                term,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::security::Action;
+    use crate::security::test_support::{date, security};
+
+    #[test]
+    fn fees_are_part_of_the_gain() {
+        let security = security(&[
+            (Action::Buy, "2023-01-10", 10, 10.0, 5.0, 0.0),
+            (Action::Sell, "2023-06-01", 10, 12.0, 3.0, 0.0),
+        ]);
+        let gains = capital_gains(
+            &[security],
+            CostBasisMethod::Fifo,
+            2023,
+            365,
+            30,
+            WashSaleRule::Defer,
+        );
+        assert_eq!(gains.len(), 1);
+        assert_eq!(gains[0].cost_basis, 105.0);
+        assert_eq!(gains[0].fees, 3.0);
+        assert_eq!(gains[0].gain(), 12.0);
+        assert_eq!(gains[0].term, Term::Short);
+        assert_eq!(gains[0].acquired, vec![date(2023, 1, 10)]);
+    }
+
+    #[test]
+    fn sale_across_terms_is_split() {
+        let security = security(&[
+            (Action::Buy, "2022-01-10", 4, 10.0, 0.0, 0.0),
+            (Action::Buy, "2023-03-01", 6, 20.0, 0.0, 0.0),
+            (Action::Sell, "2023-06-01", 5, 30.0, 10.0, 0.0),
+        ]);
+        let gains = capital_gains(
+            &[security],
+            CostBasisMethod::Fifo,
+            2023,
+            365,
+            30,
+            WashSaleRule::Defer,
+        );
+        assert_eq!(gains.len(), 2);
+        assert_eq!(gains[0].term, Term::Short);
+        assert_eq!(gains[0].quantity, 1.0);
+        assert_eq!(gains[0].acquired, vec![date(2023, 3, 1)]);
+        assert_eq!(gains[0].proceeds, 30.0);
+        assert_eq!(gains[0].fees, 2.0);
+        assert_eq!(gains[1].term, Term::Long);
+        assert_eq!(gains[1].quantity, 4.0);
+        assert_eq!(gains[1].cost_basis, 40.0);
+        assert_eq!(totals(&gains), (8.0, 72.0));
+    }
+
+    #[test]
+    fn only_sales_in_the_year_count() {
+        let security = security(&[
+            (Action::Buy, "2021-01-10", 10, 10.0, 0.0, 0.0),
+            (Action::Sell, "2022-12-31", 5, 11.0, 0.0, 0.0),
+            (Action::Sell, "2023-01-01", 5, 12.0, 0.0, 0.0),
+        ]);
+        let gains = capital_gains(
+            &[security],
+            CostBasisMethod::Fifo,
+            2022,
+            365,
+            30,
+            WashSaleRule::Defer,
+        );
+        assert_eq!(gains.len(), 1);
+        assert_eq!(gains[0].disposed, date(2022, 12, 31));
+        assert_eq!(gains[0].term, Term::Long);
+    }
+
+    #[test]
+    fn wash_sales_move_the_loss_to_the_replacement() {
+        let entries = [
+            (Action::Buy, "2024-01-02", 10, 100.0, 0.0, 0.0),
+            (Action::Sell, "2024-03-01", 10, 80.0, 0.0, 0.0),
+            (Action::Buy, "2024-03-20", 10, 82.0, 0.0, 0.0),
+            (Action::Sell, "2024-09-02", 10, 90.0, 0.0, 0.0),
+        ];
+        let gains = |rule: WashSaleRule| {
+            capital_gains(
+                &[security(&entries)],
+                CostBasisMethod::Fifo,
+                2024,
+                365,
+                30,
+                rule,
+            )
+        };
+
+        let deferred = gains(WashSaleRule::Defer);
+        assert_eq!(deferred[0].disallowed, 200.0);
+        assert_eq!(deferred[0].gain(), 0.0);
+        assert_eq!(deferred[1].cost_basis, 1020.0);
+        assert_eq!(deferred[1].gain(), -120.0);
+
+        let disallowed = gains(WashSaleRule::Disallow);
+        assert_eq!(disallowed[0].gain(), 0.0);
+        assert_eq!(disallowed[1].cost_basis, 820.0);
+        assert_eq!(disallowed[1].gain(), 80.0);
+    }
+}
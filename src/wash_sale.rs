@@ -0,0 +1,363 @@
+use chrono::{Duration, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+use crate::format::parse_date;
+use crate::i18n::Text;
+use crate::security::{Action, CostBasisMethod, Disposal, Lot, Security};
+
+/// What happens to a loss that is disallowed by a repurchase.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WashSaleRule {
+    #[default]
+    Defer, // added to the cost of the replacement units
+    Disallow, // lost for good
+}
+
+impl WashSaleRule {
+    pub const ALL: [WashSaleRule; 2] = [WashSaleRule::Defer, WashSaleRule::Disallow];
+
+    pub fn label(&self) -> Text {
+        match self {
+            WashSaleRule::Defer => Text::AddToReplacementCost,
+            WashSaleRule::Disallow => Text::DisallowLoss,
+        }
+    }
+}
+
+/// Units bought around a sale at a loss that stand in for the units sold.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Replacement {
+    pub date: NaiveDate,
+    pub quantity: f32,
+    pub basis_adjustment: f32, // added to the cost of these units
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct WashSale {
+    pub security_id: u8,
+    pub date: NaiveDate,
+    pub quantity: f32,
+    pub loss: f32,       // positive
+    pub disallowed: f32, // the part of the loss covered by replacements
+    pub replacements: Vec<Replacement>,
+}
+
+/// A position that could be sold to realize a loss.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Harvest {
+    pub security_id: u8,
+    pub quantity: f32,
+    pub cost_basis: f32, // with the deferred losses of replacement units still held
+    pub value: f32,
+    pub recent_purchase: bool, // selling now would itself be a wash sale
+}
+
+impl Harvest {
+    pub fn loss(&self) -> f32 {
+        self.cost_basis - self.value
+    }
+}
+
+/// Sales at a loss with purchases of the same security up to `window_days`
+/// before or after them. Every purchase replaces units of one sale at most,
+/// units bought and sold in the same sale don't count as replacements.
+pub fn wash_sales(
+    security: &Security,
+    method: CostBasisMethod,
+    window_days: u32,
+    rule: WashSaleRule,
+) -> Vec<WashSale> {
+    settle(security, method, window_days, rule).0
+}
+
+/// Open lots and sales as the tax rules see them, the disallowed part of a
+/// loss isn't deductible and a deferred loss is part of the replacement's cost.
+pub fn adjusted_lots(
+    security: &Security,
+    method: CostBasisMethod,
+    window_days: u32,
+    rule: WashSaleRule,
+) -> (Vec<Lot>, Vec<Disposal>) {
+    let (_, lots, disposals) = settle(security, method, window_days, rule);
+    (lots, disposals)
+}
+
+// A deferred loss raises the cost of the replacement units, which can make their own
+// sale a larger loss, so matching repeats until the adjustments stop changing. Every
+// round settles at least one more sale of a chain, there are never more rounds than sales.
+fn settle(
+    security: &Security,
+    method: CostBasisMethod,
+    window_days: u32,
+    rule: WashSaleRule,
+) -> (Vec<WashSale>, Vec<Lot>, Vec<Disposal>) {
+    let mut replacements: Vec<Replacement> = Vec::new();
+    let mut rounds = 0;
+    loop {
+        let (lots, mut disposals) = security.get_lots_with(method, &replacements);
+        let sales = match_sales(security, &disposals, window_days, rule);
+        let next: Vec<Replacement> = sales
+            .iter()
+            .flat_map(|(_, sale)| sale.replacements.iter().cloned())
+            .collect();
+        rounds += 1;
+        if next == replacements || rounds > disposals.len() {
+            for (index, sale) in sales.iter() {
+                disposals[*index].disallowed = sale.disallowed;
+            }
+            let sales = sales.into_iter().map(|(_, sale)| sale).collect();
+            return (sales, lots, disposals);
+        }
+        replacements = next;
+    }
+}
+
+// Wash sales with the index of their disposal
+fn match_sales(
+    security: &Security,
+    disposals: &[Disposal],
+    window_days: u32,
+    rule: WashSaleRule,
+) -> Vec<(usize, WashSale)> {
+    let mut buys: Vec<(NaiveDate, f32)> = security
+        .get_entries()
+        .into_iter()
+        .filter(|(action, _, _, _)| *action == Action::Buy)
+        .filter_map(|(_, date, quantity, _)| parse_date(&date).map(|day| (day, quantity as f32)))
+        .collect();
+    buys.sort_by_key(|(day, _)| *day);
+    let mut used = vec![0.0; buys.len()];
+
+    let mut sales = Vec::new();
+    for (index, disposal) in disposals.iter().enumerate() {
+        let loss = -disposal.gain();
+        let Some(date) = parse_date(&disposal.date) else {
+            continue;
+        };
+        if loss <= 0.0 || disposal.quantity <= 0.0 {
+            continue;
+        }
+        let mut remaining = disposal.quantity;
+        let mut replacements = Vec::new();
+        for (i, (day, quantity)) in buys.iter().enumerate() {
+            if remaining <= 0.0 {
+                break;
+            }
+            if (*day - date).num_days().abs() > window_days as i64 {
+                continue;
+            }
+            let available = quantity - used[i] - sold_from(disposal, *day);
+            if available <= 0.0 {
+                continue;
+            }
+            let matched = available.min(remaining);
+            used[i] += matched;
+            remaining -= matched;
+            replacements.push(Replacement {
+                date: *day,
+                quantity: matched,
+                basis_adjustment: 0.0,
+            });
+        }
+        if replacements.is_empty() {
+            continue;
+        }
+
+        let loss_per_unit = loss / disposal.quantity;
+        for replacement in replacements.iter_mut() {
+            replacement.basis_adjustment = match rule {
+                WashSaleRule::Defer => loss_per_unit * replacement.quantity,
+                WashSaleRule::Disallow => 0.0,
+            };
+        }
+        sales.push((
+            index,
+            WashSale {
+                security_id: security.id,
+                date,
+                quantity: disposal.quantity,
+                loss,
+                disallowed: loss_per_unit * (disposal.quantity - remaining),
+                replacements,
+            },
+        ));
+    }
+    sales
+}
+
+/// Positions worth less than they cost, the largest loss first.
+pub fn harvestable(
+    securities: &[Security],
+    method: CostBasisMethod,
+    window_days: u32,
+    rule: WashSaleRule,
+    today: NaiveDate,
+) -> Vec<Harvest> {
+    let mut harvests: Vec<Harvest> = securities
+        .iter()
+        .filter_map(|security| {
+            // The cost of replacement units still held includes their deferred losses
+            let (lots, _) = adjusted_lots(security, method, window_days, rule);
+            let quantity: f32 = lots.iter().map(|lot| lot.quantity).sum();
+            if quantity <= 0.0 {
+                return None;
+            }
+            let cost_basis: f32 = lots
+                .iter()
+                .map(|lot| lot.quantity * lot.price_per_unit)
+                .sum();
+            let value = quantity * security.get_current_price_per_unit();
+            let since = today - Duration::days(window_days as i64);
+            let recent_purchase = lots
+                .iter()
+                .any(|lot| lot.acquired.is_some_and(|day| day > since && day <= today));
+            (value < cost_basis).then_some(Harvest {
+                security_id: security.id,
+                quantity,
+                cost_basis,
+                value,
+                recent_purchase,
+            })
+        })
+        .collect();
+    harvests.sort_by(|a, b| b.loss().total_cmp(&a.loss()));
+    harvests
+}
+
+// Units of the sale that came from the purchase on that day
+fn sold_from(disposal: &Disposal, day: NaiveDate) -> f32 {
+    disposal
+        .lots
+        .iter()
+        .filter(|lot| lot.acquired == Some(day))
+        .map(|lot| lot.quantity)
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::security::test_support::{date, security};
+
+    fn priced(entries: &[(Action, &str, u8, f32, f32, f32)], price: f32) -> Security {
+        let mut security = security(entries);
+        security.update_current_price(price, "2024-12-31".to_string());
+        security.calculate_total_current_value();
+        security
+    }
+
+    #[test]
+    fn repurchase_within_the_window_is_a_wash_sale() {
+        let security = priced(
+            &[
+                (Action::Buy, "2024-01-02", 10, 100.0, 0.0, 0.0),
+                (Action::Sell, "2024-03-01", 10, 80.0, 0.0, 0.0),
+                (Action::Buy, "2024-03-20", 4, 82.0, 0.0, 0.0),
+            ],
+            82.0,
+        );
+        let sales = wash_sales(&security, CostBasisMethod::Fifo, 30, WashSaleRule::Defer);
+        assert_eq!(sales.len(), 1);
+        assert_eq!(sales[0].loss, 200.0);
+        assert_eq!(sales[0].disallowed, 80.0);
+        assert_eq!(
+            sales[0].replacements,
+            vec![Replacement {
+                date: date(2024, 3, 20),
+                quantity: 4.0,
+                basis_adjustment: 80.0,
+            }]
+        );
+
+        let sales = wash_sales(&security, CostBasisMethod::Fifo, 10, WashSaleRule::Defer);
+        assert!(sales.is_empty());
+    }
+
+    #[test]
+    fn units_sold_are_not_their_own_replacement() {
+        // Bought and sold within the window, but nothing else was bought
+        let security = priced(
+            &[
+                (Action::Buy, "2024-02-20", 5, 100.0, 0.0, 0.0),
+                (Action::Sell, "2024-03-01", 5, 90.0, 0.0, 0.0),
+            ],
+            90.0,
+        );
+        let sales = wash_sales(&security, CostBasisMethod::Fifo, 30, WashSaleRule::Defer);
+        assert!(sales.is_empty());
+    }
+
+    #[test]
+    fn disallowed_losses_are_not_added_to_the_cost() {
+        let security = priced(
+            &[
+                (Action::Buy, "2024-01-02", 10, 100.0, 0.0, 0.0),
+                (Action::Sell, "2024-03-01", 10, 80.0, 0.0, 0.0),
+                (Action::Buy, "2024-02-15", 10, 90.0, 0.0, 0.0),
+            ],
+            85.0,
+        );
+        let sales = wash_sales(&security, CostBasisMethod::Fifo, 30, WashSaleRule::Disallow);
+        assert_eq!(sales.len(), 1);
+        assert_eq!(sales[0].disallowed, 200.0);
+        assert_eq!(sales[0].replacements[0].basis_adjustment, 0.0);
+    }
+
+    #[test]
+    fn harvestable_losses_include_deferred_losses() {
+        let security = priced(
+            &[
+                (Action::Buy, "2024-01-02", 10, 100.0, 0.0, 0.0),
+                (Action::Sell, "2024-03-01", 10, 80.0, 0.0, 0.0),
+                (Action::Buy, "2024-03-20", 10, 82.0, 0.0, 0.0),
+            ],
+            75.0,
+        );
+        let harvests = harvestable(
+            std::slice::from_ref(&security),
+            CostBasisMethod::Fifo,
+            30,
+            WashSaleRule::Defer,
+            date(2024, 12, 31),
+        );
+        assert_eq!(harvests.len(), 1);
+        assert_eq!(harvests[0].cost_basis, 1020.0);
+        assert_eq!(harvests[0].loss(), 270.0);
+        assert!(!harvests[0].recent_purchase);
+
+        // Five days after the repurchase selling again would be a wash sale
+        let harvests = harvestable(
+            &[security],
+            CostBasisMethod::Fifo,
+            30,
+            WashSaleRule::Defer,
+            date(2024, 3, 25),
+        );
+        assert!(harvests[0].recent_purchase);
+    }
+
+    #[test]
+    fn deferred_losses_carry_on_to_the_next_replacement() {
+        // The second sale is only at a loss once the first loss is part of its cost
+        let security = priced(
+            &[
+                (Action::Buy, "2024-01-02", 10, 100.0, 0.0, 0.0),
+                (Action::Sell, "2024-03-01", 10, 80.0, 0.0, 0.0),
+                (Action::Buy, "2024-03-20", 10, 82.0, 0.0, 0.0),
+                (Action::Sell, "2024-04-01", 10, 85.0, 0.0, 0.0),
+                (Action::Buy, "2024-04-15", 10, 84.0, 0.0, 0.0),
+            ],
+            84.0,
+        );
+        let sales = wash_sales(&security, CostBasisMethod::Fifo, 30, WashSaleRule::Defer);
+        assert_eq!(sales.len(), 2);
+        assert_eq!(sales[1].loss, 170.0);
+        assert_eq!(sales[1].replacements[0].date, date(2024, 4, 15));
+
+        let (lots, disposals) =
+            adjusted_lots(&security, CostBasisMethod::Fifo, 30, WashSaleRule::Defer);
+        assert_eq!(lots[0].price_per_unit, 101.0);
+        assert!(disposals.iter().all(|disposal| disposal.gain() == 0.0));
+    }
+}